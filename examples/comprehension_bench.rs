@@ -0,0 +1,137 @@
+//! Sentence-comprehension benchmark on a synthetic book-length input: the old
+//! per-sentence scan of every term against the single pass over the
+//! sentence → term index the pipeline now uses. Both must agree.
+//!
+//! Usage:
+//!   cargo run --release --example comprehension_bench              # 20k sentences
+//!   cargo run --release --example comprehension_bench -- 60000     # a longer book
+
+use std::{
+    collections::HashMap,
+    time::Instant,
+};
+
+use yomine::{
+    anki::comprehensibility::calculate_sentence_comprehensions,
+    core::{
+        models::Sentence,
+        ComprehensionModel,
+        Term,
+    },
+    segmentation::word::POS,
+};
+
+/// Words per sentence, and distinct terms per sentence of text (a novel's
+/// vocabulary grows far slower than its length).
+const WORDS_PER_SENTENCE: usize = 12;
+const TERMS_PER_SENTENCE: f64 = 0.6;
+
+/// Deterministic xorshift, so runs are comparable.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Zipf-like pick in `0..n`: common words recur, most words are rare.
+    fn zipf(&mut self, n: usize) -> usize {
+        let u = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        ((n as f64).powf(u) as usize).saturating_sub(1).min(n - 1)
+    }
+}
+
+fn book(sentence_count: usize) -> (Vec<Sentence>, Vec<Term>) {
+    let term_count = ((sentence_count as f64 * TERMS_PER_SENTENCE) as usize).max(1);
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut terms: Vec<Term> = (0..term_count)
+        .map(|idx| Term {
+            id: idx as u32,
+            lemma_form: format!("語{idx}"),
+            lemma_reading: String::new(),
+            surface_form: format!("語{idx}"),
+            surface_reading: String::new(),
+            is_kana: false,
+            part_of_speech: POS::Noun,
+            frequencies: HashMap::new(),
+            full_segment: String::new(),
+            full_segment_reading: String::new(),
+            sentence_references: Vec::new(),
+            comprehension: (rng.next() % 101) as f32 / 100.0,
+            jlpt_level: None,
+            priority: 0.0,
+        })
+        .collect();
+
+    let sentences = (0..sentence_count)
+        .map(|id| {
+            for position in 0..WORDS_PER_SENTENCE {
+                let idx = rng.zipf(term_count);
+                terms[idx].sentence_references.push((id, position * 3));
+            }
+            Sentence {
+                id,
+                source_id: 0,
+                text: String::new(),
+                segments: Vec::new(),
+                timestamp: None,
+                page: None,
+                comprehension: 0.0,
+                ruby: Vec::new(),
+                translation: None,
+                speaker: None,
+            }
+        })
+        .collect();
+    (sentences, terms)
+}
+
+/// The previous implementation: every sentence filters the whole term list.
+fn scan_each_sentence(sentences: &mut [Sentence], terms: &[Term]) {
+    for sentence in sentences {
+        let sentence_terms: Vec<&Term> = terms
+            .iter()
+            .filter(|t| t.sentence_references.iter().any(|(sid, _)| *sid == sentence.id))
+            .collect();
+        sentence.comprehension = if sentence_terms.is_empty() {
+            0.0
+        } else {
+            sentence_terms.iter().map(|t| t.comprehension).sum::<f32>()
+                / sentence_terms.len() as f32
+        };
+    }
+}
+
+fn main() {
+    let sentence_count: usize =
+        std::env::args().nth(1).and_then(|a| a.parse().ok()).unwrap_or(20_000);
+    let (sentences, terms) = book(sentence_count);
+    println!("{} sentences, {} terms", sentences.len(), terms.len());
+
+    let mut scanned = sentences.clone();
+    let start = Instant::now();
+    scan_each_sentence(&mut scanned, &terms);
+    let scan_time = start.elapsed();
+
+    let mut indexed = sentences;
+    let start = Instant::now();
+    calculate_sentence_comprehensions(&mut indexed, &terms, ComprehensionModel::Average);
+    let index_time = start.elapsed();
+
+    let mismatches = scanned
+        .iter()
+        .zip(&indexed)
+        .filter(|(a, b)| (a.comprehension - b.comprehension).abs() > 1e-5)
+        .count();
+    assert_eq!(mismatches, 0, "index and scan disagree on {mismatches} sentences");
+
+    println!("per-sentence scan: {:>10.1} ms", scan_time.as_secs_f64() * 1000.0);
+    println!("sentence index:    {:>10.1} ms", index_time.as_secs_f64() * 1000.0);
+    println!(
+        "speed-up:          {:>10.1}x",
+        scan_time.as_secs_f64() / index_time.as_secs_f64().max(1e-9)
+    );
+}
@@ -55,7 +55,11 @@ fn main() {
             text: text.clone(),
             segments: Vec::new(),
             timestamp: None,
+            page: None,
             comprehension: 0.0,
+            ruby: Vec::new(),
+            translation: None,
+            speaker: None,
         }];
         let terms = extract_words(tokenizer.new_worker(), &mut sentences, &manager);
 
@@ -30,6 +30,7 @@ use crate::{
         names,
         AnkiStatus,
         ErrorPayload,
+        MineQueueFlushed,
     },
     player_task::PlayerHandle,
     state::AppState,
@@ -54,20 +55,47 @@ pub fn spawn(app: AppHandle) {
 }
 
 /// Anki connectivity probe: emit `anki-status` only on change (initial poll
-/// fires at t=0).
+/// fires at t=0). While Anki is up, mines queued offline are flushed to it.
 async fn poll_anki(app: AppHandle) {
-    let mut last_connected: Option<bool> = None;
+    let mut last: Option<AnkiStatus> = None;
     let mut tick = tokio::time::interval(POLL_INTERVAL);
 
     loop {
         tick.tick().await;
 
-        let connected = anki::api::get_version().await.is_ok();
-        if last_connected != Some(connected) {
-            let _ = app.emit(names::ANKI_STATUS, AnkiStatus { connected, fetching: false });
-            last_connected = Some(connected);
+        let connection = {
+            let state = app.state::<Mutex<AppState>>();
+            let guard = state.lock().unwrap();
+            guard.settings.anki_connection()
+        };
+        let connected = anki::api::get_version(&connection).await.is_ok();
+        if connected && anki::mine_queue::queued_count() > 0 {
+            flush_mine_queue(&app, &connection).await;
         }
+        let status = AnkiStatus::new(connected, false);
+        if last.as_ref() != Some(&status) {
+            let _ = app.emit(names::ANKI_STATUS, status.clone());
+            last = Some(status);
+        }
+    }
+}
+
+async fn flush_mine_queue(app: &AppHandle, connection: &anki::api::AnkiConnection) {
+    let report = anki::mine_queue::flush_queue(connection).await;
+    if report.added.is_empty() && report.duplicates.is_empty() && report.rejected.is_empty() {
+        return;
     }
+    let rejected =
+        report.rejected.into_iter().map(|(term, err)| format!("「{term}」: {err}")).collect();
+    let _ = app.emit(
+        names::MINE_QUEUE_FLUSHED,
+        MineQueueFlushed {
+            added: report.added,
+            duplicates: report.duplicates,
+            rejected,
+            remaining: report.remaining,
+        },
+    );
 }
 
 /// yomitan-api reachability probe: emit `yomitan-status` on change.
@@ -99,16 +127,23 @@ pub(crate) async fn refresh_knowledge_summary(app: &AppHandle) {
         let state = app.state::<Mutex<AppState>>();
         let guard = state.lock().unwrap();
         guard.language_tools.as_ref().map(|tools| {
-            (tools.frequency_manager.clone(), tools.known_interval, guard.knowledge_dirty.clone())
+            (
+                tools.frequency_manager.clone(),
+                tools.known_interval,
+                tools.suspended_cards,
+                guard.knowledge_dirty.clone(),
+            )
         })
     };
-    let Some((frequency_manager, known_interval, dirty)) = pending else { return };
+    let Some((frequency_manager, known_interval, suspended_cards, dirty)) = pending else {
+        return;
+    };
     // Reads the offline Anki vocab cache; only meaningful once it exists.
     if !anki::has_cached_vocab() {
         return;
     }
     if let Ok(summary) = tauri::async_runtime::spawn_blocking(move || {
-        compute_knowledge_summary(frequency_manager, known_interval)
+        compute_knowledge_summary(frequency_manager, known_interval, suspended_cards)
     })
     .await
     {
@@ -76,7 +76,7 @@ fn build_preview(result: &FrequencyAnalysisResult) -> AnalysisPreview {
     // (lowest-frequency terms, still desc order).]` for the Bottom 250 radio.
     let bottom = entries[entries.len().saturating_sub(PREVIEW_LIMIT)..].to_vec();
     entries.truncate(PREVIEW_LIMIT);
-    AnalysisPreview { entries, bottom, total }
+    AnalysisPreview { entries, bottom, total, manga_volumes: result.manga_volumes }
 }
 
 /// Tokenize the corpus and count lemma frequencies. The full result stays in
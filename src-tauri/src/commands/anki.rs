@@ -1,28 +1,57 @@
 //! Anki commands (contracts/commands.md "Anki").
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+};
 
+use tauri::State;
 use yomine::{
-    anki,
+    anki::{
+        self,
+        api::AnkiConnection,
+    },
     core::settings::AnkiModelInfo,
 };
 
-use crate::events::AnkiStatus;
+use crate::{
+    events::AnkiStatus,
+    state::AppState,
+};
+
+fn anki_connection(state: &State<'_, Mutex<AppState>>) -> AnkiConnection {
+    state.lock().unwrap().settings.anki_connection()
+}
 
 /// Point-in-time connectivity probe; `fetching` is always `false` here.
+/// `url` / `api_key` let the modal test staged (unsaved) values.
 #[tauri::command]
-pub async fn get_anki_status() -> AnkiStatus {
-    let connected = anki::api::get_version().await.is_ok();
-    AnkiStatus { connected, fetching: false }
+pub async fn get_anki_status(
+    state: State<'_, Mutex<AppState>>,
+    url: Option<String>,
+    api_key: Option<String>,
+) -> Result<AnkiStatus, String> {
+    let mut connection = anki_connection(&state);
+    if let Some(url) = url {
+        connection.url = url;
+        connection.api_key = api_key.filter(|key| !key.is_empty());
+    }
+    let connected = anki::api::get_version(&connection).await.is_ok();
+    Ok(AnkiStatus::new(connected, false))
 }
 
 /// Note types (with fields) that have at least one note. Errors when Anki is
 /// offline so the UI can say so.
 #[tauri::command]
-pub async fn list_anki_models() -> Result<Vec<AnkiModelInfo>, String> {
-    anki::api::get_version().await.map_err(|_| "Anki Offline".to_string())?;
+pub async fn list_anki_models(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<AnkiModelInfo>, String> {
+    let connection = anki_connection(&state);
+    anki::api::get_version(&connection).await.map_err(|_| "Anki Offline".to_string())?;
 
-    let models = anki::get_models().await.map_err(|e| format!("Failed to fetch models: {}", e))?;
+    let models = anki::get_models(&connection)
+        .await
+        .map_err(|e| format!("Failed to fetch models: {}", e))?;
 
     Ok(models
         .into_iter()
@@ -46,8 +75,14 @@ pub struct SampleNote {
 /// Sample note + engine-side field guessing for one note type. Errors are
 /// swallowed into "no sample", so this never rejects.
 #[tauri::command]
-pub async fn get_anki_sample_note(model_name: String, fields: Vec<String>) -> SampleNote {
-    let sample_note = anki::get_sample_note_for_model(&model_name).await.unwrap_or(None);
+pub async fn get_anki_sample_note(
+    state: State<'_, Mutex<AppState>>,
+    model_name: String,
+    fields: Vec<String>,
+) -> Result<SampleNote, String> {
+    let connection = anki_connection(&state);
+    let sample_note =
+        anki::get_sample_note_for_model(&connection, &model_name).await.unwrap_or(None);
     let (guessed_term, guessed_reading) = sample_note
         .as_ref()
         .map(|note| anki::guess_field_mappings(note, &fields))
@@ -55,5 +90,5 @@ pub async fn get_anki_sample_note(model_name: String, fields: Vec<String>) -> Sa
     let guessed_sentence =
         sample_note.as_ref().and_then(|note| anki::guess_sentence_field(note, &fields));
 
-    SampleNote { sample_note, guessed_term, guessed_reading, guessed_sentence }
+    Ok(SampleNote { sample_note, guessed_term, guessed_reading, guessed_sentence })
 }
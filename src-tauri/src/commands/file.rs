@@ -17,17 +17,29 @@ use tauri::{
 };
 use tauri_plugin_dialog::DialogExt;
 use yomine::{
-    anki::comprehensibility::calculate_sentence_comprehension,
+    anki::{
+        api::AnkiConnection,
+        comprehensibility::{
+            find_target_sentences,
+            SentenceTermIndex,
+            TargetSentence,
+        },
+        AnkiState,
+    },
     core::{
         filename_parser,
         models::{
             SourceFile,
             SourceFileType,
+            TextEncoding,
+            TextMarkup,
         },
         pipeline::{
             apply_filters,
             process_sentences,
             process_source_file,
+            process_source_files,
+            score_file,
             AnkiFilter,
         },
         recent_files::{
@@ -35,11 +47,23 @@ use yomine::{
             RecentFiles,
         },
         text_filter,
+        SettingsData,
+    },
+    parser::{
+        self,
+        ReadOptions,
     },
     persistence::{
         load_json_or_default,
         save_json,
     },
+    tools::{
+        analysis::find_supported_files_recursive,
+        term_export::{
+            self,
+            TermExportFormat,
+        },
+    },
 };
 
 use crate::{
@@ -71,14 +95,21 @@ const DEFAULT_SOURCE_FILE_ID: u32 = 3;
 pub(crate) fn load_result(file: &FileData) -> Option<FileLoadResult> {
     let source_file = file.source_file.clone()?;
     // base_terms (not the filtered `terms`) so known/ignored words color too.
-    let spans = term_spans_by_sentence(&file.base_terms, &file.anki_known_lemmas);
+    let index = SentenceTermIndex::new(&file.base_terms);
+    let spans = term_spans_by_sentence(
+        &file.base_terms,
+        &index,
+        &file.anki_known_lemmas,
+        file.sentences.len(),
+    );
     Some(FileLoadResult {
         source_file,
+        sources: file.sources.clone(),
         terms: file.terms.clone(),
         sentences: file
             .sentences
             .iter()
-            .map(|s| SentenceDto::from_sentence(s, spans.get(&s.id).map_or(&[], Vec::as_slice)))
+            .map(|s| SentenceDto::from_sentence(s, spans.get(s.id).map_or(&[], Vec::as_slice)))
             .collect(),
         file_comprehension: file.file_comprehension,
         anki_filter_active: !file.anki_known_lemmas.is_empty(),
@@ -89,27 +120,38 @@ pub(crate) fn load_result(file: &FileData) -> Option<FileLoadResult> {
 
 /// Construct a `SourceFile` from a filesystem path, parsing title/creator from the
 /// filename the same way the egui file modal does. EPUBs prefer the metadata title
-/// and carry the picker's selection label for the top bar and recents to render.
+/// and carry the picker's selection label for the top bar and recents to render;
+/// mokuro volumes prefer their title + volume name.
+/// Text formats record their encoding (and `.txt` its markup), MKVs their
+/// subtitle track — the user's override, else the sniffed/preferred one.
+/// Timed subtitles keep `secondary_subtitle`, a translation file to align.
 fn source_file_from_path(
     path: &str,
     epub_chapters: Option<Vec<usize>>,
     epub_label: Option<String>,
+    encoding: Option<TextEncoding>,
+    text_markup: Option<TextMarkup>,
+    mkv_track: Option<u64>,
+    secondary_subtitle: Option<String>,
 ) -> SourceFile {
     let filename =
         std::path::Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("Unknown");
     let media_info = filename_parser::parse_filename(filename);
     let metadata = media_info.get_metadata_string();
-    let file_type = SourceFileType::from_extension(path);
+    let file_type = yomine::mokuro::source_file_type(path);
     let is_epub = matches!(file_type, SourceFileType::EPUB);
-    let title = if is_epub {
-        yomine::epub::book_title(path)
-            .ok()
-            .filter(|t| !t.is_empty())
-            .unwrap_or_else(|| media_info.display_title())
-    } else {
-        media_info.display_title()
-    };
-    SourceFile {
+    let is_txt = matches!(file_type, SourceFileType::TXT);
+    let is_mkv = matches!(file_type, SourceFileType::MKV);
+    let is_timed = file_type.is_timed();
+    let is_text =
+        !matches!(file_type, SourceFileType::EPUB | SourceFileType::Mokuro | SourceFileType::MKV);
+    let title = match file_type {
+        SourceFileType::EPUB => yomine::epub::book_title(path).ok().filter(|t| !t.is_empty()),
+        SourceFileType::Mokuro => yomine::mokuro::volume_title(path),
+        _ => None,
+    }
+    .unwrap_or_else(|| media_info.display_title());
+    let mut source_file = SourceFile {
         id: DEFAULT_SOURCE_FILE_ID,
         source: None,
         file_type,
@@ -121,6 +163,35 @@ fn source_file_from_path(
             .filter(|_| is_epub)
             .map(|l| l.trim().to_string())
             .filter(|l| !l.is_empty()),
+        encoding: if is_text {
+            encoding.or_else(|| yomine::parser::detect_file_encoding(path).ok())
+        } else {
+            None
+        },
+        text_markup: None,
+        mkv_track: if is_mkv {
+            mkv_track.or_else(|| {
+                let tracks = yomine::mkv::subtitle_tracks(path).ok()?;
+                yomine::mkv::preferred_track(&tracks).map(|track| track.number)
+            })
+        } else {
+            None
+        },
+        secondary_subtitle: secondary_subtitle.filter(|_| is_timed),
+        excluded_styles: None,
+    };
+    if is_txt {
+        source_file.text_markup =
+            text_markup.or_else(|| yomine::parser::detect_file_markup(&source_file).ok());
+    }
+    source_file
+}
+
+/// The parse options the user's settings ask for.
+fn read_options(settings: &SettingsData) -> ReadOptions {
+    ReadOptions {
+        join_split_cues: settings.join_split_cues,
+        excluded_styles: settings.excluded_ass_styles.clone(),
     }
 }
 
@@ -144,6 +215,25 @@ pub async fn open_file_dialog(app: AppHandle) -> Result<Option<String>, String>
     .await
 }
 
+/// Multi-select open dialog for a session. Returns the chosen paths (empty on cancel).
+#[tauri::command]
+pub async fn open_files_dialog(app: AppHandle) -> Result<Vec<String>, String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .add_filter("Subtitles & text", SourceFileType::supported_extensions())
+        .pick_files(move |paths| {
+            let _ = tx.send(paths);
+        });
+    let chosen = rx.await.map_err(|_| "file dialog closed unexpectedly".to_string())?;
+    Ok(chosen
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|p| p.into_path().ok())
+        .map(|p| p.display().to_string())
+        .collect())
+}
+
 /// Book title + pickable chapters for the EPUB chapter picker.
 #[tauri::command]
 pub async fn get_epub_chapters(path: String) -> Result<EpubBookDto, String> {
@@ -170,6 +260,86 @@ pub async fn get_epub_chapters(path: String) -> Result<EpubBookDto, String> {
     })
 }
 
+/// Subtitle tracks embedded in an MKV, for the File → Subtitle Track submenu.
+/// Image-based tracks are listed too (the UI disables them).
+#[tauri::command]
+pub async fn get_mkv_tracks(path: String) -> Result<Vec<yomine::mkv::SubtitleTrack>, String> {
+    yomine::mkv::subtitle_tracks(&path).map_err(|e| e.to_string())
+}
+
+/// ASS/SSA styles of the loaded file (or its MKV track) with line counts and
+/// whether they're dropped, for the File → Subtitle Styles submenu. Empty for
+/// other formats.
+#[tauri::command]
+pub async fn get_subtitle_styles(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<parser::SubtitleStyle>, String> {
+    let (source_file, options) = {
+        let guard = state.lock().unwrap();
+        let Some(source_file) = guard.file.source_file.clone() else {
+            return Ok(Vec::new());
+        };
+        (source_file, read_options(&guard.settings))
+    };
+    parser::subtitle_styles(&source_file, &options).map_err(|e| e.to_string())
+}
+
+/// Save dialog, then the loaded file's unknown terms as CSV, JSON or an
+/// Anki-importable TSV. Returns the written path or `null` if cancelled.
+#[tauri::command]
+pub async fn export_terms(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    format: TermExportFormat,
+) -> Result<Option<String>, String> {
+    let (title, terms, sentences) = {
+        let guard = state.lock().unwrap();
+        let Some(source_file) = guard.file.source_file.as_ref() else {
+            return Err("No file loaded".to_string());
+        };
+        (source_file.title.clone(), guard.file.terms.clone(), guard.file.sentences.clone())
+    };
+
+    let extension = format.extension();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .add_filter(extension.to_uppercase(), &[extension])
+        .set_file_name(format!("{title} terms.{extension}"))
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let chosen = rx.await.map_err(|_| "file dialog closed unexpectedly".to_string())?;
+
+    let Some(path) = chosen.and_then(|p| p.into_path().ok()) else {
+        return Ok(None);
+    };
+    term_export::export_terms(&terms, &sentences, format, &path).map_err(|e| e.to_string())?;
+    Ok(Some(path.display().to_string()))
+}
+
+/// i+1 (or i+`max_unknown`) sentences of the loaded file for the sentence
+/// finder. `unknown` indexes `FileLoadResult.terms`; parts of speech the user
+/// filtered out of the table don't count either way.
+#[tauri::command]
+pub fn get_target_sentences(
+    state: State<'_, Mutex<AppState>>,
+    max_unknown: usize,
+) -> Vec<TargetSentence> {
+    let guard = state.lock().unwrap();
+    let pos_filters = &guard.settings.pos_filters;
+    find_target_sentences(
+        &guard.file.sentences,
+        &guard.file.base_terms,
+        &guard.file.terms,
+        max_unknown.max(1),
+        |term| {
+            term.part_of_speech.is_vocabulary_word()
+                && pos_filters.get(term.part_of_speech.as_key()) != Some(&false)
+        },
+    )
+}
+
 /// Part ids already mined for this book (`epub_history.json`, keyed by path).
 fn epub_history(path: &str) -> Vec<usize> {
     let mut history = load_json_or_default::<HashMap<String, Vec<usize>>>("epub_history.json");
@@ -214,6 +384,11 @@ pub async fn open_executable_dialog(app: AppHandle) -> Result<Option<String>, St
 
 /// Parse + segment + filter a source file (cached Anki snapshot, offline-safe) and
 /// return the minable terms + sentence DTOs. Stores the result in `AppState`.
+/// `encoding` / `text_markup` force a text encoding or `.txt` markup (Aozora
+/// Bunko vs plain) when the sniffed one was wrong; `mkv_track` picks an MKV's
+/// embedded subtitle track; `secondary_subtitle` aligns a translation file's cues
+/// onto the sentences; `excluded_styles` picks the ASS styles to drop (`None` =
+/// the settings' globs).
 #[tauri::command]
 pub async fn process_file(
     app: AppHandle,
@@ -221,24 +396,42 @@ pub async fn process_file(
     path: String,
     epub_chapters: Option<Vec<usize>>,
     epub_label: Option<String>,
+    encoding: Option<TextEncoding>,
+    text_markup: Option<TextMarkup>,
+    mkv_track: Option<u64>,
+    secondary_subtitle: Option<String>,
+    excluded_styles: Option<Vec<String>>,
     progress: Channel<LoadingMessage>,
 ) -> Result<FileLoadResult, String> {
-    let (tools, filters) = {
+    let (tools, filters, read_options) = {
         let guard = state.lock().unwrap();
         let tools = guard
             .language_tools
             .clone()
             .ok_or_else(|| "Language tools are still loading".to_string())?;
-        (tools, text_filter::compile_filters(&guard.settings))
+        (tools, text_filter::compile_filters(&guard.settings), read_options(&guard.settings))
     };
 
     let _ = progress.send(LoadingMessage::new("Processing file..."));
-    let source_file = source_file_from_path(&path, epub_chapters, epub_label);
+    let mut source_file = source_file_from_path(
+        &path,
+        epub_chapters,
+        epub_label,
+        encoding,
+        text_markup,
+        mkv_track,
+        secondary_subtitle,
+    );
+    if matches!(source_file.file_type, SourceFileType::SSA | SourceFileType::MKV) {
+        source_file.excluded_styles = excluded_styles;
+    }
 
     // Segmentation blocks the async runtime briefly, but the UI is a separate
     // webview process — nothing user-visible freezes.
     let (base_terms, filter_result, sentences, file_comprehension) =
-        process_source_file(&source_file, &tools, &filters).await.map_err(|e| e.to_string())?;
+        process_source_file(&source_file, &read_options, &tools, &filters)
+            .await
+            .map_err(|e| e.to_string())?;
 
     // Record the file in the shared `recent_files.json` (same store as egui) so it
     // appears on the landing state, mirroring egui's `add_recent_file`.
@@ -255,6 +448,7 @@ pub async fn process_file(
     let mut guard = state.lock().unwrap();
     guard.file = FileData {
         source_file: Some(source_file),
+        sources: Vec::new(),
         terms: filter_result.terms,
         base_terms,
         anki_known_lemmas,
@@ -271,7 +465,142 @@ pub async fn process_file(
     // refresh against it in the background via `terms-refreshed`.
     let app_handle = app.clone();
     tauri::async_runtime::spawn(async move {
-        if yomine::anki::api::get_version().await.is_ok() {
+        if yomine::anki::api::get_version(&anki_connection(&app_handle)).await.is_ok() {
+            if let Err(e) = live_refresh(&app_handle).await {
+                let _ = app_handle.emit(
+                    names::ERROR,
+                    ErrorPayload {
+                        title: "Refresh Error".into(),
+                        message: "Unable to refresh terms".into(),
+                        detail: Some(e),
+                    },
+                );
+            }
+        }
+    });
+
+    let _ = progress.send(LoadingMessage::clear());
+    Ok(payload)
+}
+
+/// The files of a session: folders (other than a mokuro volume) expand to the
+/// subtitle files below them, and the whole set reads in natural path order so
+/// `Ep 2` precedes `Ep 10` and each season stays together. Videos and text
+/// files are only taken when picked directly — in a season folder they sit
+/// beside the subtitles they would duplicate.
+fn session_paths(paths: Vec<String>) -> Vec<String> {
+    let mut files: Vec<std::path::PathBuf> = paths
+        .into_iter()
+        .map(std::path::PathBuf::from)
+        .flat_map(|path| {
+            if path.is_dir() && !yomine::mokuro::is_page_dir(&path) {
+                find_supported_files_recursive(&path)
+                    .into_iter()
+                    .filter(|file| is_sidecar_subtitle(file))
+                    .collect()
+            } else {
+                vec![path]
+            }
+        })
+        .collect();
+    files.sort_by_cached_key(|path| {
+        (yomine::mokuro::natural_key(&path.to_string_lossy()), path.clone())
+    });
+    files.dedup();
+    files.into_iter().map(|path| path.display().to_string()).collect()
+}
+
+/// A standalone timed subtitle file (not an MKV's embedded track).
+fn is_sidecar_subtitle(path: &std::path::Path) -> bool {
+    let file_type = SourceFileType::from_extension(&path.to_string_lossy());
+    file_type.is_timed() && !matches!(file_type, SourceFileType::MKV)
+}
+
+/// Process several files (a season, a book series) as one session: terms are
+/// deduplicated across them and each sentence's `source_id` indexes `sources`.
+/// `paths` may hold folders. One file falls back to a regular load.
+#[tauri::command]
+pub async fn process_files(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    paths: Vec<String>,
+    progress: Channel<LoadingMessage>,
+) -> Result<FileLoadResult, String> {
+    let paths = session_paths(paths);
+    match paths.len() {
+        0 => return Err("No supported files were selected".to_string()),
+        1 => {
+            let path = paths.into_iter().next().expect("one path");
+            return process_file(
+                app, state, path, None, None, None, None, None, None, None, progress,
+            )
+            .await;
+        }
+        _ => {}
+    }
+
+    let (tools, filters, read_options) = {
+        let guard = state.lock().unwrap();
+        let tools = guard
+            .language_tools
+            .clone()
+            .ok_or_else(|| "Language tools are still loading".to_string())?;
+        (tools, text_filter::compile_filters(&guard.settings), read_options(&guard.settings))
+    };
+
+    let _ = progress.send(LoadingMessage::new(format!("Processing {} files...", paths.len())));
+    // EPUBs load whole (no chapter picker per volume).
+    let sources: Vec<SourceFile> = paths
+        .iter()
+        .enumerate()
+        .map(|(idx, path)| SourceFile {
+            id: idx as u32,
+            ..source_file_from_path(path, None, None, None, None, None, None)
+        })
+        .collect();
+
+    let (base_terms, filter_result, sentences, file_comprehension) =
+        process_source_files(&sources, &read_options, &tools, &filters)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    // The session reads as its folder in the top bar; the first file stands in
+    // for the per-file menus and player.
+    let folder = std::path::Path::new(&sources[0].original_file)
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .map(str::to_string);
+    let source_file = SourceFile {
+        id: DEFAULT_SOURCE_FILE_ID,
+        title: folder.unwrap_or_else(|| sources[0].title.clone()),
+        creator: Some(format!("{} files", sources.len())),
+        ..sources[0].clone()
+    };
+
+    let anki_known_lemmas =
+        filter_result.anki_filtered.iter().map(|t| t.lemma_form.clone()).collect();
+
+    let mut guard = state.lock().unwrap();
+    guard.file = FileData {
+        source_file: Some(source_file),
+        sources,
+        terms: filter_result.terms,
+        base_terms,
+        anki_known_lemmas,
+        ignored_count: filter_result.ignore_filtered.len(),
+        sentences,
+        file_comprehension,
+        asbplayer_media_id: None,
+        asbplayer_subtitle_file: None,
+    };
+    let payload = load_result(&guard.file).expect("file just stored has a source_file");
+    drop(guard);
+
+    // Same background live-Anki refresh as `process_file`.
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if yomine::anki::api::get_version(&anki_connection(&app_handle)).await.is_ok() {
             if let Err(e) = live_refresh(&app_handle).await {
                 let _ = app_handle.emit(
                     names::ERROR,
@@ -327,13 +656,13 @@ pub(crate) async fn load_asbplayer_into_state(
     progress: Option<&Channel<LoadingMessage>>,
 ) -> Result<FileLoadResult, String> {
     let state = app.state::<Mutex<AppState>>();
-    let (tools, filters) = {
+    let (tools, filters, read_options) = {
         let guard = state.lock().unwrap();
         let tools = guard
             .language_tools
             .clone()
             .ok_or_else(|| "Language tools are still loading".to_string())?;
-        (tools, text_filter::compile_filters(&guard.settings))
+        (tools, text_filter::compile_filters(&guard.settings), read_options(&guard.settings))
     };
 
     let file_name = subtitle_file_name.filter(|n| !n.trim().is_empty());
@@ -396,13 +725,21 @@ pub(crate) async fn load_asbplayer_into_state(
         original_file: saved_path.unwrap_or_else(|| format!("asbplayer://{media_id}")),
         epub_chapters: None,
         epub_label: None,
+        encoding: None,
+        text_markup: None,
+        mkv_track: None,
+        secondary_subtitle: None,
+        excluded_styles: None,
     };
 
-    let sentences: Vec<_> = subtitles
+    let mut sentences: Vec<_> = subtitles
         .iter()
         .enumerate()
         .filter_map(|(id, cue)| cue.to_sentence(id, source_file.id))
         .collect();
+    if read_options.join_split_cues {
+        sentences = parser::join_split_cues(sentences);
+    }
     if sentences.is_empty() {
         return Err("The subtitles were empty after cleanup".to_string());
     }
@@ -422,6 +759,7 @@ pub(crate) async fn load_asbplayer_into_state(
     let mut guard = state.lock().unwrap();
     guard.file = FileData {
         source_file: Some(source_file),
+        sources: Vec::new(),
         terms: filter_result.terms,
         base_terms,
         anki_known_lemmas,
@@ -437,7 +775,7 @@ pub(crate) async fn load_asbplayer_into_state(
     // Same background live-Anki refresh as `process_file`.
     let app_handle = app.clone();
     tauri::async_runtime::spawn(async move {
-        if yomine::anki::api::get_version().await.is_ok() {
+        if yomine::anki::api::get_version(&anki_connection(&app_handle)).await.is_ok() {
             if let Err(e) = live_refresh(&app_handle).await {
                 let _ = app_handle.emit(
                     names::ERROR,
@@ -457,12 +795,27 @@ pub(crate) async fn load_asbplayer_into_state(
     Ok(payload)
 }
 
+fn anki_connection(app: &AppHandle) -> AnkiConnection {
+    app.state::<Mutex<AppState>>().lock().unwrap().settings.anki_connection()
+}
+
 /// Re-partition the loaded terms against **live** Anki data and emit
 /// `terms-refreshed`. Marks the knowledge summary dirty — the live fetch just
 /// rewrote the vocab cache.
 pub(crate) async fn live_refresh(app: &AppHandle) -> Result<(), String> {
+    refilter(app, true).await
+}
+
+/// Re-partition the loaded terms against the on-disk vocab cache, without
+/// touching Anki: for settings that only change how cached vocab counts
+/// (suspended cards).
+pub(crate) async fn cached_refresh(app: &AppHandle) -> Result<(), String> {
+    refilter(app, false).await
+}
+
+async fn refilter(app: &AppHandle, live: bool) -> Result<(), String> {
     let state = app.state::<Mutex<AppState>>();
-    let (tools, base_terms, mut sentences, mappings) = {
+    let (tools, base_terms, mut sentences, anki_filter) = {
         let guard = state.lock().unwrap();
         let tools = guard
             .language_tools
@@ -476,31 +829,35 @@ pub(crate) async fn live_refresh(app: &AppHandle) -> Result<(), String> {
             tools,
             guard.file.base_terms.clone(),
             guard.file.sentences.clone(),
-            guard.settings.anki_model_mappings.clone(),
+            if live {
+                AnkiFilter::Live {
+                    connection: guard.settings.anki_connection(),
+                    model_mapping: guard.settings.anki_model_mappings.clone(),
+                    vocab_query: guard.settings.anki_vocab_query.clone(),
+                }
+            } else {
+                AnkiFilter::Cached
+            },
         )
     };
 
     // Mirror egui's `anki_fetching = true` spinner while the live fetch runs.
-    let _ = app.emit(names::ANKI_STATUS, AnkiStatus { connected: true, fetching: true });
+    if live {
+        let _ = app.emit(names::ANKI_STATUS, AnkiStatus::new(true, true));
+    }
 
     let outcome: Result<FileLoadResult, String> = async {
-        let filter_result = apply_filters(base_terms, &tools, AnkiFilter::Live(mappings))
-            .await
-            .map_err(|e| e.to_string())?;
+        let mut filter_result =
+            apply_filters(base_terms, &tools, anki_filter).await.map_err(|e| e.to_string())?;
 
         // Reconstruct the full term set and recompute comprehension from it.
         let mut all_terms = Vec::new();
         all_terms.extend(filter_result.terms.iter().cloned());
         all_terms.extend(filter_result.anki_filtered.iter().cloned());
         all_terms.extend(filter_result.ignore_filtered.iter().cloned());
-        for sentence in &mut sentences {
-            calculate_sentence_comprehension(sentence, &all_terms);
-        }
-        let file_comprehension = if sentences.is_empty() {
-            0.0
-        } else {
-            sentences.iter().map(|s| s.comprehension).sum::<f32>() / sentences.len() as f32
-        };
+        // Best-sentence comprehension feeds the priority score, so both rerun.
+        let file_comprehension =
+            score_file(&mut sentences, &mut all_terms, &mut filter_result.terms, &tools);
 
         let mut guard = state.lock().unwrap();
         guard.file.anki_known_lemmas =
@@ -510,20 +867,57 @@ pub(crate) async fn live_refresh(app: &AppHandle) -> Result<(), String> {
         guard.file.base_terms = all_terms;
         guard.file.sentences = sentences;
         guard.file.file_comprehension = file_comprehension;
-        // Recompute coverage from the fresh vocab cache (egui resets
+        // Recompute coverage from the refreshed vocab (egui resets
         // `knowledge_summary_attempted`).
         guard.knowledge_dirty.store(true, Ordering::Relaxed);
         Ok(load_result(&guard.file).expect("refreshed file has a source_file"))
     }
     .await;
 
-    let _ =
-        app.emit(names::ANKI_STATUS, AnkiStatus { connected: outcome.is_ok(), fetching: false });
+    if live {
+        let _ = app.emit(names::ANKI_STATUS, AnkiStatus::new(outcome.is_ok(), false));
+    }
     let payload = outcome?;
     let _ = app.emit(names::TERMS_REFRESHED, &payload);
     Ok(())
 }
 
+/// Re-harvest the Anki vocab after what counts as known changed (mappings or
+/// queries): the loaded file is re-filtered live; with none loaded, only the
+/// vocab cache, and with it the knowledge summary, is rebuilt.
+pub(crate) async fn refresh_known_vocab(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<Mutex<AppState>>();
+    let (tools, connection, mappings, vocab_query, file_loaded) = {
+        let guard = state.lock().unwrap();
+        (
+            guard.language_tools.clone(),
+            guard.settings.anki_connection(),
+            guard.settings.anki_model_mappings.clone(),
+            guard.settings.anki_vocab_query.clone(),
+            !guard.file.base_terms.is_empty(),
+        )
+    };
+    if file_loaded {
+        return live_refresh(app).await;
+    }
+    let Some(tools) = tools else { return Ok(()) };
+
+    let _ = app.emit(names::ANKI_STATUS, AnkiStatus::new(true, true));
+    let outcome = AnkiState::new(
+        &connection,
+        mappings,
+        &vocab_query,
+        tools.frequency_manager.clone(),
+        tools.known_interval,
+        tools.suspended_cards,
+    )
+    .await;
+    let _ = app.emit(names::ANKI_STATUS, AnkiStatus::new(outcome.is_ok(), false));
+    outcome.map_err(|e| e.to_string())?;
+    state.lock().unwrap().knowledge_dirty.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
 /// Manual "reapply ignorelist and Anki filters" (egui's top-bar 🔄 / F5 / Cmd+R
 /// → `RequestRefresh`). The updated file arrives via the `terms-refreshed` event.
 #[tauri::command]
@@ -544,7 +938,7 @@ pub async fn reload_current_file(
     state: State<'_, Mutex<AppState>>,
     progress: Channel<LoadingMessage>,
 ) -> Result<FileLoadResult, String> {
-    let (tools, filters, source_file, media_id, subtitle_file) = {
+    let (tools, filters, read_options, source_file, sources, media_id, subtitle_file) = {
         let guard = state.lock().unwrap();
         let tools = guard
             .language_tools
@@ -555,26 +949,34 @@ pub async fn reload_current_file(
         (
             tools,
             text_filter::compile_filters(&guard.settings),
+            read_options(&guard.settings),
             source_file,
+            guard.file.sources.clone(),
             guard.file.asbplayer_media_id.clone(),
             guard.file.asbplayer_subtitle_file.clone(),
         )
     };
-    if !std::path::Path::new(&source_file.original_file).exists() {
+    let files = if sources.is_empty() { std::slice::from_ref(&source_file) } else { &sources };
+    if files.iter().any(|file| !std::path::Path::new(&file.original_file).exists()) {
         return Err(
             "The loaded file no longer exists on disk — reload it from its source".to_string()
         );
     }
 
     let _ = progress.send(LoadingMessage::new("Reprocessing file..."));
-    let (base_terms, filter_result, sentences, file_comprehension) =
-        process_source_file(&source_file, &tools, &filters).await.map_err(|e| e.to_string())?;
+    let (base_terms, filter_result, sentences, file_comprehension) = if sources.is_empty() {
+        process_source_file(&source_file, &read_options, &tools, &filters).await
+    } else {
+        process_source_files(&sources, &read_options, &tools, &filters).await
+    }
+    .map_err(|e| e.to_string())?;
 
     let anki_known_lemmas =
         filter_result.anki_filtered.iter().map(|t| t.lemma_form.clone()).collect();
     let mut guard = state.lock().unwrap();
     guard.file = FileData {
         source_file: Some(source_file),
+        sources,
         terms: filter_result.terms,
         base_terms,
         anki_known_lemmas,
@@ -589,7 +991,7 @@ pub async fn reload_current_file(
 
     let app_handle = app.clone();
     tauri::async_runtime::spawn(async move {
-        if yomine::anki::api::get_version().await.is_ok() {
+        if yomine::anki::api::get_version(&anki_connection(&app_handle)).await.is_ok() {
             if let Err(e) = live_refresh(&app_handle).await {
                 let _ = app_handle.emit(
                     names::ERROR,
@@ -10,12 +10,15 @@ use tauri::{
     ipc::Channel,
     AppHandle,
     Emitter,
+    Manager,
     State,
 };
 use tauri_plugin_opener::OpenerExt;
 use yomine::{
     core::{
+        pipeline::score_file,
         settings::SettingsData,
+        ComprehensionModel,
         IgnoreList,
         LanguageTools,
     },
@@ -31,11 +34,22 @@ use yomine::{
     },
 };
 
-use super::apply_frequency_weights;
+use super::{
+    apply_frequency_weights,
+    file::{
+        cached_refresh,
+        load_result,
+        refresh_known_vocab,
+    },
+};
 use crate::{
-    dto::PosInfo,
+    dto::{
+        FileLoadResult,
+        PosInfo,
+    },
     events::{
         names,
+        ErrorPayload,
         LanguageToolsStatus,
         LoadingMessage,
     },
@@ -63,8 +77,18 @@ pub async fn load_language_tools(
         return Ok(());
     }
 
-    // `known_interval` comes from settings; set on the tools once built (egui parity).
-    let known_interval = state.lock().unwrap().settings.anki_interval;
+    // `known_interval`, the priority weights, the comprehension model and how
+    // suspended cards count come from settings; set on the tools once built
+    // (egui parity).
+    let (known_interval, priority_weights, comprehension_model, suspended_cards) = {
+        let guard = state.lock().unwrap();
+        (
+            guard.settings.anki_interval,
+            guard.settings.priority_weights,
+            guard.settings.comprehension_model,
+            guard.settings.anki_suspended_cards,
+        )
+    };
 
     // The loaders block (dictionary download + parse), so run them off the async
     // runtime; the `Channel` is `Send`/`Sync` and carries progress out.
@@ -90,7 +114,16 @@ pub async fn load_language_tools(
 
         let jlpt = Arc::new(JlptDatabase::load());
 
-        Ok(LanguageTools { tokenizer, frequency_manager, ignore_list, jlpt, known_interval })
+        Ok(LanguageTools {
+            tokenizer,
+            frequency_manager,
+            ignore_list,
+            jlpt,
+            known_interval,
+            priority_weights,
+            comprehension_model,
+            suspended_cards,
+        })
     })
     .await
     .map_err(|e| e.to_string())?;
@@ -170,7 +203,6 @@ pub async fn import_theme_file(app: AppHandle) -> Result<Option<String>, String>
 #[tauri::command]
 pub async fn open_themes_window(app: AppHandle) -> Result<(), String> {
     use tauri::{
-        Manager,
         WebviewUrl,
         WebviewWindowBuilder,
     };
@@ -233,8 +265,11 @@ pub fn test_text_filters(
 }
 
 /// Persist + replace the in-memory copy, propagating the bits that affect the
-/// live tools (known-interval, frequency weights). Emits `settings-changed` so
-/// every window (main + themes) sees the update.
+/// live tools (known-interval, comprehension model, suspended cards, priority
+/// and frequency weights). Emits `settings-changed` so every window (main +
+/// themes) sees the update, and `terms-refreshed` when the loaded file was
+/// rescored for new priority weights or comprehension model, or re-filtered
+/// from the vocab cache for a new suspended-cards rule.
 #[tauri::command]
 pub fn save_settings(
     app: AppHandle,
@@ -248,14 +283,39 @@ pub fn save_settings(
 
     let summary_inputs_changed = guard.settings.anki_interval != settings.anki_interval
         || guard.settings.frequency_weights != settings.frequency_weights;
+    // The loaded file's scores read these; rescore it in place.
+    let scoring_changed = guard.settings.priority_weights != settings.priority_weights
+        || guard.settings.comprehension_model != settings.comprehension_model;
+    // What counts as known changed: the vocab cache no longer matches it.
+    let vocab_scope_changed = guard.settings.anki_model_mappings != settings.anki_model_mappings
+        || guard.settings.anki_vocab_query != settings.anki_vocab_query
+        || guard.settings.anki_connection() != settings.anki_connection();
+    // The cache still holds every card's suspension; only the counting changed.
+    let suspended_changed = guard.settings.anki_suspended_cards != settings.anki_suspended_cards;
     guard.settings = settings;
     let anki_interval = guard.settings.anki_interval;
+    let priority_weights = guard.settings.priority_weights;
+    let comprehension_model = guard.settings.comprehension_model;
+    let suspended_cards = guard.settings.anki_suspended_cards;
     if let Some(tools) = guard.language_tools.as_mut() {
         tools.known_interval = anki_interval;
+        tools.priority_weights = priority_weights;
+        tools.comprehension_model = comprehension_model;
+        tools.suspended_cards = suspended_cards;
     }
-    if summary_inputs_changed {
+    if summary_inputs_changed || suspended_changed {
         guard.knowledge_dirty.store(true, Ordering::Relaxed);
     }
+    if scoring_changed && guard.file.source_file.is_some() {
+        if let Some(tools) = guard.language_tools.clone() {
+            let file = &mut guard.file;
+            file.file_comprehension =
+                score_file(&mut file.sentences, &mut file.base_terms, &mut file.terms, &tools);
+            if let Some(payload) = load_result(file) {
+                let _ = app.emit(names::TERMS_REFRESHED, &payload);
+            }
+        }
+    }
     // `frequency_manager` is behind an `Arc` with interior mutability, so clone the
     // handle to drop the borrow on `guard` before reapplying weights.
     let manager = guard.language_tools.as_ref().map(|t| Arc::clone(&t.frequency_manager));
@@ -264,5 +324,61 @@ pub fn save_settings(
     if let Some(manager) = manager {
         apply_frequency_weights(&manager, &weights);
     }
+    if vocab_scope_changed || suspended_changed {
+        tauri::async_runtime::spawn(async move {
+            let connection =
+                app.state::<Mutex<AppState>>().lock().unwrap().settings.anki_connection();
+            // A new scope needs a re-harvest; how suspended cards count is
+            // re-applied to the cached vocab, online or not.
+            let refreshed = if vocab_scope_changed
+                && yomine::anki::api::get_version(&connection).await.is_ok()
+            {
+                refresh_known_vocab(&app).await
+            } else if suspended_changed {
+                cached_refresh(&app).await
+            } else {
+                Ok(())
+            };
+            if let Err(e) = refreshed {
+                let _ = app.emit(
+                    names::ERROR,
+                    ErrorPayload {
+                        title: "Refresh Error".into(),
+                        message: "Unable to refresh known vocabulary".into(),
+                        detail: Some(e),
+                    },
+                );
+            }
+        });
+    }
     Ok(())
 }
+
+/// Switch the comprehension model and rescore the loaded file in place (no
+/// re-parse): sentence and file comprehension, then priority. Returns the
+/// updated file, `null` if none is loaded.
+#[tauri::command]
+pub fn set_comprehension_model(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    model: ComprehensionModel,
+) -> Result<Option<FileLoadResult>, String> {
+    let mut guard = state.lock().unwrap();
+    guard.settings.comprehension_model = model;
+    persistence::save_json(&guard.settings, "settings.json").map_err(|e| e.to_string())?;
+    let _ = app.emit(names::SETTINGS_CHANGED, guard.settings.clone());
+
+    let Some(tools) = guard.language_tools.as_mut() else {
+        return Ok(None);
+    };
+    tools.comprehension_model = model;
+    let tools = tools.clone();
+    if guard.file.source_file.is_none() {
+        return Ok(None);
+    }
+
+    let file = &mut guard.file;
+    file.file_comprehension =
+        score_file(&mut file.sentences, &mut file.base_terms, &mut file.terms, &tools);
+    Ok(load_result(file))
+}
@@ -1,34 +1,60 @@
 //! One-click mining (issue #105) + mined-state tracking (issue #3). The note
 //! is always created via AnkiConnect from Yomitan-rendered fields; the
 //! asbplayer path then enriches it (audio/screenshot) via a note-targeted
-//! `mine-subtitle` update.
+//! `mine-subtitle` update. Batch mining (`mine_terms`) renders every term
+//! first, then creates the notes in a few batched AnkiConnect requests.
 
 use std::{
-    sync::Mutex,
+    collections::HashSet,
+    sync::{
+        atomic::Ordering,
+        Arc,
+        Mutex,
+    },
     time::Duration,
 };
 
 use tauri::{
     ipc::Channel,
+    AppHandle,
+    Emitter,
     State,
 };
 use yomine::{
     anki::{
-        api as anki_api,
+        api::{
+            self as anki_api,
+            AnkiConnection,
+            NewNote,
+        },
+        mine_queue::{
+            self,
+            QueuedMedia,
+            QueuedMine,
+        },
         mined,
     },
     yomitan,
 };
 
 use crate::{
+    commands::player::source_media_id,
     dto::{
         CardFormatDto,
         DefinitionEntryDto,
+        FailedMineDto,
+        MineBatchItemDto,
+        MineRequestDto,
         MineResultDto,
         MinedStateDto,
         YomitanStatusDto,
     },
-    events::LoadingMessage,
+    events::{
+        names,
+        AnkiStatus,
+        LoadingMessage,
+        MineProgress,
+    },
     player_task::PlayerHandle,
     state::AppState,
 };
@@ -40,30 +66,22 @@ const RECORD_BUFFER: Duration = Duration::from_millis(1500);
 const MEDIA_VERIFY_TIMEOUT: Duration = Duration::from_secs(6);
 const MEDIA_VERIFY_POLL: Duration = Duration::from_millis(500);
 
-#[tauri::command]
-pub async fn mine_term(
-    state: State<'_, Mutex<AppState>>,
-    player: State<'_, PlayerHandle>,
-    term: String,
-    surface: String,
-    sentence: String,
-    timestamp_secs: Option<f32>,
-    timestamp_end_secs: Option<f32>,
-    timestamp_label: Option<String>,
-    via: String,
-    entry_index: Option<usize>,
-    format_name: Option<String>,
-    progress: Channel<LoadingMessage>,
-) -> Result<MineResultDto, String> {
-    let (yomitan_url, media_id) = {
-        let guard = state.lock().unwrap();
-        (guard.settings.yomitan_url.clone(), guard.file.asbplayer_media_id.clone())
-    };
-    let entry_index = entry_index.unwrap_or(0);
+/// A Yomitan-rendered note plus the media its fields reference.
+struct PreparedMine {
+    note: NewNote,
+    media: Vec<QueuedMedia>,
+}
 
-    let _ = progress.send(LoadingMessage::new(format!("Rendering 「{}」 with Yomitan…", term)));
-    let formats = yomitan::get_term_card_formats(&yomitan_url).await.map_err(|e| e.to_string())?;
-    let format = match &format_name {
+/// Render one mine's note: the card format's fields for the chosen dictionary
+/// entry, plus the sentence and the loaded file's translation.
+async fn prepare_mine(
+    state: &Mutex<AppState>,
+    yomitan_url: &str,
+    formats: &[yomitan::CardFormat],
+    item: &MineRequestDto,
+) -> Result<PreparedMine, String> {
+    let entry_index = item.entry_index.unwrap_or(0);
+    let format = match &item.format_name {
         Some(name) => formats
             .iter()
             .find(|f| &f.name == name)
@@ -72,101 +90,438 @@ pub async fn mine_term(
     };
     let markers = yomitan::collect_markers(format);
     let rendered =
-        yomitan::render_fields(&yomitan_url, &term, &markers, entry_index as u32 + 1, true)
+        yomitan::render_fields(yomitan_url, &item.term, &markers, entry_index as u32 + 1, true)
             .await
             .map_err(|e| e.to_string())?;
 
     let empty = std::collections::HashMap::new();
     let marker_values = rendered.fields.get(entry_index).unwrap_or(&empty);
     if marker_values.values().all(|v| v.trim().is_empty()) {
-        return Err(format!("Yomitan has no dictionary entry for 「{}」", term));
+        return Err(format!("Yomitan has no dictionary entry for 「{}」", item.term));
     }
 
     // Cloze highlighting must match the text as it appears in the sentence: an
     // inflected occurrence (沈めて) never contains the lemma (沈める).
-    let cloze_term = if !surface.is_empty() && sentence.contains(&surface) {
-        surface.as_str()
+    let cloze_term = if !item.surface.is_empty() && item.sentence.contains(&item.surface) {
+        item.surface.as_str()
     } else {
-        term.as_str()
+        item.term.as_str()
+    };
+    // The loaded file's aligned secondary-subtitle line, into the note type's
+    // mapped translation field.
+    let (translation_field, translation) = {
+        let guard = state.lock().unwrap();
+        let field = guard
+            .settings
+            .anki_model_mappings
+            .get(&format.model)
+            .and_then(|mapping| mapping.translation_field.clone());
+        let translation = guard
+            .file
+            .sentences
+            .iter()
+            .find(|s| {
+                s.source_id == item.source_id
+                    && s.text == item.sentence
+                    && s.timestamp.as_ref().map(|t| t.to_secs().0) == item.timestamp_secs
+            })
+            .and_then(|s| s.translation.clone());
+        (field, translation)
+    };
+    let ctx = yomitan::SentenceContext {
+        sentence: &item.sentence,
+        term: cloze_term,
+        translation: translation_field
+            .as_deref()
+            .zip(translation.as_deref())
+            .map(|(field, text)| yomitan::Translation { field, text }),
     };
-    let ctx = yomitan::SentenceContext { sentence: &sentence, term: cloze_term };
     let fields = yomitan::assemble_fields(format, marker_values, Some(ctx));
     if fields.is_empty() {
-        return Err(format!("Yomitan rendered no card content for 「{}」", term));
+        return Err(format!("Yomitan rendered no card content for 「{}」", item.term));
     }
 
-    let _ = progress.send(LoadingMessage::new("Creating Anki note…"));
+    // No timestamp = no attachable media (EPUB/TXT); tags the note for a future re-mine flow.
+    let mut tags = vec!["yomine".to_string()];
+    if item.timestamp_secs.is_none() {
+        tags.push("yomine::no-media".to_string());
+    }
+    Ok(PreparedMine {
+        note: NewNote {
+            deck_name: format.deck.clone(),
+            model_name: format.model.clone(),
+            fields,
+            tags,
+        },
+        media: rendered
+            .audio_media
+            .iter()
+            .chain(rendered.dictionary_media.iter())
+            .map(|media| QueuedMedia {
+                filename: media.anki_filename.clone(),
+                content: media.content.clone(),
+            })
+            .collect(),
+    })
+}
 
-    // Media failures degrade the note (missing audio/image), not the mine.
-    for media in rendered.audio_media.iter().chain(rendered.dictionary_media.iter()) {
-        match anki_api::store_media_file(&media.anki_filename, &media.content).await {
-            Ok(response) if response.error.is_none() => {}
-            Ok(response) => {
-                eprintln!("storeMediaFile {}: {:?}", media.anki_filename, response.error)
+/// Store media in one round trip. Failures degrade the note (missing
+/// audio/image), not the mine, so they are only logged.
+async fn store_media(connection: &AnkiConnection, media: &[&QueuedMedia]) {
+    if media.is_empty() {
+        return;
+    }
+    let files: Vec<(&str, &str)> =
+        media.iter().map(|m| (m.filename.as_str(), m.content.as_str())).collect();
+    match anki_api::store_media_files(connection, &files).await {
+        Ok(outcomes) => {
+            for ((filename, _), outcome) in files.iter().zip(outcomes) {
+                if let Err(e) = outcome {
+                    eprintln!("storeMediaFile {}: {}", filename, e);
+                }
             }
-            Err(e) => eprintln!("storeMediaFile {}: {}", media.anki_filename, e),
         }
+        Err(e) => eprintln!("storeMediaFile: {}", e),
+    }
+}
+
+/// Keep the rendered note for `poll_anki` to add once Anki is back.
+fn queue_mine(item: &MineRequestDto, prepared: PreparedMine) -> MineResultDto {
+    let PreparedMine { mut note, media } = prepared;
+    let media_lost = item.via == "asbplayer";
+    if media_lost && item.timestamp_secs.is_some() {
+        note.tags.push("yomine::no-media".to_string());
+    }
+    let queued = mine_queue::enqueue_mine(QueuedMine {
+        id: 0,
+        term: item.term.clone(),
+        sentence: item.sentence.clone(),
+        deck: note.deck_name,
+        model: note.model_name,
+        fields: note.fields,
+        tags: note.tags,
+        media,
+        error: None,
+    });
+    let mut warning = format!(
+        "Anki is unreachable — 「{}」 was queued ({} waiting) and will be added when Anki is back",
+        item.term, queued
+    );
+    if media_lost {
+        warning.push_str(", without audio/screenshot");
     }
+    MineResultDto {
+        status: "queued".to_string(),
+        via: item.via.clone(),
+        warning: Some(warning),
+        note_id: None,
+        media_missing: false,
+    }
+}
 
-    // No timestamp = no attachable media (EPUB/TXT); tags the note for a future re-mine flow.
-    let mut tags = vec!["yomine".to_string()];
-    if timestamp_secs.is_none() {
-        tags.push("yomine::no-media".to_string());
+/// No enrichment: a duplicate returns no note id to target.
+fn duplicate_result(item: &MineRequestDto) -> MineResultDto {
+    MineResultDto {
+        status: "duplicate".to_string(),
+        via: item.via.clone(),
+        warning: None,
+        note_id: None,
+        media_missing: false,
     }
-    let response = anki_api::add_note(&format.deck, &format.model, &fields, &tags)
-        .await
-        .map_err(|e| format!("AnkiConnect is unreachable: {}", e))?;
+}
+
+/// A batch note Anki won't add: a duplicate is an outcome, anything else an error.
+fn refused(item: &MineRequestDto, err: String) -> MineBatchItemDto {
+    if err.contains("duplicate") {
+        MineBatchItemDto { result: Some(duplicate_result(item)), error: None }
+    } else {
+        MineBatchItemDto { result: None, error: Some(err) }
+    }
+}
+
+/// Queue a batch's rendered notes after AnkiConnect stopped answering.
+fn queue_batch(
+    app: &AppHandle,
+    items: &[MineRequestDto],
+    outcomes: &mut [MineBatchItemDto],
+    mines: Vec<(usize, PreparedMine)>,
+    error: &impl std::fmt::Display,
+) {
+    eprintln!("AnkiConnect is unreachable, queuing {} mines: {}", mines.len(), error);
+    for (index, mine) in mines {
+        outcomes[index].result = Some(queue_mine(&items[index], mine));
+    }
+    let _ = app.emit(names::ANKI_STATUS, AnkiStatus::new(false, false));
+}
+
+/// asbplayer enrichment of a created note, as `(warning, media_missing)`.
+/// Failures don't undo the mine (the note exists) — they become a warning.
+async fn enrich_created(
+    connection: &AnkiConnection,
+    state: &Mutex<AppState>,
+    player: &PlayerHandle,
+    item: &MineRequestDto,
+    note_id: u64,
+    notify: &(impl Fn(&str) + Sync),
+) -> (Option<String>, bool) {
+    let media_id = match source_media_id(state, player, item.source_id).await {
+        Ok(media_id) => media_id,
+        Err(e) => return (Some(format!("Card created, but media wasn't added: {}", e)), true),
+    };
+    if target_lacks_subtitles(player, media_id.as_deref()).await {
+        let warning = "asbplayer has no subtitles loaded on the loaded video — card created \
+                       without audio/screenshot";
+        return (Some(warning.to_string()), true);
+    }
+    let record_secs = cue_duration_secs(item.timestamp_secs, item.timestamp_end_secs);
+    match enrich_and_verify(
+        connection,
+        player,
+        note_id,
+        media_id,
+        item.timestamp_secs,
+        item.timestamp_label.clone(),
+        record_secs,
+        notify,
+    )
+    .await
+    {
+        Ok(()) => (None, false),
+        Err(e) => (Some(format!("Card created, but media wasn't added: {}", e)), true),
+    }
+}
+
+#[tauri::command]
+pub async fn mine_term(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    player: State<'_, PlayerHandle>,
+    term: String,
+    surface: String,
+    sentence: String,
+    timestamp_secs: Option<f32>,
+    timestamp_end_secs: Option<f32>,
+    timestamp_label: Option<String>,
+    source_id: u32,
+    via: String,
+    entry_index: Option<usize>,
+    format_name: Option<String>,
+    progress: Channel<LoadingMessage>,
+) -> Result<MineResultDto, String> {
+    let item = MineRequestDto {
+        term,
+        surface,
+        sentence,
+        timestamp_secs,
+        timestamp_end_secs,
+        timestamp_label,
+        source_id,
+        via,
+        entry_index,
+        format_name,
+    };
+    let (yomitan_url, connection) = {
+        let guard = state.lock().unwrap();
+        (guard.settings.yomitan_url.clone(), guard.settings.anki_connection())
+    };
+    let notify = |message: &str| {
+        let _ = progress.send(LoadingMessage::new(message));
+    };
+
+    notify(&format!("Rendering 「{}」 with Yomitan…", item.term));
+    let formats = yomitan::get_term_card_formats(&yomitan_url).await.map_err(|e| e.to_string())?;
+    let prepared = prepare_mine(&state, &yomitan_url, &formats, &item).await?;
+
+    notify("Creating Anki note…");
+    store_media(&connection, &prepared.media.iter().collect::<Vec<_>>()).await;
+    let note = &prepared.note;
+    let added = anki_api::add_note(
+        &connection,
+        &note.deck_name,
+        &note.model_name,
+        &note.fields,
+        &note.tags,
+    )
+    .await;
+    let response = match added {
+        Ok(response) => response,
+        Err(e) if anki_api::is_unreachable(&e) => {
+            eprintln!("AnkiConnect is unreachable, queuing 「{}」: {}", item.term, e);
+            let result = queue_mine(&item, prepared);
+            let _ = app.emit(names::ANKI_STATUS, AnkiStatus::new(false, false));
+            return Ok(result);
+        }
+        Err(e) => return Err(e.to_string()),
+    };
     let note_id = match response.error {
         None => response.result,
-        Some(err) if err.contains("duplicate") => {
-            // No enrichment: a duplicate returns no note id to target.
-            return Ok(MineResultDto {
-                status: "duplicate".to_string(),
-                via,
-                warning: None,
-                note_id: None,
-                media_missing: false,
-            });
-        }
+        Some(err) if err.contains("duplicate") => return Ok(duplicate_result(&item)),
         Some(err) => return Err(err),
     };
     if let Some(id) = note_id {
-        mined::record_mined_sentence(id, &sentence);
+        mined::record_mined_sentence(id, &item.sentence);
+    }
+
+    let (warning, media_missing) = match note_id {
+        Some(id) if item.via == "asbplayer" => {
+            enrich_created(&connection, &state, &player, &item, id, &notify).await
+        }
+        _ => (None, false),
+    };
+    Ok(MineResultDto {
+        status: "created".to_string(),
+        via: item.via,
+        warning,
+        note_id,
+        media_missing,
+    })
+}
+
+/// Batch mining: render every item with Yomitan, then create all the notes
+/// in three AnkiConnect round trips (duplicate pre-check, media, add) instead
+/// of several per note. asbplayer enrichment still runs note by note, as each
+/// records its own cue. Outcomes come back in `items` order.
+#[tauri::command]
+pub async fn mine_terms(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    player: State<'_, PlayerHandle>,
+    items: Vec<MineRequestDto>,
+    progress: Channel<MineProgress>,
+) -> Result<Vec<MineBatchItemDto>, String> {
+    let (yomitan_url, connection, cancel) = {
+        let guard = state.lock().unwrap();
+        guard.mining_cancel.store(false, Ordering::Relaxed);
+        (
+            guard.settings.yomitan_url.clone(),
+            guard.settings.anki_connection(),
+            Arc::clone(&guard.mining_cancel),
+        )
+    };
+    let notify = |index: Option<usize>, message: String| {
+        let _ = progress.send(MineProgress { index, message });
+    };
+    let mut outcomes: Vec<MineBatchItemDto> =
+        items.iter().map(|_| MineBatchItemDto::default()).collect();
+
+    let formats = yomitan::get_term_card_formats(&yomitan_url).await.map_err(|e| e.to_string())?;
+    let mut prepared = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        notify(Some(index), format!("Rendering 「{}」 with Yomitan…", item.term));
+        match prepare_mine(&state, &yomitan_url, &formats, item).await {
+            Ok(mine) => prepared.push((index, mine)),
+            Err(e) => outcomes[index].error = Some(e),
+        }
+    }
+    if prepared.is_empty() {
+        return Ok(outcomes);
+    }
+
+    notify(None, format!("Creating {} Anki notes…", prepared.len()));
+    let notes: Vec<NewNote> = prepared.iter().map(|(_, mine)| mine.note.clone()).collect();
+    let checks = match anki_api::can_add_notes(&connection, &notes).await {
+        Ok(checks) => checks,
+        Err(e) if anki_api::is_unreachable(&e) => {
+            queue_batch(&app, &items, &mut outcomes, prepared, &e);
+            return Ok(outcomes);
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    // Notes Anki would refuse take its reason now, so their media is never uploaded.
+    let mut addable = Vec::new();
+    for ((index, mine), check) in prepared.into_iter().zip(checks) {
+        match check {
+            Ok(()) => addable.push((index, mine)),
+            Err(err) => outcomes[index] = refused(&items[index], err),
+        }
+    }
+    if addable.is_empty() {
+        return Ok(outcomes);
     }
 
-    // Enrichment failures don't undo the mine (the note exists) — warn instead.
-    let mut warning = None;
-    let mut media_missing = false;
-    if via == "asbplayer" {
-        if let Some(id) = note_id {
-            if target_lacks_subtitles(&player, media_id.as_deref()).await {
-                warning = Some(
-                    "asbplayer has no subtitles loaded on the loaded video — card created without \
-                     audio/screenshot"
-                        .to_string(),
-                );
-                media_missing = true;
-            } else {
-                let record_secs = cue_duration_secs(timestamp_secs, timestamp_end_secs);
-                if let Err(e) = enrich_and_verify(
-                    &player,
-                    id,
-                    media_id,
-                    timestamp_secs,
-                    timestamp_label,
-                    record_secs,
-                    &progress,
-                )
-                .await
-                {
-                    warning = Some(format!("Card created, but media wasn't added: {}", e));
-                    media_missing = true;
+    let mut filenames = HashSet::new();
+    let media: Vec<&QueuedMedia> = addable
+        .iter()
+        .flat_map(|(_, mine)| &mine.media)
+        .filter(|media| filenames.insert(media.filename.as_str()))
+        .collect();
+    store_media(&connection, &media).await;
+    let notes: Vec<NewNote> = addable.iter().map(|(_, mine)| mine.note.clone()).collect();
+    let added = match anki_api::add_notes(&connection, &notes).await {
+        Ok(added) => added,
+        Err(e) if anki_api::is_unreachable(&e) => {
+            queue_batch(&app, &items, &mut outcomes, addable, &e);
+            return Ok(outcomes);
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut to_enrich = Vec::new();
+    let mut added = added.into_iter();
+    for (index, _) in &addable {
+        let item = &items[*index];
+        match added.next().unwrap_or_else(|| Err("no response to addNote".to_string())) {
+            Ok(note_id) => {
+                mined::record_mined_sentence(note_id, &item.sentence);
+                outcomes[*index].result = Some(MineResultDto {
+                    status: "created".to_string(),
+                    via: item.via.clone(),
+                    warning: None,
+                    note_id: Some(note_id),
+                    media_missing: false,
+                });
+                if item.via == "asbplayer" {
+                    to_enrich.push((*index, note_id));
                 }
             }
+            Err(err) => outcomes[*index] = refused(item, err),
+        }
+    }
+
+    for (index, note_id) in to_enrich {
+        let (warning, media_missing) = if cancel.load(Ordering::Relaxed) {
+            let warning = "Card created; mining was cancelled before audio/screenshot were added";
+            (Some(warning.to_string()), true)
+        } else {
+            let notify_item = |message: &str| notify(Some(index), message.to_string());
+            enrich_created(&connection, &state, &player, &items[index], note_id, &notify_item).await
+        };
+        if let Some(result) = outcomes[index].result.as_mut() {
+            result.warning = warning;
+            result.media_missing = media_missing;
         }
     }
+    Ok(outcomes)
+}
+
+/// Stop a running `mine_terms` between items; notes it already created stay.
+#[tauri::command]
+pub fn cancel_mining(state: State<'_, Mutex<AppState>>) {
+    state.lock().unwrap().mining_cancel.store(true, Ordering::Relaxed);
+}
+
+/// Queued mines Anki refused, with its reason, in the order they were made.
+#[tauri::command]
+pub fn get_failed_mines() -> Vec<FailedMineDto> {
+    mine_queue::failed_mines()
+        .into_iter()
+        .map(|mine| FailedMineDto { term: mine.term, error: mine.error.unwrap_or_default() })
+        .collect()
+}
 
-    Ok(MineResultDto { status: "created".to_string(), via, warning, note_id, media_missing })
+/// Hand the failed mines back to the queue; the next Anki poll flushes them.
+#[tauri::command]
+pub fn retry_failed_mines() {
+    mine_queue::retry_failed();
+}
+
+/// Drop the failed mines for good.
+#[tauri::command]
+pub fn discard_failed_mines() {
+    mine_queue::discard_failed();
 }
 
 /// Re-run asbplayer enrichment on a note whose media never landed.
@@ -178,21 +533,26 @@ pub async fn retry_mine_media(
     timestamp_secs: Option<f32>,
     timestamp_end_secs: Option<f32>,
     timestamp_label: Option<String>,
+    source_id: u32,
     progress: Channel<LoadingMessage>,
 ) -> Result<(), String> {
-    let media_id = { state.lock().unwrap().file.asbplayer_media_id.clone() };
+    let connection = state.lock().unwrap().settings.anki_connection();
+    let media_id = source_media_id(&state, &player, source_id).await?;
     if target_lacks_subtitles(&player, media_id.as_deref()).await {
         return Err("asbplayer still has no subtitles loaded on the loaded video".to_string());
     }
     let record_secs = cue_duration_secs(timestamp_secs, timestamp_end_secs);
     enrich_and_verify(
+        &connection,
         &player,
         note_id,
         media_id,
         timestamp_secs,
         timestamp_label,
         record_secs,
-        &progress,
+        &|message: &str| {
+            let _ = progress.send(LoadingMessage::new(message));
+        },
     )
     .await
 }
@@ -214,8 +574,11 @@ fn cue_duration_secs(start: Option<f32>, end: Option<f32>) -> f32 {
 }
 
 /// The note's current field values, or `None` when AnkiConnect can't serve it.
-async fn snapshot_fields(note_id: u64) -> Option<std::collections::HashMap<String, String>> {
-    let notes = anki_api::get_notes(vec![note_id]).await.ok()?;
+async fn snapshot_fields(
+    connection: &AnkiConnection,
+    note_id: u64,
+) -> Option<std::collections::HashMap<String, String>> {
+    let notes = anki_api::get_notes(connection, vec![note_id]).await.ok()?;
     let note = notes.into_iter().next()?;
     Some(note.fields.into_iter().map(|(name, field)| (name, field.value)).collect())
 }
@@ -225,16 +588,17 @@ async fn snapshot_fields(note_id: u64) -> Option<std::collections::HashMap<Strin
 /// note update happen asynchronously afterwards. Verification also catches a
 /// pre-v1.20 extension ignoring `noteId` and updating the last-added note.
 async fn enrich_and_verify(
+    connection: &AnkiConnection,
     player: &PlayerHandle,
     note_id: u64,
     media_id: Option<String>,
     timestamp_secs: Option<f32>,
     timestamp_label: Option<String>,
     record_secs: f32,
-    progress: &Channel<LoadingMessage>,
+    notify: &(impl Fn(&str) + Sync),
 ) -> Result<(), String> {
-    let _ = progress.send(LoadingMessage::new("Adding audio & screenshot via asbplayer…"));
-    let baseline = snapshot_fields(note_id).await;
+    notify("Adding audio & screenshot via asbplayer…");
+    let baseline = snapshot_fields(connection, note_id).await;
 
     if let Some(secs) = timestamp_secs {
         player.seek(secs, timestamp_label.unwrap_or_default(), media_id.clone()).await?;
@@ -245,13 +609,13 @@ async fn enrich_and_verify(
     // AnkiConnect hiccup on the baseline read: enrichment ran, verification can't.
     let Some(baseline) = baseline else { return Ok(()) };
 
-    let _ = progress.send(LoadingMessage::new("Waiting for asbplayer to record the cue…"));
+    notify("Waiting for asbplayer to record the cue…");
     tokio::time::sleep(Duration::from_secs_f32(record_secs) + RECORD_BUFFER).await;
 
-    let _ = progress.send(LoadingMessage::new("Verifying the media landed in Anki…"));
+    notify("Verifying the media landed in Anki…");
     let deadline = std::time::Instant::now() + MEDIA_VERIFY_TIMEOUT;
     loop {
-        if snapshot_fields(note_id).await.is_some_and(|now| now != baseline) {
+        if snapshot_fields(connection, note_id).await.is_some_and(|now| now != baseline) {
             return Ok(());
         }
         if std::time::Instant::now() >= deadline {
@@ -265,16 +629,17 @@ async fn enrich_and_verify(
 
 /// Open Anki's browser on recent adds with the mined note's card selected.
 #[tauri::command]
-pub async fn open_in_anki(note_id: u64) -> Result<(), String> {
-    let response = anki_api::gui_browse(&format!("added:1 OR nid:{}", note_id))
+pub async fn open_in_anki(state: State<'_, Mutex<AppState>>, note_id: u64) -> Result<(), String> {
+    let connection = state.lock().unwrap().settings.anki_connection();
+    let response = anki_api::gui_browse(&connection, &format!("added:1 OR nid:{}", note_id))
         .await
         .map_err(|e| format!("AnkiConnect is unreachable: {}", e))?;
     if let Some(err) = response.error {
         return Err(err);
     }
-    if let Ok(notes) = anki_api::get_notes(vec![note_id]).await {
+    if let Ok(notes) = anki_api::get_notes(&connection, vec![note_id]).await {
         if let Some(card) = notes.first().and_then(|n| n.cards.first()) {
-            let _ = anki_api::gui_select_card(*card).await;
+            let _ = anki_api::gui_select_card(&connection, *card).await;
         }
     }
     Ok(())
@@ -282,9 +647,13 @@ pub async fn open_in_anki(note_id: u64) -> Result<(), String> {
 
 /// Open Anki's browser on a set of notes (post-batch review).
 #[tauri::command]
-pub async fn open_notes_in_anki(note_ids: Vec<u64>) -> Result<(), String> {
+pub async fn open_notes_in_anki(
+    state: State<'_, Mutex<AppState>>,
+    note_ids: Vec<u64>,
+) -> Result<(), String> {
+    let connection = state.lock().unwrap().settings.anki_connection();
     let ids = note_ids.iter().map(u64::to_string).collect::<Vec<_>>().join(",");
-    let response = anki_api::gui_browse(&format!("nid:{}", ids))
+    let response = anki_api::gui_browse(&connection, &format!("nid:{}", ids))
         .await
         .map_err(|e| format!("AnkiConnect is unreachable: {}", e))?;
     match response.error {
@@ -311,11 +680,18 @@ async fn wait_for_seek_confirmation(player: &PlayerHandle, secs: f32) {
 /// AnkiConnect still returns the cached sentences.
 #[tauri::command]
 pub async fn get_mined_state(state: State<'_, Mutex<AppState>>) -> Result<MinedStateDto, String> {
-    let mappings = { state.lock().unwrap().settings.anki_model_mappings.clone() };
+    let (connection, mappings, vocab_query) = {
+        let guard = state.lock().unwrap();
+        (
+            guard.settings.anki_connection(),
+            guard.settings.anki_model_mappings.clone(),
+            guard.settings.anki_vocab_query.clone(),
+        )
+    };
     let (added_terms, added_sentences) =
-        mined::get_recently_added(&mappings).await.unwrap_or_default();
+        mined::get_recently_added(&connection, &mappings, &vocab_query).await.unwrap_or_default();
 
-    let mut mined_sentences = mined::mined_sentences_pruned().await;
+    let mut mined_sentences = mined::mined_sentences_pruned(&connection).await;
     mined_sentences.extend(added_sentences);
     Ok(MinedStateDto { added_terms, mined_sentences })
 }
@@ -1,7 +1,10 @@
 //! Player commands (contracts/commands.md "Player"): thin wrappers over the
 //! `PlayerHandle` channel — the player is owned by its task, never this lock.
 
-use std::sync::Mutex;
+use std::{
+    path::Path,
+    sync::Mutex,
+};
 
 use tauri::State;
 use yomine::persistence;
@@ -14,17 +17,49 @@ use crate::{
 
 /// Seek the active player to `seconds` (prefers MPV, else the WebSocket client).
 /// Errors if no player is connected (the handle relays the player's own error).
+/// `source_id` is the sentence's file, which picks the asbplayer video of a
+/// multi-file session.
 #[tauri::command]
 pub async fn seek_timestamp(
     state: State<'_, Mutex<AppState>>,
     player: State<'_, PlayerHandle>,
     seconds: f32,
     label: String,
+    source_id: u32,
 ) -> Result<(), String> {
-    let media_id = { state.lock().unwrap().file.asbplayer_media_id.clone() };
+    let media_id = source_media_id(&state, &player, source_id).await?;
     player.seek(seconds, label, media_id).await
 }
 
+/// The asbplayer media a sentence of `source_id` plays in. A single file uses
+/// the media it was loaded from (or asbplayer's active tab); a multi-file
+/// session looks for the video whose loaded subtitle file is that source, and
+/// errors when asbplayer has none open rather than seeking another episode.
+/// Without asbplayer (MPV mode) there is nothing to pick.
+pub(crate) async fn source_media_id(
+    state: &Mutex<AppState>,
+    player: &PlayerHandle,
+    source_id: u32,
+) -> Result<Option<String>, String> {
+    let (media_id, source) = {
+        let guard = state.lock().unwrap();
+        (guard.file.asbplayer_media_id.clone(), guard.file.sources.get(source_id as usize).cloned())
+    };
+    let Some(source) = source else { return Ok(media_id) };
+    let Ok(bound) = player.get_bound_media().await else { return Ok(None) };
+    let stem = Path::new(&source.original_file).file_stem();
+    bound
+        .into_iter()
+        .find(|media| {
+            media
+                .loaded_subtitles
+                .iter()
+                .any(|track| Path::new(&track.file_name).file_stem() == stem)
+        })
+        .map(|media| Some(media.id))
+        .ok_or_else(|| format!("「{}」 isn't open in asbplayer", source.title))
+}
+
 /// Current player connectivity/mode. Also pushed via the `player-status` event.
 #[tauri::command]
 pub async fn get_player_status(player: State<'_, PlayerHandle>) -> Result<PlayerStatus, String> {
@@ -69,7 +104,8 @@ pub enum MpvLaunchOutcome {
 
 /// Launch mpv on the IPC endpoint `MpvManager` polls (issue #89); detection
 /// flips the mode to "mpv" within ~1s. Refuses while an mpv is already
-/// connected — a second instance would fight over the socket.
+/// connected — a second instance would fight over the socket. Launching the
+/// loaded MKV itself selects the subtitle track Yomine is reading.
 #[tauri::command]
 pub async fn launch_mpv(
     state: State<'_, Mutex<AppState>>,
@@ -79,12 +115,25 @@ pub async fn launch_mpv(
     if player.status().await?.mpv_connected {
         return Err("MPV is already connected".to_string());
     }
-    let mpv_path = { state.lock().unwrap().settings.mpv_path.clone() };
-    match std::process::Command::new(&mpv_path)
-        .arg(format!("--input-ipc-server={}", yomine::mpv::default_mpv_endpoint()))
-        .arg(&video_path)
-        .spawn()
-    {
+    let (mpv_path, mkv_track) = {
+        let guard = state.lock().unwrap();
+        let mkv_track = guard
+            .file
+            .source_file
+            .as_ref()
+            .filter(|f| f.original_file == video_path)
+            .and_then(|f| f.mkv_track);
+        (guard.settings.mpv_path.clone(), mkv_track)
+    };
+    let mut command = std::process::Command::new(&mpv_path);
+    command.arg(format!("--input-ipc-server={}", yomine::mpv::default_mpv_endpoint()));
+    if let Some(number) = mkv_track {
+        let tracks = yomine::mkv::subtitle_tracks(&video_path).unwrap_or_default();
+        if let Some(track) = tracks.iter().find(|t| t.number == number) {
+            command.arg(format!("--sid={}", track.sid));
+        }
+    }
+    match command.arg(&video_path).spawn() {
         Ok(mut child) => {
             // Reap in the background so an exited mpv never lingers as a zombie.
             tauri::async_runtime::spawn_blocking(move || {
@@ -18,7 +18,7 @@ pub async fn get_setup_status(
     state: State<'_, Mutex<AppState>>,
     player: State<'_, PlayerHandle>,
 ) -> Result<SetupStatus, String> {
-    let (tools_loaded, has_field_mapping, frequency_dict_count, yomitan_url) = {
+    let (tools_loaded, has_field_mapping, frequency_dict_count, yomitan_url, anki_connection) = {
         let guard = state.lock().unwrap();
         let tools_loaded = guard.language_tools.is_some();
         let has_field_mapping = !guard.settings.anki_model_mappings.is_empty();
@@ -26,11 +26,17 @@ pub async fn get_setup_status(
             .language_tools
             .as_ref()
             .map_or(0, |t| t.frequency_manager.get_dictionary_names().len());
-        (tools_loaded, has_field_mapping, frequency_dict_count, guard.settings.yomitan_url.clone())
+        (
+            tools_loaded,
+            has_field_mapping,
+            frequency_dict_count,
+            guard.settings.yomitan_url.clone(),
+            guard.settings.anki_connection(),
+        )
     };
     let has_frequency_dict = frequency_dict_count > 0;
 
-    let anki_connected = anki::api::get_version().await.is_ok();
+    let anki_connected = anki::api::get_version(&anki_connection).await.is_ok();
     let yomitan_connected = yomine::yomitan::get_version(&yomitan_url).await.is_ok();
     let player = player.status().await?;
     let player_connected = player.mpv_connected || player.ws_clients > 0;
@@ -1,10 +1,7 @@
 //! Wire DTOs (data-model.md). Domain types serialize directly when cheap; a
 //! DTO exists only where the domain type is awkward on the wire.
 
-use std::collections::{
-    HashMap,
-    HashSet,
-};
+use std::collections::HashSet;
 
 use serde::{
     Deserialize,
@@ -12,7 +9,9 @@ use serde::{
 };
 use wana_kana::ConvertJapanese;
 use yomine::{
+    anki::comprehensibility::SentenceTermIndex,
     core::models::{
+        PageLocation,
         Sentence,
         SourceFile,
         Term,
@@ -24,9 +23,10 @@ use yomine::{
     },
 };
 
-/// `mine_term` outcome. `status`: `"created"` | `"duplicate"`; `warning` =
-/// note created but asbplayer enrichment failed; `media_missing` = enrichment
-/// verifiably didn't land (drives the retry chip).
+/// `mine_term` outcome. `status`: `"created"` | `"duplicate"` | `"queued"`
+/// (Anki unreachable, see `anki::mine_queue`); `warning` = note created but
+/// asbplayer enrichment failed, or why it was queued; `media_missing` =
+/// enrichment verifiably didn't land (drives the retry chip).
 #[derive(Serialize, Clone)]
 pub struct MineResultDto {
     pub status: String,
@@ -36,6 +36,31 @@ pub struct MineResultDto {
     pub media_missing: bool,
 }
 
+/// One term of a `mine_terms` batch; the fields are `mine_term`'s arguments.
+#[derive(Deserialize, Clone)]
+pub struct MineRequestDto {
+    pub term: String,
+    pub surface: String,
+    pub sentence: String,
+    pub timestamp_secs: Option<f32>,
+    pub timestamp_end_secs: Option<f32>,
+    pub timestamp_label: Option<String>,
+    /// The sentence's file in a multi-file session (its translation and video).
+    #[serde(default)]
+    pub source_id: u32,
+    pub via: String,
+    pub entry_index: Option<usize>,
+    pub format_name: Option<String>,
+}
+
+/// A `mine_terms` outcome, in request order: the mine's result or why it
+/// failed; neither when the batch was cancelled before reaching it.
+#[derive(Serialize, Clone, Default)]
+pub struct MineBatchItemDto {
+    pub result: Option<MineResultDto>,
+    pub error: Option<String>,
+}
+
 /// Already-mined state (issue #3): `added:1` terms + normalized sentence keys.
 #[derive(Serialize, Clone)]
 pub struct MinedStateDto {
@@ -43,6 +68,13 @@ pub struct MinedStateDto {
     pub mined_sentences: Vec<String>,
 }
 
+/// A queued mine Anki refused, for the Anki menu's failed list.
+#[derive(Serialize, Clone)]
+pub struct FailedMineDto {
+    pub term: String,
+    pub error: String,
+}
+
 #[derive(Serialize, Clone, PartialEq)]
 pub struct YomitanStatusDto {
     pub reachable: bool,
@@ -105,8 +137,10 @@ pub enum SegmentKnowledge {
 
 impl SegmentKnowledge {
     /// `in_anki` = the Anki filter matched the lemma; within that, comprehension
-    /// encodes the card interval (0 = unreviewed, ≥1 = past the known interval).
-    /// Outside Anki, comprehension 1.0 means ignored — user-declared known.
+    /// encodes the card state (0 = new card, ≥1 = past the known interval; a card
+    /// in (re)learning stays below that). Suspended cards set to count as unknown
+    /// or ignored never match. Outside Anki, comprehension 1.0 means ignored —
+    /// user-declared known.
     fn classify(in_anki: bool, comprehension: f32) -> Self {
         match (in_anki, comprehension) {
             (true, c) if c >= 1.0 => Self::Mature,
@@ -121,25 +155,35 @@ impl SegmentKnowledge {
 /// One term occurrence as `(start, end, knowledge)` byte offsets.
 pub type TermSpan = (usize, usize, SegmentKnowledge);
 
-/// Group every term occurrence by sentence id. Expressions span their full
-/// segment — must match `isTermSeg` in `SentenceView.svelte`.
+/// Every term occurrence, indexed by sentence id, off the shared
+/// [`SentenceTermIndex`]. Expressions span their full segment — must match
+/// `isTermSeg` in `SentenceView.svelte`.
 pub fn term_spans_by_sentence(
     terms: &[Term],
+    index: &SentenceTermIndex,
     anki_lemmas: &HashSet<String>,
-) -> HashMap<usize, Vec<TermSpan>> {
-    let mut map: HashMap<usize, Vec<TermSpan>> = HashMap::new();
-    for term in terms {
-        let knowledge =
-            SegmentKnowledge::classify(anki_lemmas.contains(&term.lemma_form), term.comprehension);
-        let len = match term.part_of_speech {
-            POS::Expression | POS::NounExpression => term.full_segment.len(),
-            _ => term.surface_form.len(),
-        };
-        for (sentence_id, start) in &term.sentence_references {
-            map.entry(*sentence_id).or_default().push((*start, start + len, knowledge));
-        }
-    }
-    map
+    sentence_count: usize,
+) -> Vec<Vec<TermSpan>> {
+    let spans: Vec<(usize, SegmentKnowledge)> = terms
+        .iter()
+        .map(|term| {
+            let len = match term.part_of_speech {
+                POS::Expression | POS::NounExpression => term.full_segment.len(),
+                _ => term.surface_form.len(),
+            };
+            let known = anki_lemmas.contains(&term.lemma_form);
+            (len, SegmentKnowledge::classify(known, term.comprehension))
+        })
+        .collect();
+    (0..sentence_count)
+        .map(|sentence_id| {
+            index
+                .occurrences(sentence_id)
+                .iter()
+                .map(|&(idx, start)| (start, start + spans[idx].0, spans[idx].1))
+                .collect()
+        })
+        .collect()
 }
 
 /// Seconds (for seeking, FR-008) + human-readable labels (for display). Replaces
@@ -159,7 +203,13 @@ pub struct SentenceDto {
     pub text: String,
     pub segments: Vec<SegmentDto>,
     pub timestamp: Option<TimeStampDto>,
+    /// Manga (mokuro) sources: the page + block, shown where a timestamp would be.
+    pub page: Option<PageLocation>,
     pub comprehension: f32,
+    /// The aligned secondary-subtitle line, when a translation file is loaded.
+    pub translation: Option<String>,
+    /// ASS/SSA actor of the line, when the script names one.
+    pub speaker: Option<String>,
 }
 
 impl SentenceDto {
@@ -196,7 +246,10 @@ impl SentenceDto {
             text: s.text.clone(),
             segments,
             timestamp,
+            page: s.page,
             comprehension: s.comprehension,
+            translation: s.translation.clone(),
+            speaker: s.speaker.clone(),
         }
     }
 }
@@ -205,6 +258,9 @@ impl SentenceDto {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FileLoadResult {
     pub source_file: SourceFile,
+    /// A multi-file session's files in order — `SentenceDto::source_id` indexes
+    /// this. Empty for a single file.
+    pub sources: Vec<SourceFile>,
     pub terms: Vec<Term>,
     pub sentences: Vec<SentenceDto>,
     pub file_comprehension: f32,
@@ -289,6 +345,8 @@ pub struct AnalysisPreview {
     /// frequency-descending list) for the UI's Bottom 250 radio.
     pub bottom: Vec<AnalysisPreviewEntry>,
     pub total: usize,
+    /// Mokuro volumes among the analyzed files (0 for non-manga corpora).
+    pub manga_volumes: usize,
 }
 
 /// Aggregated readiness for the setup checklist/banner (`get_setup_status`).
@@ -419,6 +477,8 @@ impl From<yomine::websocket::BoundMedia> for BoundMediaDto {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     fn term(surface: &str, pos: POS, comprehension: f32, start: usize) -> Term {
@@ -436,6 +496,7 @@ mod tests {
             sentence_references: vec![(1, start)],
             comprehension,
             jlpt_level: None,
+            priority: 0.0,
         }
     }
 
@@ -459,7 +520,11 @@ mod tests {
                 ("クル".to_string(), POS::Verb, 24, 30),
             ],
             timestamp: None,
+            page: None,
             comprehension: 0.0,
+            ruby: Vec::new(),
+            translation: None,
+            speaker: None,
         };
         let terms = vec![
             term("気になる", POS::Expression, 0.2, 0), // not in Anki → Unknown
@@ -470,8 +535,9 @@ mod tests {
         ];
         let anki: HashSet<String> = ["気", "人", "来る"].iter().map(|s| s.to_string()).collect();
 
-        let spans = term_spans_by_sentence(&terms, &anki);
-        let dto = SentenceDto::from_sentence(&sentence, &spans[&1]);
+        let index = SentenceTermIndex::new(&terms);
+        let spans = term_spans_by_sentence(&terms, &index, &anki, 2);
+        let dto = SentenceDto::from_sentence(&sentence, &spans[1]);
 
         let knowledge: Vec<Option<SegmentKnowledge>> =
             dto.segments.iter().map(|s| s.knowledge).collect();
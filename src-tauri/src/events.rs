@@ -5,11 +5,13 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use yomine::anki::mine_queue;
 
 /// Event-name constants. Frontend `listen`s to these; backend `app.emit`s them.
 pub mod names {
     pub const LANGUAGE_TOOLS_STATUS: &str = "language-tools-status";
     pub const ANKI_STATUS: &str = "anki-status";
+    pub const MINE_QUEUE_FLUSHED: &str = "mine-queue-flushed";
     pub const YOMITAN_STATUS: &str = "yomitan-status";
     pub const PLAYER_STATUS: &str = "player-status";
     pub const TERMS_REFRESHED: &str = "terms-refreshed";
@@ -41,6 +43,14 @@ impl LoadingMessage {
     }
 }
 
+/// `mine_terms` progress channel: what the batch is doing, and to which of
+/// its items (`None` while it works on all of them at once).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MineProgress {
+    pub index: Option<usize>,
+    pub message: String,
+}
+
 /// Tools load lifecycle. Serializes to `"loading"` / `"ready"` / `{ "error": "…" }`
 /// (externally-tagged + lowercase variant names match the TS union in data-model.md).
 #[derive(Serialize, Deserialize, Clone)]
@@ -55,6 +65,31 @@ pub enum LanguageToolsStatus {
 pub struct AnkiStatus {
     pub connected: bool,
     pub fetching: bool,
+    /// Mines waiting in the offline queue for Anki to come back.
+    pub queued: usize,
+    /// Queued mines Anki refused, held until the user retries or discards them.
+    pub failed: usize,
+}
+
+impl AnkiStatus {
+    pub fn new(connected: bool, fetching: bool) -> Self {
+        Self {
+            connected,
+            fetching,
+            queued: mine_queue::queued_count(),
+            failed: mine_queue::failed_mines().len(),
+        }
+    }
+}
+
+/// `mine-queue-flushed` payload: offline mines that reached Anki, by term.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MineQueueFlushed {
+    pub added: Vec<String>,
+    pub duplicates: Vec<String>,
+    /// `「term」: error` for mines Anki refused; they stay queued as failed.
+    pub rejected: Vec<String>,
+    pub remaining: usize,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
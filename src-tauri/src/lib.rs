@@ -30,6 +30,7 @@ pub fn run() {
             commands::lifecycle::get_pos_catalog,
             commands::lifecycle::get_settings,
             commands::lifecycle::save_settings,
+            commands::lifecycle::set_comprehension_model,
             commands::lifecycle::get_text_filter_presets,
             commands::lifecycle::test_text_filters,
             commands::lifecycle::open_data_folder,
@@ -37,10 +38,16 @@ pub fn run() {
             commands::lifecycle::export_theme_file,
             commands::lifecycle::import_theme_file,
             commands::file::open_file_dialog,
+            commands::file::open_files_dialog,
             commands::file::get_epub_chapters,
+            commands::file::get_mkv_tracks,
+            commands::file::get_subtitle_styles,
+            commands::file::export_terms,
+            commands::file::get_target_sentences,
             commands::file::open_video_dialog,
             commands::file::open_executable_dialog,
             commands::file::process_file,
+            commands::file::process_files,
             commands::file::get_terms,
             commands::file::refresh_terms,
             commands::file::reload_current_file,
@@ -74,6 +81,11 @@ pub fn run() {
             commands::player::get_asbplayer_media,
             commands::player::launch_mpv,
             commands::mining::mine_term,
+            commands::mining::mine_terms,
+            commands::mining::cancel_mining,
+            commands::mining::get_failed_mines,
+            commands::mining::retry_failed_mines,
+            commands::mining::discard_failed_mines,
             commands::mining::retry_mine_media,
             commands::mining::get_mined_state,
             commands::mining::get_yomitan_status,
@@ -31,6 +31,9 @@ pub const KNOWLEDGE_SUMMARY_CACHE: &str = "knowledge_summary_cache.json";
 #[derive(Default)]
 pub struct FileData {
     pub source_file: Option<SourceFile>,
+    /// The episodes/volumes of a multi-file session, indexed by each
+    /// sentence's `source_id`; empty for a single file.
+    pub sources: Vec<SourceFile>,
     /// The minable (unknown) terms shown in the table.
     pub terms: Vec<Term>,
     /// All terms as the pipeline returned them — a live Anki refresh
@@ -56,6 +59,8 @@ pub struct AppState {
     pub settings: SettingsData,
     pub file: FileData,
     pub analysis_cancel: Arc<AtomicBool>,
+    /// Stops a running `mine_terms` batch between items.
+    pub mining_cancel: Arc<AtomicBool>,
     /// Kept for `export_analysis`; only a preview DTO is sent to the UI.
     pub last_analysis: Option<FrequencyAnalysisResult>,
     /// Set when a knowledge-summary input changes; the background task
@@ -77,6 +82,7 @@ impl AppState {
             settings,
             file: FileData::default(),
             analysis_cancel: Arc::new(AtomicBool::new(false)),
+            mining_cancel: Arc::new(AtomicBool::new(false)),
             last_analysis: None,
             knowledge_dirty: Arc::new(AtomicBool::new(true)),
             knowledge_summary: {
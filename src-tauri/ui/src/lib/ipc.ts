@@ -32,6 +32,8 @@ export interface Term {
 	comprehension: number;
 	/** `null` when the lemma isn't in the JLPT corpus. */
 	jlpt_level: JlptLevel | null;
+	/** Mining priority 0–1 (`core::priority`); the table's default order. */
+	priority: number;
 }
 
 export interface SegmentDto {
@@ -64,10 +66,46 @@ export interface SentenceDto {
 	text: string;
 	segments: SegmentDto[];
 	timestamp: TimeStampDto | null;
+	/** Manga (mokuro) sources: where the bubble sits, shown in place of a timestamp. */
+	page: PageLocation | null;
 	comprehension: number;
-}
-
-export type SourceFileType = 'SRT' | 'SSA' | 'TXT' | 'EPUB' | { Other: string };
+	/** The aligned line from the loaded translation subtitles, if any. */
+	translation: string | null;
+	/** ASS/SSA actor of the line, when the script names one. */
+	speaker: string | null;
+}
+
+/** Mirrors `PageLocation` (core/models.rs): 1-based page, 0-based block on it. */
+export interface PageLocation {
+	page: number;
+	block: number;
+}
+
+export type SourceFileType =
+	| 'SRT'
+	| 'SSA'
+	| 'VTT'
+	| 'TTML'
+	| 'TXT'
+	| 'EPUB'
+	| 'Mokuro'
+	| 'MKV'
+	| { Other: string };
+
+/** Mirrors `TextEncoding` (core/models.rs). */
+export type TextEncoding = 'Utf8' | 'Utf16Le' | 'Utf16Be' | 'ShiftJis' | 'EucJp';
+
+/** Menu labels for the "Reopen with Encoding" submenu, in `TextEncoding::all()` order. */
+export const TEXT_ENCODINGS: { id: TextEncoding; label: string }[] = [
+	{ id: 'Utf8', label: 'UTF-8' },
+	{ id: 'Utf16Le', label: 'UTF-16LE' },
+	{ id: 'Utf16Be', label: 'UTF-16BE' },
+	{ id: 'ShiftJis', label: 'Shift-JIS' },
+	{ id: 'EucJp', label: 'EUC-JP' }
+];
+
+/** Mirrors `TextMarkup` (core/models.rs): `.txt` markup convention. */
+export type TextMarkup = 'Plain' | 'Aozora';
 
 export interface SourceFile {
 	id: number;
@@ -80,10 +118,23 @@ export interface SourceFile {
 	epub_chapters: number[] | null;
 	/** The picker's selection summary, rendered beside the title. */
 	epub_label: string | null;
+	/** Sniffed (or user-forced) text encoding; `null` for EPUBs. */
+	encoding: TextEncoding | null;
+	/** `.txt` only: Aozora Bunko ruby/annotation markup vs plain; `null` otherwise. */
+	text_markup: TextMarkup | null;
+	/** MKV only: the embedded subtitle track being read (Matroska TrackNumber). */
+	mkv_track: number | null;
+	/** Timed subtitles only: the translation subtitle file aligned onto the sentences. */
+	secondary_subtitle: string | null;
+	/** ASS/SSA only: style names dropped for this file; `null` = the settings' globs. */
+	excluded_styles: string[] | null;
 }
 
 export interface FileLoadResult {
 	source_file: SourceFile;
+	/** A multi-file session's files in order — `SentenceDto.source_id` indexes
+	 * this. Empty for a single file. */
+	sources: SourceFile[];
 	terms: Term[];
 	sentences: SentenceDto[];
 	file_comprehension: number;
@@ -146,6 +197,8 @@ export interface AnalysisPreview {
 	 * for the Top 250 / Bottom 250 radio. */
 	bottom: AnalysisPreviewEntry[];
 	total: number;
+	/** Mokuro volumes among the analyzed files. */
+	manga_volumes: number;
 }
 
 /** Export metadata + format flags (mirrors `tools::analysis::ExportOptions`).
@@ -182,6 +235,10 @@ export interface FieldMapping {
 	reading_field: string;
 	/** Sentence field for already-mined detection (issue #3); optional. */
 	sentence_field?: string | null;
+	/** Field mined cards get the sentence's aligned translation in; optional. */
+	translation_field?: string | null;
+	/** Anki search narrowing which of this type's notes count as known; optional. */
+	query?: string | null;
 }
 
 /** A note type with its fields (`core::settings::AnkiModelInfo`). `sample_note`
@@ -205,6 +262,41 @@ export interface FrequencyDictionarySetting {
 	enabled: boolean;
 }
 
+/** Mirrors `ComprehensionModel` (core/settings.rs, serde snake_case). */
+export type ComprehensionModel =
+	| 'average'
+	| 'token_weighted'
+	| 'content_words'
+	| 'character_weighted'
+	| 'whole_sentence';
+
+export const COMPREHENSION_MODELS: { id: ComprehensionModel; label: string; title: string }[] = [
+	{ id: 'average', label: 'Average', title: 'Plain mean over the distinct words' },
+	{
+		id: 'token_weighted',
+		label: 'Token-Weighted',
+		title: 'A word repeated in the line counts each time'
+	},
+	{
+		id: 'content_words',
+		label: 'Content Words Only',
+		title: 'Particles, affixes and names are left out'
+	},
+	{
+		id: 'character_weighted',
+		label: 'Character-Length Weighted',
+		title: 'Longer words weigh more'
+	},
+	{
+		id: 'whole_sentence',
+		label: 'Whole Sentence Understood',
+		title: 'Probability every content word is understood — one unknown word sinks the line'
+	}
+];
+
+/** Mirrors `SuspendedCards` (core/settings.rs, serde snake_case). */
+export type SuspendedCards = 'known' | 'unknown' | 'ignored';
+
 /** Mirrors `SentenceColoring` (core/settings.rs, serde lowercase). */
 export type SentenceColoring = 'knowledge' | 'none';
 
@@ -214,6 +306,14 @@ export type UnderlineToggles = Record<SegmentKnowledge, boolean>;
 export interface SettingsData {
 	anki_model_mappings: Record<string, FieldMapping>;
 	anki_interval: number;
+	/** Anki search all known vocabulary is limited to; '' = the whole collection. */
+	anki_vocab_query: string;
+	/** How vocab on suspended cards counts; default 'known' (by its interval). */
+	anki_suspended_cards: SuspendedCards;
+	/** AnkiConnect endpoint; default `http://localhost:8765`. */
+	anki_connect_url: string;
+	/** Sent as `key` when AnkiConnect's `apiKey` is set. */
+	anki_connect_api_key: string | null;
 	websocket_settings: { port: number };
 	frequency_weights: Record<string, FrequencyDictionarySetting>;
 	pos_filters: Record<string, boolean>;
@@ -255,6 +355,22 @@ export interface SettingsData {
 	text_filters: TextFilterSetting[];
 	/** Preset id → enabled; missing = off. */
 	text_filter_presets: Record<string, boolean>;
+	/** Merge subtitle cues that split one sentence across lines when parsing. */
+	join_split_cues: boolean;
+	/** ASS/SSA style or actor globs dropped on parse (`*sign*`, `*OP*`, …); smart-case. */
+	excluded_ass_styles: string[];
+	/** Weights of the mining priority score; 0 drops a signal. */
+	priority_weights: PriorityWeights;
+	/** How sentence (and file) comprehension weighs the terms. */
+	comprehension_model: ComprehensionModel;
+}
+
+export interface PriorityWeights {
+	frequency: number;
+	occurrences: number;
+	sentences: number;
+	jlpt: number;
+	comprehension: number;
 }
 
 export interface TextFilterSetting {
@@ -313,6 +429,19 @@ export type LanguageToolsStatus = 'loading' | 'ready' | { error: string };
 export interface AnkiStatus {
 	connected: boolean;
 	fetching: boolean;
+	/** Mines waiting in the offline queue for Anki to come back. */
+	queued: number;
+	/** Queued mines Anki refused, held until retried or discarded. */
+	failed: number;
+}
+
+/** `mine-queue-flushed`: offline mines that reached Anki, by term. */
+export interface MineQueueFlushed {
+	added: string[];
+	duplicates: string[];
+	/** `「term」: error` for mines Anki refused; they stay queued as failed. */
+	rejected: string[];
+	remaining: number;
 }
 
 export interface PlayerStatus {
@@ -365,11 +494,21 @@ export function saveSettings(settings: SettingsData): Promise<void> {
 	return invoke('save_settings', { settings });
 }
 
+/** Persists the model and rescores the loaded file; `null` when none is loaded. */
+export function setComprehensionModel(model: ComprehensionModel): Promise<FileLoadResult | null> {
+	return invoke('set_comprehension_model', { model });
+}
+
 /** Native open dialog; resolves to the chosen path or `null`. */
 export function openFileDialog(): Promise<string | null> {
 	return invoke('open_file_dialog');
 }
 
+/** Multi-select variant for sessions; empty when cancelled. */
+export function openFilesDialog(): Promise<string[]> {
+	return invoke('open_files_dialog');
+}
+
 /** One selectable chapter slice; `id` is what `processFile` takes back, `seen` = mined before. */
 export interface EpubPart {
 	id: number;
@@ -394,6 +533,61 @@ export function getEpubChapters(path: string): Promise<EpubBook> {
 	return invoke('get_epub_chapters', { path });
 }
 
+/** One subtitle track embedded in an MKV (mirrors `mkv::SubtitleTrack`). */
+export interface MkvTrack {
+	/** Matroska TrackNumber — `processFile`'s `mkvTrack`. */
+	number: number;
+	/** mpv's `--sid` for the same track. */
+	sid: number;
+	codec: string;
+	language: string;
+	name: string | null;
+	default: boolean;
+	forced: boolean;
+}
+
+/** Mirrors `SubtitleTrack::is_text`; image-based tracks (PGS/VobSub) are listed but unreadable. */
+const TEXT_MKV_CODECS = ['S_TEXT/UTF8', 'S_TEXT/ASCII', 'S_TEXT/ASS', 'S_TEXT/SSA', 'S_TEXT/WEBVTT'];
+export const isTextMkvTrack = (track: MkvTrack): boolean => TEXT_MKV_CODECS.includes(track.codec);
+
+export function getMkvTracks(path: string): Promise<MkvTrack[]> {
+	return invoke('get_mkv_tracks', { path });
+}
+
+/** Mirrors `parser::SubtitleStyle`: one ASS/SSA style of the loaded file. */
+export interface SubtitleStyle {
+	name: string;
+	/** Dialogue events using the style. */
+	lines: number;
+	/** Dropped on read — by the file's own picks, else the settings' globs. */
+	excluded: boolean;
+}
+
+/** Styles of the loaded ASS/SSA file or MKV ASS track; empty for other formats. */
+export function getSubtitleStyles(): Promise<SubtitleStyle[]> {
+	return invoke('get_subtitle_styles');
+}
+
+/** Mirrors `TargetSentence` (anki/comprehensibility.rs): a sentence-card candidate.
+ * `unknown` indexes `FileLoadResult.terms`, most frequent first. */
+export interface TargetSentence {
+	sentence_id: number;
+	unknown: number[];
+}
+
+/** Sentences of the loaded file with 1..=`maxUnknown` unknown words, best first. */
+export function getTargetSentences(maxUnknown: number): Promise<TargetSentence[]> {
+	return invoke('get_target_sentences', { maxUnknown });
+}
+
+/** Mirrors `TermExportFormat` (tools/term_export.rs). */
+export type TermExportFormat = 'Csv' | 'Json' | 'AnkiTsv';
+
+/** Native save dialog; writes the loaded file's unknown terms, returns the path or `null`. */
+export function exportTerms(format: TermExportFormat): Promise<string | null> {
+	return invoke('export_terms', { format });
+}
+
 /** Video picker for the MPV launcher (issue #89). */
 export function openVideoDialog(): Promise<string | null> {
 	return invoke('open_video_dialog');
@@ -406,16 +600,46 @@ export function openExecutableDialog(): Promise<string | null> {
 
 /** Parse + segment + filter a file; streams progress; returns the minable terms.
  * `epubChapters` = selected part ids for EPUBs (`null` = whole book);
- * `epubLabel` = the picker's human-readable selection summary. */
+ * `epubLabel` = the picker's human-readable selection summary;
+ * `encoding` / `textMarkup` force a text encoding / `.txt` markup (`null` = sniff);
+ * `mkvTrack` picks an MKV's subtitle track (`null` = the preferred Japanese one);
+ * `secondarySubtitle` = a translation subtitle file to align onto the sentences;
+ * `excludedStyles` = ASS style names to drop (`null` = the settings' globs). */
 export async function processFile(
 	path: string,
 	onProgress: (msg: LoadingMessage) => void,
 	epubChapters: number[] | null = null,
-	epubLabel: string | null = null
+	epubLabel: string | null = null,
+	encoding: TextEncoding | null = null,
+	textMarkup: TextMarkup | null = null,
+	mkvTrack: number | null = null,
+	secondarySubtitle: string | null = null,
+	excludedStyles: string[] | null = null
 ): Promise<FileLoadResult> {
 	const channel = new Channel<LoadingMessage>();
 	channel.onmessage = onProgress;
-	return invoke('process_file', { path, epubChapters, epubLabel, progress: channel });
+	return invoke('process_file', {
+		path,
+		epubChapters,
+		epubLabel,
+		encoding,
+		textMarkup,
+		mkvTrack,
+		secondarySubtitle,
+		excludedStyles,
+		progress: channel
+	});
+}
+
+/** Several files (or folders of them) as one session with terms deduplicated
+ * across files; a single file loads as with `processFile`. */
+export async function processFiles(
+	paths: string[],
+	onProgress: (msg: LoadingMessage) => void
+): Promise<FileLoadResult> {
+	const channel = new Channel<LoadingMessage>();
+	channel.onmessage = onProgress;
+	return invoke('process_files', { paths, progress: channel });
 }
 
 /** The currently loaded file, or `null` if none. */
@@ -542,9 +766,10 @@ export function importThemeFile(): Promise<string | null> {
 	return invoke('import_theme_file');
 }
 
-/** Seek the connected player (mpv or asbplayer) to a sentence timestamp. */
-export function seekTimestamp(seconds: number, label: string): Promise<void> {
-	return invoke('seek_timestamp', { seconds, label });
+/** Seek the connected player (mpv or asbplayer) to a sentence timestamp;
+ * `sourceId` is the sentence's file, which picks a session's asbplayer video. */
+export function seekTimestamp(seconds: number, label: string, sourceId: number): Promise<void> {
+	return invoke('seek_timestamp', { seconds, label, sourceId });
 }
 
 /** `not_found` = the mpv executable is missing; drives "Locate mpv…" (issue #89). */
@@ -602,10 +827,12 @@ export async function loadAsbplayerMedia(
 	});
 }
 
-/** `mine_term` outcome; `warning` = note created but enrichment failed;
- * `media_missing` = enrichment verifiably didn't land (drives the retry chip). */
+/** `mine_term` outcome; `warning` = note created but enrichment failed, or
+ * why it was queued; `media_missing` = enrichment verifiably didn't land
+ * (drives the retry chip). `queued` = Anki was unreachable, so the note waits
+ * in the offline queue. */
 export interface MineResult {
-	status: 'created' | 'duplicate';
+	status: 'created' | 'duplicate' | 'queued';
 	via: string;
 	warning: string | null;
 	note_id: number | null;
@@ -633,6 +860,8 @@ export function mineTerm(
 		timestampSecs: number | null;
 		timestampEndSecs: number | null;
 		timestampLabel: string | null;
+		/** The sentence's file in a multi-file session (`SentenceDto.source_id`). */
+		sourceId: number;
 		via: 'asbplayer' | 'direct';
 		/** Yomitan entry to build the card from (default first). */
 		entryIndex: number | null;
@@ -646,6 +875,70 @@ export function mineTerm(
 	return invoke('mine_term', { ...args, progress: channel });
 }
 
+/** One term of a `mine_terms` batch: `mineTerm`'s arguments, snake_case. */
+export interface MineRequest {
+	term: string;
+	surface: string;
+	sentence: string;
+	timestamp_secs: number | null;
+	timestamp_end_secs: number | null;
+	timestamp_label: string | null;
+	source_id: number;
+	via: 'asbplayer' | 'direct';
+	entry_index: number | null;
+	format_name: string | null;
+}
+
+/** A batch item's outcome; both `null` = cancelled before it was reached. */
+export interface MineBatchItem {
+	result: MineResult | null;
+	error: string | null;
+}
+
+/** `mine_terms` progress; `index` is the item being worked on (`null` while
+ * the batch handles all of them at once). */
+export interface MineProgress {
+	index: number | null;
+	message: string;
+}
+
+/** Batch mine: every item rendered, then the notes created in a few batched
+ * AnkiConnect requests. Outcomes come back in `items` order. */
+export function mineTerms(
+	items: MineRequest[],
+	onProgress: (msg: MineProgress) => void
+): Promise<MineBatchItem[]> {
+	const channel = new Channel<MineProgress>();
+	channel.onmessage = onProgress;
+	return invoke('mine_terms', { items, progress: channel });
+}
+
+/** Stop a running `mine_terms` between items. */
+export function cancelMining(): Promise<void> {
+	return invoke('cancel_mining');
+}
+
+/** A queued mine Anki refused, with its reason. */
+export interface FailedMine {
+	term: string;
+	error: string;
+}
+
+/** Queued mines Anki refused, in the order they were made. */
+export function getFailedMines(): Promise<FailedMine[]> {
+	return invoke('get_failed_mines');
+}
+
+/** Put the failed mines back in line for the next flush. */
+export function retryFailedMines(): Promise<void> {
+	return invoke('retry_failed_mines');
+}
+
+/** Drop the failed mines from the queue. */
+export function discardFailedMines(): Promise<void> {
+	return invoke('discard_failed_mines');
+}
+
 /** Re-run asbplayer enrichment on a media-missing note. Rejects when the note
  * is no longer Anki's newest ("update last card" can't target a specific note). */
 export function retryMineMedia(
@@ -654,6 +947,7 @@ export function retryMineMedia(
 		timestampSecs: number | null;
 		timestampEndSecs: number | null;
 		timestampLabel: string | null;
+		sourceId: number;
 	},
 	onProgress: (msg: LoadingMessage) => void
 ): Promise<void> {
@@ -723,9 +1017,10 @@ export function getPlayerStatus(): Promise<PlayerStatus> {
 	return invoke('get_player_status');
 }
 
-/** Snapshot of Anki connectivity (same hydrate rationale as getPlayerStatus). */
-export function getAnkiStatus(): Promise<AnkiStatus> {
-	return invoke('get_anki_status');
+/** Snapshot of Anki connectivity (same hydrate rationale as getPlayerStatus).
+ * `url` / `apiKey` probe a staged (unsaved) endpoint instead of the saved one. */
+export function getAnkiStatus(url?: string, apiKey?: string): Promise<AnkiStatus> {
+	return invoke('get_anki_status', { url: url ?? null, apiKey: apiKey ?? null });
 }
 
 /** `null` until the background task has produced one. One-shot hydrate so a
@@ -891,6 +1186,8 @@ export function onDragDrop(handlers: DragDropHandlers): Promise<UnlistenFn> {
 export const onLanguageToolsStatus = (cb: (s: LanguageToolsStatus) => void) =>
 	listenTo('language-tools-status', cb);
 export const onAnkiStatus = (cb: (s: AnkiStatus) => void) => listenTo('anki-status', cb);
+export const onMineQueueFlushed = (cb: (r: MineQueueFlushed) => void) =>
+	listenTo('mine-queue-flushed', cb);
 export const onYomitanStatus = (cb: (s: YomitanStatus) => void) => listenTo('yomitan-status', cb);
 export const onPlayerStatus = (cb: (s: PlayerStatus) => void) => listenTo('player-status', cb);
 export const onTermsRefreshed = (cb: (r: FileLoadResult) => void) =>
@@ -6,9 +6,16 @@ export function fileIcon(path: string): string {
 	switch (path.split('.').pop()?.toLowerCase()) {
 		case 'epub':
 			return '📖';
+		case 'mokuro':
+			return '💬';
 		case 'srt':
 		case 'ass':
 		case 'ssa':
+		case 'vtt':
+		case 'ttml':
+		case 'dfxp':
+		case 'mkv':
+		case 'mks':
 			return '🎬';
 		default:
 			return '📄';
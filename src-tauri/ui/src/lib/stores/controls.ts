@@ -9,8 +9,8 @@ export const posCatalog = writable<ipc.PosInfo[]>([]);
 export const tableSearch = writable('');
 
 export const tableSort = writable<{ field: SortField; dir: SortDir }>({
-	field: 'frequency',
-	dir: 'asc'
+	field: 'priority',
+	dir: 'desc'
 });
 
 /** POS-key → enabled; a missing key means enabled. */
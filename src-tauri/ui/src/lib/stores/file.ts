@@ -1,6 +1,7 @@
 import { derived, get, writable } from 'svelte/store';
+import { open as openDialog } from '@tauri-apps/plugin-dialog';
 import * as ipc from '$lib/ipc';
-import { lastError, overlay } from './ui';
+import { lastError, overlay, showNotice } from './ui';
 import { ensureToolsReady, languageToolsStatus } from './status';
 import { refreshMinedState } from './mining';
 import { epubChapterModalOpen } from './modals';
@@ -15,12 +16,65 @@ export const ankiFilterActive = derived(fileResult, ($f) => $f?.anki_filter_acti
 export const recentFiles = writable<ipc.RecentFileEntry[]>([]);
 
 /** Mirrors the engine's `SourceFileType::supported_extensions`. */
-const SUPPORTED_EXTENSIONS = ['srt', 'ass', 'ssa', 'txt', 'epub'];
+const SUPPORTED_EXTENSIONS = [
+	'srt',
+	'ass',
+	'ssa',
+	'vtt',
+	'ttml',
+	'dfxp',
+	'txt',
+	'epub',
+	'mokuro',
+	'mkv',
+	'mks'
+];
 export const isSupportedPath = (path: string): boolean => {
 	const ext = path.split('.').pop()?.toLowerCase();
 	return ext !== undefined && SUPPORTED_EXTENSIONS.includes(ext);
 };
 
+/** Subtitle tracks of the loaded MKV (File → Subtitle Track); empty otherwise. */
+export const mkvTracks = writable<ipc.MkvTrack[]>([]);
+
+/** Whether the loaded file is a multi-file session (per-file reopen options don't apply). */
+export const isSession = derived(fileResult, ($f) => ($f?.sources.length ?? 0) > 0);
+
+fileResult.subscribe(($f) => {
+	const path =
+		$f?.source_file.file_type === 'MKV' && $f.sources.length === 0
+			? $f.source_file.original_file
+			: null;
+	if (!path) {
+		mkvTracks.set([]);
+		return;
+	}
+	ipc.getMkvTracks(path).then(
+		(tracks) => {
+			if (get(fileResult)?.source_file.original_file === path) mkvTracks.set(tracks);
+		},
+		(err) => console.error('[yomine] mkv track listing failed', err)
+	);
+});
+
+/** ASS styles of the loaded file (File → Subtitle Styles); empty otherwise. */
+export const subtitleStyles = writable<ipc.SubtitleStyle[]>([]);
+
+fileResult.subscribe(($f) => {
+	const type = $f?.source_file.file_type;
+	if ((type !== 'SSA' && type !== 'MKV') || $f?.sources.length) {
+		subtitleStyles.set([]);
+		return;
+	}
+	const path = $f?.source_file.original_file;
+	ipc.getSubtitleStyles().then(
+		(styles) => {
+			if (get(fileResult)?.source_file.original_file === path) subtitleStyles.set(styles);
+		},
+		(err) => console.error('[yomine] subtitle style listing failed', err)
+	);
+});
+
 /** The book behind the open chapter-picker modal, or `null`. */
 export const epubPicker = writable<{ path: string; book: ipc.EpubBook } | null>(null);
 
@@ -80,10 +134,122 @@ export async function openAndProcessFile(): Promise<void> {
 	}
 }
 
+/** A folder of mokuro page JSONs (the `_ocr/<volume>/` layout) — one volume. */
+export async function openMokuroFolder(): Promise<void> {
+	try {
+		const dir = await openDialog({ directory: true });
+		if (!dir || Array.isArray(dir)) return;
+		await loadAndStore(dir);
+	} catch (err) {
+		console.error('[yomine] open dialog failed', err);
+		lastError.set({ title: 'Failed to open folder', message: String(err), detail: null });
+	}
+}
+
+async function loadSession(paths: string[]): Promise<void> {
+	if (!(await ensureToolsReady())) return;
+	try {
+		overlay.set('Processing files…');
+		const result = await ipc.processFiles(paths, (msg) => overlay.set(msg.message));
+		fileResult.set(result);
+		void refreshMinedState(true);
+		recentFiles.set(await ipc.getRecentFiles());
+	} catch (err) {
+		console.error('[yomine] session failed', err);
+		lastError.set({ title: 'Failed to open files', message: String(err), detail: null });
+	} finally {
+		overlay.set(null);
+	}
+}
+
+/** Several files (a season, book volumes) as one session, terms merged across them. */
+export async function openFilesAsSession(): Promise<void> {
+	try {
+		const paths = await ipc.openFilesDialog();
+		if (paths.length === 0) return;
+		await loadSession(paths);
+	} catch (err) {
+		console.error('[yomine] open dialog failed', err);
+		lastError.set({ title: 'Failed to open files', message: String(err), detail: null });
+	}
+}
+
+/** Every supported file below a folder as one session. */
+export async function openFolderAsSession(): Promise<void> {
+	try {
+		const dir = await openDialog({ directory: true });
+		if (!dir || Array.isArray(dir)) return;
+		await loadSession([dir]);
+	} catch (err) {
+		console.error('[yomine] open dialog failed', err);
+		lastError.set({ title: 'Failed to open folder', message: String(err), detail: null });
+	}
+}
+
+/** Pick the translation (e.g. English) subtitles for the loaded subtitle file;
+ * their cues are aligned onto its sentences by timing. */
+export async function loadTranslationSubtitles(): Promise<void> {
+	try {
+		const path = await openDialog({
+			filters: [
+				{
+					name: 'Subtitles',
+					extensions: ['srt', 'ass', 'ssa', 'vtt', 'ttml', 'dfxp', 'mkv', 'mks']
+				}
+			]
+		});
+		if (!path || Array.isArray(path)) return;
+		await reopenWith({ secondarySubtitle: path });
+	} catch (err) {
+		console.error('[yomine] open dialog failed', err);
+		lastError.set({ title: 'Failed to open file', message: String(err), detail: null });
+	}
+}
+
 export function openRecentFile(path: string): Promise<void> {
 	return loadAndStore(path);
 }
 
+/** Re-open the loaded file forcing an encoding, `.txt` markup (a wrong sniff),
+ * MKV subtitle track, translation subtitles (`null` removes them) or dropped
+ * ASS styles (`null` = back to the settings' globs); unspecified options keep
+ * their current values. */
+export async function reopenWith(options: {
+	encoding?: ipc.TextEncoding;
+	textMarkup?: ipc.TextMarkup;
+	mkvTrack?: number;
+	secondarySubtitle?: string | null;
+	excludedStyles?: string[] | null;
+}): Promise<void> {
+	const current = get(fileResult);
+	if (!current || !(await ensureToolsReady())) return;
+	try {
+		overlay.set('Reprocessing file…');
+		const result = await ipc.processFile(
+			current.source_file.original_file,
+			(msg) => overlay.set(msg.message),
+			null,
+			null,
+			options.encoding ?? current.source_file.encoding,
+			options.textMarkup ?? current.source_file.text_markup,
+			options.mkvTrack ?? current.source_file.mkv_track,
+			options.secondarySubtitle === undefined
+				? current.source_file.secondary_subtitle
+				: options.secondarySubtitle,
+			options.excludedStyles === undefined
+				? current.source_file.excluded_styles
+				: options.excludedStyles
+		);
+		fileResult.set(result);
+		void refreshMinedState(true);
+	} catch (err) {
+		console.error('[yomine] reopen failed', err);
+		lastError.set({ title: 'Failed to reopen file', message: String(err), detail: null });
+	} finally {
+		overlay.set(null);
+	}
+}
+
 export async function reloadCurrentFile(): Promise<void> {
 	if (!get(fileResult)) return;
 	try {
@@ -109,6 +275,16 @@ export async function refreshTerms(): Promise<void> {
 	}
 }
 
+/** Sentence and file comprehension (and priority) update in place. */
+export async function setComprehensionModel(model: ipc.ComprehensionModel): Promise<void> {
+	try {
+		const result = await ipc.setComprehensionModel(model);
+		if (result) fileResult.set(result);
+	} catch (err) {
+		lastError.set({ title: 'Comprehension Model', message: String(err), detail: null });
+	}
+}
+
 export async function openDataFolder(): Promise<void> {
 	try {
 		await ipc.openDataFolder();
@@ -116,3 +292,12 @@ export async function openDataFolder(): Promise<void> {
 		lastError.set({ title: 'Failed to open data folder', message: String(err), detail: null });
 	}
 }
+
+export async function exportTerms(format: ipc.TermExportFormat): Promise<void> {
+	try {
+		const path = await ipc.exportTerms(format);
+		if (path) showNotice(`Terms exported to ${path}`);
+	} catch (err) {
+		lastError.set({ title: 'Export Failed', message: String(err), detail: null });
+	}
+}
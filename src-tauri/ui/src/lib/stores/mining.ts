@@ -6,6 +6,7 @@ import * as ipc from '$lib/ipc';
 import { termKey } from '$lib/table';
 import { playerStatus } from './player';
 import { selectedTerms } from './selection';
+import { ankiStatus } from './status';
 import { lastError, showNotice } from './ui';
 
 /** Lemmas mined this session (optimistic, until the next refresh). */
@@ -28,6 +29,8 @@ export const yomitanReachable = writable(false);
 export const cardFormats = writable<ipc.CardFormat[]>([]);
 /** Seek/mine lock while asbplayer records the mined line. */
 export const playerBusy = writable(false);
+/** Queued mines Anki refused, loaded when the Anki menu opens. */
+export const failedMines = writable<ipc.FailedMine[]>([]);
 
 /** Must stay in sync with the engine's `anki::mined::normalize_sentence`. */
 export const normalizeSentence = (s: string): string => s.replace(/\s+/g, '');
@@ -75,6 +78,7 @@ async function mineOne(
 	surface: string,
 	sentence: string,
 	timestamp: ipc.TimeStampDto | null,
+	sourceId: number,
 	via: 'asbplayer' | 'direct',
 	entryIndex?: number,
 	formatName?: string,
@@ -89,6 +93,7 @@ async function mineOne(
 			timestampSecs: timestamp?.start_secs ?? null,
 			timestampEndSecs: timestamp?.end_secs ?? null,
 			timestampLabel: timestamp?.start_label ?? null,
+			sourceId,
 			via,
 			entryIndex: entryIndex ?? null,
 			formatName: formatName ?? null
@@ -97,6 +102,12 @@ async function mineOne(
 			if (msg.message) showNotice(msg.message);
 		}
 	);
+	recordMine(term, sentence, result);
+	return result;
+}
+
+/** Optimistic mined state for one mine's result. */
+function recordMine(term: ipc.Term, sentence: string, result: ipc.MineResult): void {
 	minedTerms.update((s) => new Set(s).add(term.lemma_form));
 	if (result.note_id !== null) {
 		minedNoteIds.update((m) => ({ ...m, [term.lemma_form]: result.note_id! }));
@@ -104,10 +115,9 @@ async function mineOne(
 	if (result.media_missing) {
 		mediaMissing.update((s) => new Set(s).add(term.lemma_form));
 	}
-	if (sentence && result.status === 'created') {
+	if (sentence && result.status !== 'duplicate') {
 		sessionMinedSentences.update((s) => new Set(s).add(normalizeSentence(sentence)));
 	}
-	return result;
 }
 
 /** Mine one term from its displayed sentence; the caller decides `via`.
@@ -116,6 +126,7 @@ export async function mineTerm(
 	term: ipc.Term,
 	sentence: string,
 	timestamp: ipc.TimeStampDto | null,
+	sourceId: number,
 	via: 'asbplayer' | 'direct',
 	surface: string = term.surface_form,
 	entryIndex?: number,
@@ -131,6 +142,7 @@ export async function mineTerm(
 			surface,
 			sentence,
 			timestamp,
+			sourceId,
 			via,
 			entryIndex,
 			formatName,
@@ -158,6 +170,8 @@ export interface QueueItem {
 	surface: string;
 	sentence: string;
 	timestamp: ipc.TimeStampDto | null;
+	/** The sentence's file in a multi-file session. */
+	sourceId: number;
 	/** Yomitan entry chosen via the popover's Queue (default first). */
 	entryIndex?: number;
 	/** Yomitan card format chosen via the popover's Queue (default first). */
@@ -176,13 +190,14 @@ export const mineQueueState = writable<{
 
 let queueCancelled = false;
 
-/** Stops the running queue between items; the in-flight mine still finishes. */
+/** Stops the running batch between items; notes already created stay. */
 export function cancelQueue(): void {
 	queueCancelled = true;
+	ipc.cancelMining().catch(() => {});
 }
 
-/** Mine the items one by one in timestamp order (timestamp-less last).
- * Failures are collected, never abort the queue. */
+/** Mine the items as one `mine_terms` batch in timestamp order
+ * (timestamp-less last). Failures are collected, never abort the queue. */
 export async function mineQueue(items: QueueItem[]): Promise<void> {
 	if (get(miningTerm) !== null || get(playerBusy) || items.length === 0) return;
 	const sorted = [...items].sort((a, b) => {
@@ -194,60 +209,80 @@ export async function mineQueue(items: QueueItem[]): Promise<void> {
 	playerBusy.set(true);
 	let created = 0;
 	let duplicates = 0;
+	let queued = 0;
 	let mediaMissed = 0;
 	let done = 0;
 	const failures: string[] = [];
 	const noteIds: number[] = [];
+	const showItem = (index: number) => {
+		const item = sorted[index];
+		miningTerm.set(item.term.lemma_form);
+		mineQueueState.set({
+			total: sorted.length,
+			done: index,
+			current: item.term.lemma_form,
+			key: termKey(item.term)
+		});
+	};
+	// Must match the `via` rule in TermTable's mine().
+	const status = get(playerStatus);
+	const requests: ipc.MineRequest[] = sorted.map((item) => ({
+		// entry_index is a position within the scan of scanText — mine_terms must rescan that same string.
+		term: item.scanText ?? item.term.lemma_form,
+		surface: item.surface,
+		sentence: item.sentence,
+		timestamp_secs: item.timestamp?.start_secs ?? null,
+		timestamp_end_secs: item.timestamp?.end_secs ?? null,
+		timestamp_label: item.timestamp?.start_label ?? null,
+		source_id: item.sourceId,
+		via:
+			status.mode === 'asbplayer' && status.ws_clients > 0 && item.timestamp !== null
+				? 'asbplayer'
+				: 'direct',
+		entry_index: item.entryIndex ?? null,
+		format_name: item.formatName ?? null
+	}));
 	try {
-		for (const item of sorted) {
-			if (queueCancelled) break;
-			miningTerm.set(item.term.lemma_form);
-			mineQueueState.set({
-				total: sorted.length,
-				done,
-				current: item.term.lemma_form,
-				key: termKey(item.term)
-			});
-			// Must match the `via` rule in TermTable's mine().
-			const status = get(playerStatus);
-			const via =
-				status.mode === 'asbplayer' && status.ws_clients > 0 && item.timestamp !== null
-					? 'asbplayer'
-					: 'direct';
-			try {
-				const result = await mineOne(
-					item.term,
-					item.surface,
-					item.sentence,
-					item.timestamp,
-					via,
-					item.entryIndex,
-					item.formatName,
-					item.scanText
-				);
-				if (result.status === 'duplicate') duplicates++;
-				else created++;
-				if (result.note_id !== null) noteIds.push(result.note_id);
-				if (result.media_missing) mediaMissed++;
-				selectedTerms.update((s) => {
-					const next = new Set(s);
-					next.delete(termKey(item.term));
-					return next;
-				});
-			} catch (err) {
-				failures.push(`「${item.term.lemma_form}」: ${String(err)}`);
+		showItem(0);
+		const outcomes = await ipc.mineTerms(requests, (msg) => {
+			if (msg.index !== null) showItem(msg.index);
+			showNotice(msg.message);
+		});
+		outcomes.forEach(({ result, error }, index) => {
+			const item = sorted[index];
+			if (error !== null) {
+				failures.push(`「${item.term.lemma_form}」: ${error}`);
+				done++;
+				return;
 			}
+			// Cancelled before the batch reached it.
+			if (result === null) return;
+			recordMine(item.term, item.sentence, result);
+			if (result.status === 'duplicate') duplicates++;
+			else if (result.status === 'queued') queued++;
+			else created++;
+			if (result.note_id !== null) noteIds.push(result.note_id);
+			if (result.media_missing) mediaMissed++;
+			selectedTerms.update((s) => {
+				const next = new Set(s);
+				next.delete(termKey(item.term));
+				return next;
+			});
 			done++;
-		}
+		});
+	} catch (err) {
+		for (const item of sorted) failures.push(`「${item.term.lemma_form}」: ${String(err)}`);
+		done = sorted.length;
 	} finally {
 		miningTerm.set(null);
 		playerBusy.set(false);
 		mineQueueState.set(null);
-		// Mined items were already deselected one by one; cancelled/failed
-		// terms stay selected so the batch can be resumed.
+		// Mined items were deselected above; cancelled/failed terms stay
+		// selected so the batch can be resumed.
 		void refreshMinedState(true);
 		const parts = [`Mined ${created}`];
 		if (duplicates > 0) parts.push(`${duplicates} duplicate${duplicates === 1 ? '' : 's'}`);
+		if (queued > 0) parts.push(`${queued} queued until Anki is back`);
 		if (mediaMissed > 0) parts.push(`${mediaMissed} missing media`);
 		if (failures.length > 0) parts.push(`${failures.length} failed`);
 		showNotice(
@@ -271,7 +306,8 @@ export async function mineQueue(items: QueueItem[]): Promise<void> {
  * the note id from this session's mine). */
 export async function retryMedia(
 	term: ipc.Term,
-	timestamp: ipc.TimeStampDto | null
+	timestamp: ipc.TimeStampDto | null,
+	sourceId: number
 ): Promise<void> {
 	if (get(miningTerm) !== null || get(playerBusy)) return;
 	const noteId = get(minedNoteIds)[term.lemma_form];
@@ -284,7 +320,8 @@ export async function retryMedia(
 				noteId,
 				timestampSecs: timestamp?.start_secs ?? null,
 				timestampEndSecs: timestamp?.end_secs ?? null,
-				timestampLabel: timestamp?.start_label ?? null
+				timestampLabel: timestamp?.start_label ?? null,
+				sourceId
 			},
 			(msg) => {
 				if (msg.message) showNotice(msg.message);
@@ -312,3 +349,42 @@ export async function openInAnki(noteId: number): Promise<void> {
 		lastError.set({ title: 'Failed to open Anki', message: String(err), detail: null });
 	}
 }
+
+/** Offline mines the backend flushed once Anki came back (`mine-queue-flushed`). */
+export function reportQueueFlush(report: ipc.MineQueueFlushed): void {
+	const added = report.added.length;
+	const parts = [`Added ${added} queued mine${added === 1 ? '' : 's'} to Anki`];
+	if (report.duplicates.length > 0) parts.push(`${report.duplicates.length} already in Anki`);
+	if (report.remaining > 0) parts.push(`${report.remaining} still queued`);
+	showNotice(parts.join(' · '));
+	const rejected = report.rejected.length;
+	if (rejected > 0) {
+		lastError.set({
+			title: 'Queued mines',
+			message: `Anki refused ${rejected} queued mine${rejected === 1 ? '' : 's'} — kept under the Anki indicator to retry or discard`,
+			detail: report.rejected.join('\n')
+		});
+	}
+	void refreshMinedState(true);
+}
+
+export async function loadFailedMines(): Promise<void> {
+	try {
+		failedMines.set(await ipc.getFailedMines());
+	} catch (err) {
+		lastError.set({ title: 'Queued mines', message: String(err), detail: null });
+	}
+}
+
+/** Hand the failed mines back to the queue; the next Anki poll flushes them. */
+export async function retryFailedMines(): Promise<void> {
+	await ipc.retryFailedMines();
+	failedMines.set([]);
+	ankiStatus.update((s) => ({ ...s, queued: s.queued + s.failed, failed: 0 }));
+}
+
+export async function discardFailedMines(): Promise<void> {
+	await ipc.discardFailedMines();
+	failedMines.set([]);
+	ankiStatus.update((s) => ({ ...s, failed: 0 }));
+}
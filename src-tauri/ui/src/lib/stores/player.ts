@@ -30,9 +30,13 @@ export const asbContext = writable<ipc.AsbplayerContext>({
 	loaded_has_subtitles: false
 });
 
-export async function seekTimestamp(seconds: number, label: string): Promise<void> {
+export async function seekTimestamp(
+	seconds: number,
+	label: string,
+	sourceId: number
+): Promise<void> {
 	try {
-		await ipc.seekTimestamp(seconds, label);
+		await ipc.seekTimestamp(seconds, label, sourceId);
 	} catch (err) {
 		lastError.set({ title: 'Failed to seek', message: String(err), detail: null });
 	}
@@ -59,6 +63,14 @@ export async function launchMpvVideo(): Promise<boolean> {
 	return tryLaunchMpv(video);
 }
 
+/** Open the loaded MKV itself in mpv; the backend selects the subtitle track
+ * being mined (`--sid`). */
+export async function launchMpvLoaded(): Promise<boolean> {
+	const current = get(fileResult);
+	if (current?.source_file.file_type !== 'MKV') return false;
+	return tryLaunchMpv(current.source_file.original_file);
+}
+
 /** Persist a user-located mpv executable, then retry the pending launch. */
 export async function locateMpvAndRetry(): Promise<boolean> {
 	const exe = await ipc.openExecutableDialog();
@@ -41,6 +41,13 @@ export async function toggleSerifFont(): Promise<void> {
 	await patchSettings({ use_serif_font: !s.use_serif_font });
 }
 
+/** Applies from the next parse; the caller reprocesses the loaded file. */
+export async function toggleJoinSplitCues(): Promise<void> {
+	const s = get(settings);
+	if (!s) return;
+	await patchSettings({ join_split_cues: !s.join_split_cues });
+}
+
 export const setFontScale = (scale: number) =>
 	patchSettings({ font_scale: Math.min(1.5, Math.max(0.75, scale)) });
 
@@ -86,15 +93,23 @@ export async function saveWebsocketPort(port: number): Promise<boolean> {
 export async function saveAnkiSettings(
 	mappings: Record<string, ipc.FieldMapping>,
 	interval: number,
-	yomitanUrl: string
+	vocabQuery: string,
+	suspendedCards: ipc.SuspendedCards,
+	yomitanUrl: string,
+	ankiConnectUrl: string,
+	ankiConnectApiKey: string
 ): Promise<boolean> {
 	try {
 		const saved = await patchSettings({
 			anki_model_mappings: mappings,
 			anki_interval: interval,
-			yomitan_url: yomitanUrl
+			anki_vocab_query: vocabQuery,
+			anki_suspended_cards: suspendedCards,
+			yomitan_url: yomitanUrl,
+			anki_connect_url: ankiConnectUrl,
+			anki_connect_api_key: ankiConnectApiKey || null
 		});
-		// Re-probe: the Yomitan URL / sentence mappings may have changed.
+		// Re-probe: the AnkiConnect / Yomitan URLs or sentence mappings may have changed.
 		if (saved) void refreshMinedState(true);
 		return saved;
 	} catch (err) {
@@ -40,6 +40,11 @@ export async function ensureToolsReady(): Promise<boolean> {
 	}
 }
 
-export const ankiStatus = writable<ipc.AnkiStatus>({ connected: false, fetching: false });
+export const ankiStatus = writable<ipc.AnkiStatus>({
+	connected: false,
+	fetching: false,
+	queued: 0,
+	failed: 0
+});
 
 export const knowledge = writable<ipc.KnowledgeSummary | null>(null);
@@ -46,10 +46,16 @@ export function normalizeColumns(
 	return out;
 }
 
-export type SortField = 'frequency' | 'chronological' | 'sentenceCount' | 'comprehension' | 'jlpt';
+export type SortField =
+	| 'priority'
+	| 'frequency'
+	| 'chronological'
+	| 'sentenceCount'
+	| 'comprehension'
+	| 'jlpt';
 export type SortDir = 'asc' | 'desc';
 
-/** egui `SortState::default_direction`: frequency/chronological ascending; count/comprehension descending. */
+/** egui `SortState::default_direction`: frequency/chronological ascending; priority/count/comprehension descending. */
 export function defaultDir(field: SortField): SortDir {
 	return field === 'frequency' || field === 'chronological' ? 'asc' : 'desc';
 }
@@ -67,6 +73,18 @@ function chronoIndex(term: Term): number {
 	return min;
 }
 
+/** A session term's occurrences per file (keyed by `SentenceDto.source_id`),
+ * in first-appearance order — the first key is the file it first appears in. */
+export function sourceCounts(term: Term, sentences: SentenceDto[]): Map<number, number> {
+	const counts = new Map<number, number>();
+	const ordered = [...term.sentence_references].sort((a, b) => a[0] - b[0]);
+	for (const [idx] of ordered) {
+		const s = sentences[idx];
+		if (s) counts.set(s.source_id, (counts.get(s.source_id) ?? 0) + 1);
+	}
+	return counts;
+}
+
 /** Comprehension of the term's first resolvable sentence — the one the table shows. */
 function comprehensionOf(term: Term, sentences: SentenceDto[]): number {
 	for (const [idx] of term.sentence_references) {
@@ -195,6 +213,8 @@ export function applyControls(
 	const { field, dir } = c.sort;
 	const keyOf = (t: Term): number => {
 		switch (field) {
+			case 'priority':
+				return t.priority;
 			case 'frequency':
 				return harmonic(t);
 			case 'chronological':
@@ -1,4 +1,8 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::LazyLock,
+    time::Duration,
+};
 
 use reqwest::Client;
 use serde::{
@@ -8,6 +12,28 @@ use serde::{
 
 use crate::core::errors::YomineError;
 
+/// Shared by every request so connections are pooled.
+static CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
+
+pub const DEFAULT_ANKI_CONNECT_URL: &str = "http://localhost:8765";
+
+/// How long a mine waits on AnkiConnect before it is queued instead — a busy
+/// Anki (e.g. mid-sync) otherwise hangs the mine. Generous enough for a batch.
+const NOTE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where AnkiConnect listens, and the key it expects when its `apiKey` is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnkiConnection {
+    pub url: String,
+    pub api_key: Option<String>,
+}
+
+impl Default for AnkiConnection {
+    fn default() -> Self {
+        Self { url: DEFAULT_ANKI_CONNECT_URL.to_string(), api_key: None }
+    }
+}
+
 #[derive(Debug)]
 pub struct Deck {
     pub name: String,
@@ -58,6 +84,45 @@ pub struct Card {
     modified: u64,
 }
 
+/// A card's scheduling state from `cardsInfo`, without the rendered question
+/// and answer a full `Card` carries.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CardState {
+    pub card_id: u64,
+    /// 0 new, 1 learning, 2 review, 3 relearning.
+    #[serde(rename = "type")]
+    pub card_type: i32,
+    /// -1 suspended, -2/-3 buried, else the queue of the card's type.
+    pub queue: i32,
+    pub deck_name: String,
+}
+
+/// A card's modification time from `cardsModTime`.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CardModTime {
+    pub card_id: u64,
+    #[serde(rename = "mod")]
+    pub modified: i64,
+}
+
+/// One review-log entry from `getReviewsOfCards`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Review {
+    /// Review time, epoch milliseconds.
+    pub id: i64,
+    /// Interval scheduled by the review: days if positive, seconds if negative.
+    pub ivl: i64,
+    /// SM-2 ease ×1000, or under FSRS the card's difficulty rescaled to 100..=1100.
+    pub factor: i64,
+    /// Answer button, 1 (Again) to 4 (Easy).
+    pub ease: i64,
+    /// 0 learn, 1 review, 2 relearn, 3 filtered, 4 manual, 5 rescheduled.
+    #[serde(rename = "type")]
+    pub kind: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub result: Option<T>,
@@ -73,27 +138,81 @@ impl<T> ApiResponse<T> {
     }
 }
 
-async fn make_request<T: for<'de> Deserialize<'de>>(
+/// One action's request object; also each entry of a `multi` request, which
+/// AnkiConnect checks for the version and key separately.
+fn request_body(
+    connection: &AnkiConnection,
     action: &str,
     params: Option<serde_json::Value>,
-) -> Result<ApiResponse<T>, reqwest::Error> {
+) -> serde_json::Value {
     let mut body = serde_json::Map::new();
     body.insert("action".to_string(), serde_json::Value::String(action.to_string()));
     body.insert("version".to_string(), serde_json::Value::Number((6).into()));
 
+    if let Some(key) = &connection.api_key {
+        body.insert("key".to_string(), serde_json::Value::String(key.clone()));
+    }
     if let Some(params) = params {
         body.insert("params".to_string(), params);
     }
+    serde_json::Value::Object(body)
+}
 
-    let response: ApiResponse<T> =
-        Client::new().post("http://localhost:8765/").json(&body).send().await?.json().await?;
+async fn make_request<T: for<'de> Deserialize<'de>>(
+    connection: &AnkiConnection,
+    action: &str,
+    params: Option<serde_json::Value>,
+) -> Result<ApiResponse<T>, reqwest::Error> {
+    send_request(connection, request_body(connection, action, params), None).await
+}
+
+async fn send_request<T: for<'de> Deserialize<'de>>(
+    connection: &AnkiConnection,
+    body: serde_json::Value,
+    timeout: Option<Duration>,
+) -> Result<ApiResponse<T>, reqwest::Error> {
+    let mut request = CLIENT.post(&connection.url).json(&body);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let response: ApiResponse<T> = request.send().await?.json().await?;
 
     Ok(response)
 }
 
+/// Run several calls of one action in a single round trip (`multi`). Every
+/// call gets its own outcome, in order; one the response lacks is an error.
+async fn multi<T: for<'de> Deserialize<'de>>(
+    connection: &AnkiConnection,
+    action: &str,
+    params: Vec<serde_json::Value>,
+    timeout: Option<Duration>,
+) -> Result<Vec<Result<T, String>>, reqwest::Error> {
+    let count = params.len();
+    let actions: Vec<serde_json::Value> =
+        params.into_iter().map(|params| request_body(connection, action, Some(params))).collect();
+    let body = request_body(connection, "multi", Some(serde_json::json!({ "actions": actions })));
+    let response: ApiResponse<Vec<ApiResponse<T>>> =
+        send_request(connection, body, timeout).await?;
+    let batch_error = response.error.clone();
+    let mut outcomes: Vec<Result<T, String>> = response
+        .result
+        .unwrap_or_default()
+        .into_iter()
+        .map(|outcome| match (outcome.result, outcome.error) {
+            (_, Some(err)) => Err(err),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(format!("{action} returned nothing")),
+        })
+        .collect();
+    let missing = batch_error.unwrap_or_else(|| format!("no response to {action}"));
+    outcomes.resize_with(count, || Err(missing.clone()));
+    Ok(outcomes)
+}
+
 //Will just use to check if ankiconnect is online
-pub async fn get_version() -> Result<u32, YomineError> {
-    let response: ApiResponse<u32> = make_request("version", None).await?;
+pub async fn get_version(connection: &AnkiConnection) -> Result<u32, YomineError> {
+    let response: ApiResponse<u32> = make_request(connection, "version", None).await?;
     match (response.result, response.error) {
         (Some(version), None) => Ok(version),
         (_, error) => {
@@ -102,8 +221,9 @@ pub async fn get_version() -> Result<u32, YomineError> {
     }
 }
 
-pub async fn get_deck_ids() -> Result<Vec<Deck>, reqwest::Error> {
-    let response: ApiResponse<HashMap<String, u64>> = make_request("deckNamesAndIds", None).await?;
+pub async fn get_deck_ids(connection: &AnkiConnection) -> Result<Vec<Deck>, reqwest::Error> {
+    let response: ApiResponse<HashMap<String, u64>> =
+        make_request(connection, "deckNamesAndIds", None).await?;
 
     Ok(response
         .unwrap_result()
@@ -113,98 +233,267 @@ pub async fn get_deck_ids() -> Result<Vec<Deck>, reqwest::Error> {
         .collect())
 }
 
-pub async fn get_note_ids(query: &str) -> Result<Vec<u64>, reqwest::Error> {
+pub async fn get_note_ids(
+    connection: &AnkiConnection,
+    query: &str,
+) -> Result<Vec<u64>, reqwest::Error> {
     let params = serde_json::json!({ "query": query });
-    let response: ApiResponse<Vec<u64>> = make_request("findNotes", Some(params)).await?;
+    let response: ApiResponse<Vec<u64>> =
+        make_request(connection, "findNotes", Some(params)).await?;
     Ok(response.unwrap_result().unwrap_or_default())
 }
 
-pub async fn get_notes(note_ids: Vec<u64>) -> Result<Vec<Note>, reqwest::Error> {
+pub async fn get_notes(
+    connection: &AnkiConnection,
+    note_ids: Vec<u64>,
+) -> Result<Vec<Note>, reqwest::Error> {
     let params = serde_json::json!({ "notes": note_ids });
-    let response: ApiResponse<Vec<Note>> = make_request("notesInfo", Some(params)).await?;
+    let response: ApiResponse<Vec<Note>> =
+        make_request(connection, "notesInfo", Some(params)).await?;
+    Ok(response.unwrap_result().unwrap_or_default())
+}
+
+pub async fn get_cards(
+    connection: &AnkiConnection,
+    card_ids: Vec<u64>,
+) -> Result<Vec<Card>, reqwest::Error> {
+    let params = serde_json::json!({ "cards": card_ids });
+    let response: ApiResponse<Vec<Card>> =
+        make_request(connection, "cardsInfo", Some(params)).await?;
+    Ok(response.unwrap_result().unwrap_or_default())
+}
+
+pub async fn get_card_states(
+    connection: &AnkiConnection,
+    card_ids: Vec<u64>,
+) -> Result<Vec<CardState>, reqwest::Error> {
+    let params = serde_json::json!({ "cards": card_ids });
+    let response: ApiResponse<Vec<CardState>> =
+        make_request(connection, "cardsInfo", Some(params)).await?;
     Ok(response.unwrap_result().unwrap_or_default())
 }
 
-pub async fn get_cards(card_ids: Vec<u64>) -> Result<Vec<Card>, reqwest::Error> {
+/// Cheap next to `cardsInfo`: only ids and times come back.
+pub async fn get_cards_mod_time(
+    connection: &AnkiConnection,
+    card_ids: Vec<u64>,
+) -> Result<Vec<CardModTime>, reqwest::Error> {
+    let params = serde_json::json!({ "cards": card_ids });
+    let response: ApiResponse<Vec<CardModTime>> =
+        make_request(connection, "cardsModTime", Some(params)).await?;
+    Ok(response.unwrap_result().unwrap_or_default())
+}
+
+/// FSRS desired retention of the deck's options preset; `None` before Anki
+/// 23.10 or for a deck AnkiConnect doesn't know.
+pub async fn get_desired_retention(
+    connection: &AnkiConnection,
+    deck: &str,
+) -> Result<Option<f32>, reqwest::Error> {
+    let params = serde_json::json!({ "deck": deck });
+    let response: ApiResponse<serde_json::Value> =
+        make_request(connection, "getDeckConfig", Some(params)).await?;
+    Ok(response
+        .unwrap_result()
+        .and_then(|config| config.get("desiredRetention")?.as_f64())
+        .map(|retention| retention as f32))
+}
+
+pub async fn get_intervals(
+    connection: &AnkiConnection,
+    card_ids: Vec<u64>,
+) -> Result<Vec<i32>, reqwest::Error> {
     let params = serde_json::json!({ "cards": card_ids });
-    let response: ApiResponse<Vec<Card>> = make_request("cardsInfo", Some(params)).await?;
+    let response: ApiResponse<Vec<i32>> =
+        make_request(connection, "getIntervals", Some(params)).await?;
     Ok(response.unwrap_result().unwrap_or_default())
 }
 
-pub async fn get_intervals(card_ids: Vec<u64>) -> Result<Vec<i32>, reqwest::Error> {
+/// Review logs per card, keyed by the card id as a string.
+pub async fn get_reviews_of_cards(
+    connection: &AnkiConnection,
+    card_ids: Vec<u64>,
+) -> Result<HashMap<String, Vec<Review>>, reqwest::Error> {
     let params = serde_json::json!({ "cards": card_ids });
-    let response: ApiResponse<Vec<i32>> = make_request("getIntervals", Some(params)).await?;
+    let response: ApiResponse<HashMap<String, Vec<Review>>> =
+        make_request(connection, "getReviewsOfCards", Some(params)).await?;
     Ok(response.unwrap_result().unwrap_or_default())
 }
 
+/// A note to create, for the batch calls below.
+#[derive(Debug, Clone)]
+pub struct NewNote {
+    pub deck_name: String,
+    pub model_name: String,
+    pub fields: HashMap<String, String>,
+    pub tags: Vec<String>,
+}
+
+impl NewNote {
+    fn to_json(&self) -> serde_json::Value {
+        note_json(&self.deck_name, &self.model_name, &self.fields, &self.tags)
+    }
+}
+
+fn note_json(
+    deck_name: &str,
+    model_name: &str,
+    fields: &HashMap<String, String>,
+    tags: &[String],
+) -> serde_json::Value {
+    serde_json::json!({
+        "deckName": deck_name,
+        "modelName": model_name,
+        "fields": fields,
+        "tags": tags,
+        "options": { "allowDuplicate": false }
+    })
+}
+
+/// Whether a note request failed for want of AnkiConnect, so the mine is worth
+/// queuing: the connection was refused (nothing was done) or it timed out
+/// after `NOTE_TIMEOUT` (Anki may still have added the note; the queue's flush
+/// then reports it as a duplicate). A decode error is not: Anki answered, and
+/// the note may exist.
+pub fn is_unreachable(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
 /// Create a note (one-click mining, issue #105). Returns the raw `ApiResponse`
 /// so callers can tell a duplicate rejection apart from other errors.
 pub async fn add_note(
+    connection: &AnkiConnection,
     deck_name: &str,
     model_name: &str,
     fields: &HashMap<String, String>,
     tags: &[String],
 ) -> Result<ApiResponse<u64>, reqwest::Error> {
-    let params = serde_json::json!({
-        "note": {
-            "deckName": deck_name,
-            "modelName": model_name,
-            "fields": fields,
-            "tags": tags,
-            "options": { "allowDuplicate": false }
-        }
-    });
-    make_request("addNote", Some(params)).await
+    let params = serde_json::json!({ "note": note_json(deck_name, model_name, fields, tags) });
+    let body = request_body(connection, "addNote", Some(params));
+    send_request(connection, body, Some(NOTE_TIMEOUT)).await
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CanAddDetail {
+    can_add: bool,
+    error: Option<String>,
+}
+
+/// Which notes `add_notes` would accept (`canAddNotesWithErrorDetail`): per
+/// note, `Err` with Anki's reason, e.g. that it is a duplicate.
+pub async fn can_add_notes(
+    connection: &AnkiConnection,
+    notes: &[NewNote],
+) -> Result<Vec<Result<(), String>>, reqwest::Error> {
+    let params =
+        serde_json::json!({ "notes": notes.iter().map(NewNote::to_json).collect::<Vec<_>>() });
+    let body = request_body(connection, "canAddNotesWithErrorDetail", Some(params));
+    let response: ApiResponse<Vec<CanAddDetail>> =
+        send_request(connection, body, Some(NOTE_TIMEOUT)).await?;
+    let mut details: Vec<Result<(), String>> = response
+        .unwrap_result()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|detail| match detail {
+            CanAddDetail { can_add: true, .. } => Ok(()),
+            CanAddDetail { error, .. } => {
+                Err(error.unwrap_or_else(|| "cannot add note".to_string()))
+            }
+        })
+        .collect();
+    // An older AnkiConnect without the action: let `add_notes` decide.
+    details.resize_with(notes.len(), || Ok(()));
+    Ok(details)
+}
+
+/// Create many notes in one round trip; per note its id or Anki's error.
+/// `addNote` calls batched with `multi` rather than `addNotes`, which reports
+/// failures only as one combined error.
+pub async fn add_notes(
+    connection: &AnkiConnection,
+    notes: &[NewNote],
+) -> Result<Vec<Result<u64, String>>, reqwest::Error> {
+    let params = notes.iter().map(|note| serde_json::json!({ "note": note.to_json() })).collect();
+    multi(connection, "addNote", params, Some(NOTE_TIMEOUT)).await
 }
 
 /// Open Anki's card browser on a search (e.g. `nid:123`) — the "open the card
 /// I just mined" affordance.
-pub async fn gui_browse(query: &str) -> Result<ApiResponse<Vec<u64>>, reqwest::Error> {
+pub async fn gui_browse(
+    connection: &AnkiConnection,
+    query: &str,
+) -> Result<ApiResponse<Vec<u64>>, reqwest::Error> {
     let params = serde_json::json!({ "query": query });
-    make_request("guiBrowse", Some(params)).await
+    make_request(connection, "guiBrowse", Some(params)).await
 }
 
 /// Select a card in the open browser (`guiSelectCard`); returns false when no
 /// browser is open.
-pub async fn gui_select_card(card_id: u64) -> Result<ApiResponse<bool>, reqwest::Error> {
+pub async fn gui_select_card(
+    connection: &AnkiConnection,
+    card_id: u64,
+) -> Result<ApiResponse<bool>, reqwest::Error> {
     let params = serde_json::json!({ "card": card_id });
-    make_request("guiSelectCard", Some(params)).await
+    make_request(connection, "guiSelectCard", Some(params)).await
 }
 
 /// Store a base64 media payload in Anki's collection (`storeMediaFile`).
 pub async fn store_media_file(
+    connection: &AnkiConnection,
     filename: &str,
     base64_data: &str,
 ) -> Result<ApiResponse<String>, reqwest::Error> {
     let params = serde_json::json!({ "filename": filename, "data": base64_data });
-    make_request("storeMediaFile", Some(params)).await
+    make_request(connection, "storeMediaFile", Some(params)).await
+}
+
+/// Store many media payloads in one round trip; per file `Err` with Anki's error.
+pub async fn store_media_files(
+    connection: &AnkiConnection,
+    files: &[(&str, &str)],
+) -> Result<Vec<Result<String, String>>, reqwest::Error> {
+    let params = files
+        .iter()
+        .map(|(filename, base64_data)| serde_json::json!({ "filename": filename, "data": base64_data }))
+        .collect();
+    multi(connection, "storeMediaFile", params, None).await
 }
 
-pub async fn get_model_ids() -> Result<HashMap<String, u64>, reqwest::Error> {
+pub async fn get_model_ids(
+    connection: &AnkiConnection,
+) -> Result<HashMap<String, u64>, reqwest::Error> {
     let response: ApiResponse<HashMap<String, u64>> =
-        make_request("modelNamesAndIds", None).await?;
+        make_request(connection, "modelNamesAndIds", None).await?;
     Ok(response.unwrap_result().unwrap_or_default())
 }
 
-pub async fn get_field_names(model_name: &str) -> Result<Vec<String>, reqwest::Error> {
+pub async fn get_field_names(
+    connection: &AnkiConnection,
+    model_name: &str,
+) -> Result<Vec<String>, reqwest::Error> {
     let params = serde_json::json!({ "modelName": model_name });
-    let response: ApiResponse<Vec<String>> = make_request("modelFieldNames", Some(params)).await?;
+    let response: ApiResponse<Vec<String>> =
+        make_request(connection, "modelFieldNames", Some(params)).await?;
     Ok(response.unwrap_result().unwrap_or_default())
 }
 
-pub async fn get_sample_note_for_model(model_name: &str) -> Result<Option<Note>, reqwest::Error> {
+pub async fn get_sample_note_for_model(
+    connection: &AnkiConnection,
+    model_name: &str,
+) -> Result<Option<Note>, reqwest::Error> {
     let query = if model_name.contains(' ') || model_name.contains(':') || model_name.contains('"')
     {
         format!("note:\"{}\"", model_name.replace('"', "\\\""))
     } else {
         format!("note:{}", model_name)
     };
-    let note_ids = get_note_ids(&query).await?;
+    let note_ids = get_note_ids(connection, &query).await?;
 
     if !note_ids.is_empty() {
         let mid_index = note_ids.len() / 2;
         let mid_note_id = note_ids[mid_index];
-        let notes = get_notes(vec![mid_note_id]).await?;
+        let notes = get_notes(connection, vec![mid_note_id]).await?;
         Ok(notes.into_iter().next())
     } else {
         Ok(None)
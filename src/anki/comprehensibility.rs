@@ -1,10 +1,34 @@
-//Until we can get FSRS, we're using interval
+//! Card comprehension from the FSRS memory state inferred from the revlog,
+//! falling back to the interval curve for cards without one.
 
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use super::{
+    api::Review,
+    types::{
+        CardType,
+        Vocab,
+    },
+};
 use crate::core::{
+    ComprehensionModel,
     Sentence,
     Term,
 };
 
+/// FSRS forgetting curve `R(t) = (1 + FACTOR·t/S)^DECAY`, chosen so that
+/// `R(S) = 0.9`: stability is the number of days until recall drops to 90%.
+const FSRS_DECAY: f32 = -0.5;
+const FSRS_FACTOR: f32 = 19.0 / 81.0;
+const SECONDS_PER_DAY: f32 = 86_400.0;
+/// Anki's default FSRS desired retention, for decks whose preset has none.
+pub const DEFAULT_DESIRED_RETENTION: f32 = 0.9;
+/// A (re)learning card is never mature, whatever its step.
+const LEARNING_COMPREHENSION_CAP: f32 = 0.5;
+
 pub fn comp_term(interval: Option<f32>, known_interval: u32) -> f32 {
     match interval {
         None => 0.0,
@@ -21,19 +45,431 @@ pub fn comp_term(interval: Option<f32>, known_interval: u32) -> f32 {
     }
 }
 
-/// Calculate sentence comprehension as average of its terms, again there's a bunch of ways to weigh terms. Hatsuon length, character number, etc.
-pub fn calculate_sentence_comprehension(sentence: &mut Sentence, terms: &[Term]) {
-    let sentence_terms: Vec<&Term> = terms
+/// Probability of recalling a card `elapsed_days` after its last review.
+pub fn retrievability(stability: f32, elapsed_days: f32) -> f32 {
+    (1.0 + FSRS_FACTOR * elapsed_days.max(0.0) / stability.max(f32::EPSILON)).powf(FSRS_DECAY)
+}
+
+/// Stability of a card FSRS scheduled `interval_days` out: the inverse of the
+/// scheduler's `I = S/FACTOR·(R^(1/DECAY) − 1)` at the deck's desired
+/// retention `R`. Only at 90% is the interval the stability.
+pub fn stability_for_interval(interval_days: f32, desired_retention: f32) -> f32 {
+    interval_days * FSRS_FACTOR / (desired_retention.powf(1.0 / FSRS_DECAY) - 1.0)
+}
+
+/// Comprehension of one Anki card at `now` (unix seconds): its current FSRS
+/// retrievability when the card has a memory state, else the interval model.
+/// New cards aren't known yet, and cards in (re)learning go by their learning
+/// step, since right after a lapse retrievability still reads as near-certain.
+pub fn comp_vocab(vocab: &Vocab, known_interval: u32, now: i64) -> f32 {
+    match (vocab.card_type, vocab.stability, vocab.last_review) {
+        (CardType::New, _, _) => 0.0,
+        (CardType::Learning | CardType::Relearning, _, _) => {
+            comp_term(vocab.interval, known_interval).min(LEARNING_COMPREHENSION_CAP)
+        }
+        (CardType::Review, Some(stability), Some(last_review)) => {
+            retrievability(stability, (now - last_review) as f32 / SECONDS_PER_DAY)
+        }
+        _ => comp_term(vocab.interval, known_interval),
+    }
+}
+
+/// `(stability, difficulty, last review)` from a card's review log, when its
+/// latest review was scheduled by FSRS. AnkiConnect doesn't expose the memory
+/// state itself, so it is read back from the log: FSRS stores difficulty in
+/// `factor` as 100..=1100 (SM-2 eases start at 1300), and the scheduled
+/// interval gives the stability at the deck's `desired_retention`.
+pub fn fsrs_memory_state(reviews: &[Review], desired_retention: f32) -> Option<(f32, f32, i64)> {
+    let last = reviews.iter().filter(|r| r.kind <= 3).max_by_key(|r| r.id)?;
+    if !(100..=1100).contains(&last.factor) {
+        return None;
+    }
+    let interval = if last.ivl >= 0 {
+        last.ivl as f32
+    } else {
+        last.ivl.unsigned_abs() as f32 / SECONDS_PER_DAY
+    };
+    let stability = stability_for_interval(interval, desired_retention);
+    let difficulty = (last.factor as f32 / 1000.0 - 0.1) * 9.0 + 1.0;
+    Some((stability, difficulty, last.id / 1000))
+}
+
+/// Times a card was forgotten: review-queue answers of Again.
+pub fn lapse_count(reviews: &[Review]) -> u32 {
+    reviews.iter().filter(|r| r.kind == 1 && r.ease == 1).count() as u32
+}
+
+/// Sentence id → the terms occurring in it, built once from every term's
+/// `sentence_references` so per-sentence work doesn't rescan the term list.
+pub struct SentenceTermIndex {
+    occurrences: Vec<Vec<(usize, usize)>>,
+}
+
+impl SentenceTermIndex {
+    pub fn new(terms: &[Term]) -> Self {
+        let len = terms
+            .iter()
+            .flat_map(|t| &t.sentence_references)
+            .map(|(sentence_id, _)| sentence_id + 1)
+            .max()
+            .unwrap_or(0);
+        let mut occurrences = vec![Vec::new(); len];
+        for (idx, term) in terms.iter().enumerate() {
+            for &(sentence_id, start) in &term.sentence_references {
+                occurrences[sentence_id].push((idx, start));
+            }
+        }
+        Self { occurrences }
+    }
+
+    /// `(term index, byte start)` of every term occurrence in the sentence, in
+    /// term order, so a term's repeats are adjacent.
+    pub fn occurrences(&self, sentence_id: usize) -> &[(usize, usize)] {
+        self.occurrences.get(sentence_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Each distinct term in the sentence with its occurrence count there.
+    fn term_counts<'a>(&self, sentence_id: usize, terms: &'a [Term]) -> Vec<(&'a Term, usize)> {
+        let mut counts: Vec<(&Term, usize)> = Vec::new();
+        let mut last = None;
+        for &(idx, _) in self.occurrences(sentence_id) {
+            match counts.last_mut() {
+                Some((_, count)) if last == Some(idx) => *count += 1,
+                _ => counts.push((&terms[idx], 1)),
+            }
+            last = Some(idx);
+        }
+        counts
+    }
+}
+
+/// Calculate every sentence's comprehension from its terms' comprehension,
+/// weighed per `model`, in one pass over a [`SentenceTermIndex`]. A sentence
+/// no term covers reads 0.
+pub fn calculate_sentence_comprehensions(
+    sentences: &mut [Sentence],
+    terms: &[Term],
+    model: ComprehensionModel,
+) {
+    let index = SentenceTermIndex::new(terms);
+    for sentence in sentences {
+        sentence.comprehension =
+            sentence_comprehension(&index.term_counts(sentence.id, terms), model);
+    }
+}
+
+fn weighted_mean(pairs: impl Iterator<Item = (f32, f32)>) -> f32 {
+    let (total, weight) =
+        pairs.fold((0.0, 0.0), |(total, weight), (w, value)| (total + w * value, weight + w));
+    if weight > 0.0 {
+        total / weight
+    } else {
+        0.0
+    }
+}
+
+fn sentence_comprehension(occurrences: &[(&Term, usize)], model: ComprehensionModel) -> f32 {
+    if occurrences.is_empty() {
+        return 0.0;
+    }
+    // Content-word models fall back to every term for lines without one
+    // (an interjection, a lone name).
+    let content: Vec<(&Term, usize)> = occurrences
+        .iter()
+        .filter(|(t, _)| t.part_of_speech.is_vocabulary_word())
+        .copied()
+        .collect();
+    let content = if content.is_empty() { occurrences } else { &content[..] };
+
+    match model {
+        ComprehensionModel::Average => {
+            weighted_mean(occurrences.iter().map(|(t, _)| (1.0, t.comprehension)))
+        }
+        ComprehensionModel::TokenWeighted => {
+            weighted_mean(occurrences.iter().map(|(t, n)| (*n as f32, t.comprehension)))
+        }
+        ComprehensionModel::ContentWords => {
+            weighted_mean(content.iter().map(|(t, _)| (1.0, t.comprehension)))
+        }
+        ComprehensionModel::CharacterWeighted => weighted_mean(occurrences.iter().map(|(t, n)| {
+            let chars = t.surface_form.chars().count().max(1);
+            ((chars * n) as f32, t.comprehension)
+        })),
+        ComprehensionModel::WholeSentence => {
+            content.iter().map(|(t, _)| t.comprehension.clamp(0.0, 1.0)).product()
+        }
+    }
+}
+
+/// A sentence-card candidate from [`find_target_sentences`]: the sentence and
+/// its unknown words as indices into the unknown-term slice, most frequent first.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TargetSentence {
+    pub sentence_id: usize,
+    pub unknown: Vec<usize>,
+}
+
+fn frequency_rank(term: &Term) -> u32 {
+    term.frequencies.get("HARMONIC").copied().unwrap_or(u32::MAX)
+}
+
+/// i+N sentences: those with 1..=`max_unknown` unknown words and at least one
+/// known word beside them, counting only the words `counts` accepts.
+/// `base_terms` is every term of the file, `unknown_terms` the mining table
+/// (neither in Anki nor ignored). Ranked by the frequency of the rarest unknown
+/// word, then by sentence length, shortest first.
+pub fn find_target_sentences(
+    sentences: &[Sentence],
+    base_terms: &[Term],
+    unknown_terms: &[Term],
+    max_unknown: usize,
+    counts: impl Fn(&Term) -> bool,
+) -> Vec<TargetSentence> {
+    let unknown_index: HashMap<(&str, &str), usize> = unknown_terms
         .iter()
-        .filter(|t| t.sentence_references.iter().any(|(sid, _)| *sid == sentence.id))
+        .enumerate()
+        .map(|(idx, t)| ((t.lemma_form.as_str(), t.lemma_reading.as_str()), idx))
         .collect();
 
-    if sentence_terms.is_empty() {
-        sentence.comprehension = 0.0;
-        return;
+    // Sentence id → (known word count, unknown term indices).
+    let mut tallies: HashMap<usize, (usize, Vec<usize>)> = HashMap::new();
+    for term in base_terms.iter().filter(|t| counts(t)) {
+        let unknown = unknown_index.get(&(term.lemma_form.as_str(), term.lemma_reading.as_str()));
+        let mut seen = HashSet::new(); // A word repeated within a sentence counts once.
+        for (sentence_id, _) in &term.sentence_references {
+            if !seen.insert(*sentence_id) {
+                continue;
+            }
+            let tally = tallies.entry(*sentence_id).or_default();
+            match unknown {
+                Some(idx) => tally.1.push(*idx),
+                None => tally.0 += 1,
+            }
+        }
     }
 
-    // Average comprehension of all terms in the sentence
-    let sum: f32 = sentence_terms.iter().map(|t| t.comprehension).sum();
-    sentence.comprehension = sum / sentence_terms.len() as f32;
+    let lengths: HashMap<usize, usize> =
+        sentences.iter().map(|s| (s.id, s.text.chars().count())).collect();
+    let mut ranked: Vec<(u32, usize, TargetSentence)> = tallies
+        .into_iter()
+        .filter_map(|(sentence_id, (known, mut unknown))| {
+            if known == 0 || unknown.is_empty() || unknown.len() > max_unknown {
+                return None;
+            }
+            let length = *lengths.get(&sentence_id)?;
+            unknown.sort_by_key(|idx| frequency_rank(&unknown_terms[*idx]));
+            let rarest = frequency_rank(&unknown_terms[*unknown.last()?]);
+            Some((rarest, length, TargetSentence { sentence_id, unknown }))
+        })
+        .collect();
+    ranked.sort_by_key(|(rarest, length, target)| (*rarest, *length, target.sentence_id));
+    ranked.into_iter().map(|(_, _, target)| target).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segmentation::word::POS;
+
+    fn term(lemma: &str, pos: POS, rank: u32, sentence_ids: &[usize]) -> Term {
+        Term {
+            id: 1,
+            lemma_form: lemma.to_string(),
+            surface_form: lemma.to_string(),
+            part_of_speech: pos,
+            frequencies: HashMap::from([("HARMONIC".to_string(), rank)]),
+            full_segment: lemma.to_string(),
+            sentence_references: sentence_ids.iter().map(|id| (*id, 0)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn sentence(id: usize, text: &str) -> Sentence {
+        Sentence { id, text: text.to_string(), ..Default::default() }
+    }
+
+    fn review(id: i64, ivl: i64, factor: i64, kind: i64) -> Review {
+        Review { id, ivl, factor, ease: 3, kind }
+    }
+
+    #[test]
+    fn retrievability_follows_the_fsrs_forgetting_curve() {
+        assert_eq!(retrievability(10.0, 0.0), 1.0);
+        assert!((retrievability(10.0, 10.0) - 0.9).abs() < 1e-5);
+        assert!(retrievability(10.0, 100.0) < retrievability(10.0, 20.0));
+    }
+
+    #[test]
+    fn fsrs_state_read_from_the_latest_scheduled_review() {
+        let day = 86_400_000;
+        let log = [
+            review(day, 3, 550, 1),
+            review(5 * day, 12, 1100, 1),
+            // Manual reschedules don't carry a memory state.
+            review(6 * day, 30, 0, 4),
+        ];
+        let (stability, difficulty, last_review) =
+            fsrs_memory_state(&log, DEFAULT_DESIRED_RETENTION).unwrap();
+        assert!((stability - 12.0).abs() < 1e-4);
+        assert_eq!(last_review, 5 * 86_400);
+        assert!((difficulty - 10.0).abs() < 1e-5);
+
+        // SM-2 ease: no FSRS state, so the caller keeps the interval model.
+        assert!(fsrs_memory_state(&[review(day, 12, 2500, 1)], 0.9).is_none());
+        assert!(fsrs_memory_state(&[], 0.9).is_none());
+    }
+
+    #[test]
+    fn stability_follows_the_desired_retention() {
+        // Scheduled 12 days out at 80%: recall hits 80% on day 12, so it hits
+        // 90% well before.
+        let stability = stability_for_interval(12.0, 0.8);
+        assert!(stability < 12.0);
+        assert!((retrievability(stability, 12.0) - 0.8).abs() < 1e-4);
+        assert!((retrievability(stability_for_interval(12.0, 0.95), 12.0) - 0.95).abs() < 1e-4);
+    }
+
+    #[test]
+    fn vocab_comprehension_prefers_fsrs_over_interval() {
+        let mut vocab = Vocab {
+            term: "猫".to_string(),
+            reading: "ねこ".to_string(),
+            note_id: Some(1),
+            card_id: Some(1),
+            interval: Some(30.0),
+            stability: None,
+            difficulty: None,
+            last_review: None,
+            card_type: CardType::Review,
+            suspended: false,
+            buried: false,
+            lapses: 0,
+        };
+        assert_eq!(comp_vocab(&vocab, 21, 0), 1.0);
+
+        // Overdue: ten stabilities past the last review.
+        vocab.stability = Some(30.0);
+        vocab.difficulty = Some(5.0);
+        vocab.last_review = Some(0);
+        let overdue = comp_vocab(&vocab, 21, 300 * 86_400);
+        assert!((overdue - retrievability(30.0, 300.0)).abs() < 1e-6);
+        assert!(overdue < 0.6);
+    }
+
+    #[test]
+    fn new_and_relearning_cards_are_not_known_yet() {
+        let day = 86_400_000;
+        let log = [
+            review(day, 12, 550, 1),
+            Review { ease: 1, ..review(13 * day, -600, 700, 1) },
+            review(13 * day + 600_000, -600, 700, 2),
+        ];
+        assert_eq!(lapse_count(&log), 1);
+
+        // Just relearned: retrievability is near 1, but the card lapsed.
+        let (stability, difficulty, last_review) =
+            fsrs_memory_state(&log, DEFAULT_DESIRED_RETENTION).unwrap();
+        let mut vocab = Vocab {
+            term: "猫".to_string(),
+            reading: "ねこ".to_string(),
+            note_id: Some(1),
+            card_id: Some(1),
+            interval: Some(600.0 / 86_400.0),
+            stability: Some(stability),
+            difficulty: Some(difficulty),
+            last_review: Some(last_review),
+            card_type: CardType::Relearning,
+            suspended: false,
+            buried: false,
+            lapses: 1,
+        };
+        let relearning = comp_vocab(&vocab, 21, last_review + 60);
+        assert!(relearning > 0.0 && relearning <= LEARNING_COMPREHENSION_CAP);
+
+        vocab.card_type = CardType::New;
+        assert_eq!(comp_vocab(&vocab, 21, last_review + 60), 0.0);
+    }
+
+    #[test]
+    fn comprehension_models_weigh_terms_differently() {
+        let known = |lemma: &str, pos: POS, refs: &[usize]| Term {
+            comprehension: 1.0,
+            ..term(lemma, pos, 100, refs)
+        };
+        // 「ねえねえ、猫が寝る」: ねえ twice, one unknown verb, a particle.
+        let terms = vec![
+            known("ねえ", POS::Interjection, &[0, 0]),
+            known("猫", POS::Noun, &[0]),
+            known("が", POS::Postposition, &[0]),
+            Term { comprehension: 0.5, ..term("寝る", POS::Verb, 300, &[0]) },
+        ];
+        let score = |model| {
+            let mut sentences = [sentence(0, "ねえねえ、猫が寝る"), sentence(1, "……")];
+            calculate_sentence_comprehensions(&mut sentences, &terms, model);
+            assert_eq!(sentences[1].comprehension, 0.0);
+            sentences[0].comprehension
+        };
+
+        assert_eq!(score(ComprehensionModel::Average), 3.5 / 4.0);
+        assert_eq!(score(ComprehensionModel::TokenWeighted), 4.5 / 5.0);
+        assert_eq!(score(ComprehensionModel::ContentWords), 1.5 / 2.0);
+        // ねえ ×2 (2 chars each), 猫 1, が 1, 寝る 2 at half.
+        assert_eq!(score(ComprehensionModel::CharacterWeighted), 7.0 / 8.0);
+        assert_eq!(score(ComprehensionModel::WholeSentence), 0.5);
+    }
+
+    #[test]
+    fn index_groups_occurrences_by_sentence_and_term() {
+        let terms =
+            vec![term("猫", POS::Noun, 100, &[2, 0, 2]), term("が", POS::Postposition, 1, &[0, 2])];
+        let index = SentenceTermIndex::new(&terms);
+        assert_eq!(index.occurrences(0), [(0, 0), (1, 0)]);
+        assert!(index.occurrences(1).is_empty());
+        assert!(index.occurrences(9).is_empty());
+
+        let counts: Vec<(&str, usize)> = index
+            .term_counts(2, &terms)
+            .into_iter()
+            .map(|(t, n)| (t.lemma_form.as_str(), n))
+            .collect();
+        assert_eq!(counts, [("猫", 2), ("が", 1)]);
+    }
+
+    #[test]
+    fn finds_i_plus_one_sentences_ranked_by_frequency_then_length() {
+        let sentences = vec![
+            sentence(0, "猫が魚を食べた"),
+            sentence(1, "猫が寝る"),
+            sentence(2, "犬が吠えた"),
+            sentence(3, "太郎が吠えた"),
+            sentence(4, "吠えた"),
+        ];
+        let unknown = vec![
+            term("魚", POS::Noun, 900, &[0]),
+            term("寝る", POS::Verb, 300, &[1]),
+            term("吠える", POS::Verb, 5000, &[2, 3, 4]),
+            term("太郎", POS::ProperNoun, 20000, &[3]),
+        ];
+        let mut base = unknown.clone();
+        base.push(term("猫", POS::Noun, 100, &[0, 1]));
+        base.push(term("食べる", POS::Verb, 50, &[0]));
+        base.push(term("犬", POS::Noun, 200, &[2]));
+        base.push(term("が", POS::Postposition, 1, &[0, 1, 2, 3]));
+
+        let counts = |t: &Term| t.part_of_speech.is_vocabulary_word();
+        let found = find_target_sentences(&sentences, &base, &unknown, 1, counts);
+        // 3 has only a name beside 吠える and 4 has no known word at all.
+        let ids: Vec<usize> = found.iter().map(|t| t.sentence_id).collect();
+        assert_eq!(ids, vec![1, 0, 2]);
+        assert_eq!(found[0].unknown, vec![1]);
+
+        // 猫 unknown too: sentence 0 becomes i+2 and sentence 1 has nothing known.
+        let mut unknown_two = unknown.clone();
+        unknown_two.push(term("猫", POS::Noun, 100, &[0, 1]));
+        assert!(find_target_sentences(&sentences, &base, &unknown_two, 1, counts)
+            .iter()
+            .all(|t| t.sentence_id == 2));
+        let found = find_target_sentences(&sentences, &base, &unknown_two, 2, counts);
+        assert_eq!(found[0], TargetSentence { sentence_id: 0, unknown: vec![4, 0] });
+    }
 }
@@ -0,0 +1,234 @@
+//! Mines made while AnkiConnect was unreachable, kept in the data folder until
+//! it is back. Each entry is the fully rendered note, so flushing needs only
+//! Anki, not Yomitan or the file it was mined from.
+
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    sync::Mutex,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    api::{
+        add_note,
+        store_media_file,
+        AnkiConnection,
+    },
+    mined::record_mined_sentence,
+};
+
+pub const MINING_QUEUE: &str = "anki_mining_queue.json";
+
+/// Serializes read-modify-write of the queue file between a mine and a flush.
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A media file the note references, as the base64 payload `storeMediaFile` takes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedMedia {
+    pub filename: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedMine {
+    /// Unix milliseconds the mine was queued at, set by `enqueue_mine`; unique
+    /// within the queue.
+    pub id: i64,
+    pub term: String,
+    pub sentence: String,
+    pub deck: String,
+    pub model: String,
+    pub fields: HashMap<String, String>,
+    pub tags: Vec<String>,
+    pub media: Vec<QueuedMedia>,
+    /// Why Anki refused the mine (a missing deck or note type, a media file it
+    /// couldn't store). A refused mine stays queued but flushes skip it until
+    /// the user retries or discards it.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// What one flush did; mines it didn't reach stay queued.
+#[derive(Debug, Default)]
+pub struct FlushReport {
+    pub added: Vec<String>,
+    pub duplicates: Vec<String>,
+    /// Mines Anki refused, with its error. They stay queued as failed.
+    pub rejected: Vec<(String, String)>,
+    pub remaining: usize,
+}
+
+pub fn queued_mines() -> Vec<QueuedMine> {
+    crate::persistence::load_json_or_default(MINING_QUEUE)
+}
+
+/// Mines waiting for Anki, not counting failed ones.
+pub fn queued_count() -> usize {
+    if !crate::persistence::data_file_exists(MINING_QUEUE) {
+        return 0;
+    }
+    queued_mines().iter().filter(|m| m.error.is_none()).count()
+}
+
+/// Mines Anki refused, in queue order.
+pub fn failed_mines() -> Vec<QueuedMine> {
+    if !crate::persistence::data_file_exists(MINING_QUEUE) {
+        return Vec::new();
+    }
+    queued_mines().into_iter().filter(|m| m.error.is_some()).collect()
+}
+
+/// Put every failed mine back in line for the next flush.
+pub fn retry_failed() {
+    let _guard = QUEUE_LOCK.lock().unwrap();
+    let mut queue = queued_mines();
+    for mine in &mut queue {
+        mine.error = None;
+    }
+    save_queue(&queue);
+}
+
+/// Drop every failed mine from the queue.
+pub fn discard_failed() {
+    let _guard = QUEUE_LOCK.lock().unwrap();
+    let mut queue = queued_mines();
+    queue.retain(|m| m.error.is_none());
+    save_queue(&queue);
+}
+
+fn save_queue(queue: &[QueuedMine]) {
+    if let Err(e) = crate::persistence::save_json(&queue, MINING_QUEUE) {
+        eprintln!("Failed to save mining queue: {}", e);
+    }
+}
+
+/// Append a mine; returns how many mines wait for Anki, failed ones aside.
+pub fn enqueue_mine(mut mine: QueuedMine) -> usize {
+    let _guard = QUEUE_LOCK.lock().unwrap();
+    let mut queue = queued_mines();
+    let last_id = queue.iter().map(|m| m.id).max().unwrap_or(i64::MIN);
+    mine.id = chrono::Utc::now().timestamp_millis().max(last_id.saturating_add(1));
+    queue.push(mine);
+    save_queue(&queue);
+    queue.iter().filter(|m| m.error.is_none()).count()
+}
+
+/// The queue after a flush: the mines it added (or found in Anki) removed, the
+/// ones Anki refused marked failed, and any queued meanwhile kept.
+fn after_flush(
+    queue: Vec<QueuedMine>,
+    processed: &HashSet<i64>,
+    failed: &HashMap<i64, String>,
+) -> Vec<QueuedMine> {
+    queue
+        .into_iter()
+        .filter(|mine| !processed.contains(&mine.id))
+        .map(|mut mine| {
+            if let Some(err) = failed.get(&mine.id) {
+                mine.error = Some(err.clone());
+            }
+            mine
+        })
+        .collect()
+}
+
+/// Add the queued mines to Anki in the order they were made, recording each
+/// sentence as `mine_term` does. A mine whose media or note Anki refuses is
+/// kept as failed, never added without its media. Stops at the first request
+/// that doesn't reach AnkiConnect, leaving it and the rest queued for the next
+/// attempt.
+pub async fn flush_queue(connection: &AnkiConnection) -> FlushReport {
+    let mut report = FlushReport::default();
+    let mut processed = HashSet::new();
+    let mut failed = HashMap::new();
+
+    'mines: for mine in queued_mines().into_iter().filter(|m| m.error.is_none()) {
+        for media in &mine.media {
+            match store_media_file(connection, &media.filename, &media.content).await {
+                Ok(response) => {
+                    if let Some(err) = response.error {
+                        let err = format!("storing {}: {}", media.filename, err);
+                        report.rejected.push((mine.term, err.clone()));
+                        failed.insert(mine.id, err);
+                        continue 'mines;
+                    }
+                }
+                Err(_) => break 'mines,
+            }
+        }
+        let Ok(response) =
+            add_note(connection, &mine.deck, &mine.model, &mine.fields, &mine.tags).await
+        else {
+            break;
+        };
+        match (response.result, response.error) {
+            (_, Some(err)) if err.contains("duplicate") => {
+                report.duplicates.push(mine.term);
+                processed.insert(mine.id);
+            }
+            (_, Some(err)) => {
+                report.rejected.push((mine.term, err.clone()));
+                failed.insert(mine.id, err);
+            }
+            (note_id, None) => {
+                if let Some(id) = note_id {
+                    record_mined_sentence(id, &mine.sentence);
+                }
+                report.added.push(mine.term);
+                processed.insert(mine.id);
+            }
+        }
+    }
+
+    if !processed.is_empty() || !failed.is_empty() {
+        let _guard = QUEUE_LOCK.lock().unwrap();
+        save_queue(&after_flush(queued_mines(), &processed, &failed));
+    }
+    report.remaining = queued_count();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mine(id: i64, term: &str) -> QueuedMine {
+        QueuedMine {
+            id,
+            term: term.to_string(),
+            sentence: String::new(),
+            deck: "Mining".to_string(),
+            model: "Lapis".to_string(),
+            fields: HashMap::new(),
+            tags: vec!["yomine".to_string()],
+            media: Vec::new(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn flush_keeps_mines_queued_while_it_ran() {
+        // 1 and 2 were flushed; 3 was queued by a mine during the flush.
+        let queue = vec![mine(1, "猫"), mine(2, "犬"), mine(3, "鳥")];
+        let left = after_flush(queue, &HashSet::from([1, 2]), &HashMap::new());
+        let terms: Vec<&str> = left.iter().map(|m| m.term.as_str()).collect();
+        assert_eq!(terms, ["鳥"]);
+    }
+
+    #[test]
+    fn refused_mines_stay_queued_as_failed() {
+        let queue = vec![mine(1, "猫"), mine(2, "犬")];
+        let failed = HashMap::from([(2, "deck was not found".to_string())]);
+        let left = after_flush(queue, &HashSet::from([1]), &failed);
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].term, "犬");
+        assert_eq!(left[0].error.as_deref(), Some("deck was not found"));
+    }
+}
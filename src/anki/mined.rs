@@ -1,17 +1,22 @@
 //! Already-mined detection (issue #3): terms with a recently-added card and
 //! sentences that already exist in the user's notes.
 
-use std::collections::HashMap;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
 
 use super::{
     api::{
         get_note_ids,
         get_notes,
+        AnkiConnection,
     },
+    state::known_vocab_query,
     types::FieldMapping,
 };
 
-/// Sentence-field harvest, overwritten by each `get_total_vocab` pass.
+/// Sentence-field harvest, rewritten by each vocab sync's note pass.
 pub const MINED_SENTENCE_CACHE: &str = "anki_mined_sentences.json";
 
 /// Sentences Yomine itself mined — survives without a sentence-field mapping.
@@ -24,15 +29,21 @@ pub struct MinedSentence {
     pub sentence: String,
 }
 
-/// Terms + normalized sentences from notes added in the last day (`added:1`).
+/// Terms + normalized sentences from notes added in the last day (`added:1`),
+/// within the known-vocabulary scope.
 pub async fn get_recently_added(
+    connection: &AnkiConnection,
     model_mapping: &HashMap<String, FieldMapping>,
+    vocab_query: &str,
 ) -> Result<(Vec<String>, Vec<String>), reqwest::Error> {
-    let note_ids = get_note_ids("added:1").await?;
+    let Some(scope) = known_vocab_query(model_mapping, vocab_query) else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+    let note_ids = get_note_ids(connection, &format!("added:1 ({})", scope)).await?;
     if note_ids.is_empty() {
         return Ok((Vec::new(), Vec::new()));
     }
-    let notes = get_notes(note_ids).await?;
+    let notes = get_notes(connection, note_ids).await?;
 
     let mut terms = Vec::new();
     let mut sentences = Vec::new();
@@ -56,13 +67,28 @@ pub async fn get_recently_added(
     Ok((terms, sentences))
 }
 
-/// Written by the `get_total_vocab` harvest.
+/// Written by a full vocab sync's harvest.
 pub fn save_harvested_sentences(entries: &[MinedSentence]) {
     if let Err(e) = crate::persistence::save_json(&entries, MINED_SENTENCE_CACHE) {
         eprintln!("Failed to save mined sentence cache: {}", e);
     }
 }
 
+/// Incremental counterpart of `save_harvested_sentences`: drop entries of
+/// notes that left the scope or were refetched, then add the refetched ones.
+pub fn merge_harvested_sentences(
+    current: &HashSet<u64>,
+    refetched: &HashSet<u64>,
+    fresh: Vec<MinedSentence>,
+) {
+    let mut harvested: Vec<MinedSentence> =
+        crate::persistence::load_json_or_default(MINED_SENTENCE_CACHE);
+    harvested
+        .retain(|entry| current.contains(&entry.note_id) && !refetched.contains(&entry.note_id));
+    harvested.extend(fresh);
+    save_harvested_sentences(&harvested);
+}
+
 pub fn record_mined_sentence(note_id: u64, raw: &str) {
     let normalized = normalize_sentence(raw);
     if normalized.is_empty() {
@@ -79,12 +105,15 @@ pub fn record_mined_sentence(note_id: u64, raw: &str) {
 }
 
 /// Note ids that still exist in Anki; `None` when unreachable (keep caches).
-async fn existing_note_ids(ids: &[u64]) -> Option<std::collections::HashSet<u64>> {
+async fn existing_note_ids(
+    connection: &AnkiConnection,
+    ids: &[u64],
+) -> Option<std::collections::HashSet<u64>> {
     let mut existing = std::collections::HashSet::new();
     for chunk in ids.chunks(500) {
         let query =
             format!("nid:{}", chunk.iter().map(u64::to_string).collect::<Vec<_>>().join(","));
-        match get_note_ids(&query).await {
+        match get_note_ids(connection, &query).await {
             Ok(found) => existing.extend(found),
             Err(_) => return None,
         }
@@ -94,7 +123,7 @@ async fn existing_note_ids(ids: &[u64]) -> Option<std::collections::HashSet<u64>
 
 /// Harvest + recorded mines, minus notes since deleted in Anki (both caches
 /// pruned in passing).
-pub async fn mined_sentences_pruned() -> Vec<String> {
+pub async fn mined_sentences_pruned(connection: &AnkiConnection) -> Vec<String> {
     let mut harvested: Vec<MinedSentence> =
         crate::persistence::load_json_or_default(MINED_SENTENCE_CACHE);
     let mut recorded: Vec<MinedSentence> =
@@ -103,7 +132,7 @@ pub async fn mined_sentences_pruned() -> Vec<String> {
     let ids: Vec<u64> =
         harvested.iter().chain(recorded.iter()).map(|entry| entry.note_id).collect();
     if !ids.is_empty() {
-        if let Some(existing) = existing_note_ids(&ids).await {
+        if let Some(existing) = existing_note_ids(connection, &ids).await {
             let before = (harvested.len(), recorded.len());
             harvested.retain(|entry| existing.contains(&entry.note_id));
             recorded.retain(|entry| existing.contains(&entry.note_id));
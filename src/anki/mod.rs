@@ -1,9 +1,11 @@
 pub mod api;
 pub mod comprehensibility;
 pub mod field_guessing;
+pub mod mine_queue;
 pub mod mined;
 pub mod scoring;
 pub mod state;
+pub mod sync;
 pub mod types;
 
 pub use field_guessing::{
@@ -13,7 +15,6 @@ pub use field_guessing::{
 pub use state::{
     get_models,
     get_sample_note_for_model,
-    get_total_vocab,
     has_cached_vocab,
     wait_awake,
     AnkiState,
@@ -207,6 +207,7 @@ mod tests {
 
     use super::*;
     use crate::{
+        anki::types::CardType,
         dictionary::{
             frequency_dict::FrequencyDictionary,
             JsonFrequency,
@@ -240,8 +241,16 @@ mod tests {
         Vocab {
             term: term.to_string(),
             reading: reading.to_string(),
+            note_id: None,
             card_id: None,
             interval: None,
+            stability: None,
+            difficulty: None,
+            last_review: None,
+            card_type: CardType::Review,
+            suspended: false,
+            buried: false,
+            lapses: 0,
         }
     }
 
@@ -22,116 +22,93 @@ use wana_kana::IsJapaneseStr;
 
 use super::{
     api::{
+        get_card_states,
+        get_desired_retention,
         get_field_names,
         get_intervals,
         get_model_ids,
         get_note_ids,
         get_notes,
+        get_reviews_of_cards,
         get_version,
+        AnkiConnection,
+        CardState,
     },
+    mined::MinedSentence,
     scoring::{
         AnkiMatcher,
         KEEP_TERM_THRESHOLD,
     },
+    sync::{
+        sync_vocab,
+        VocabCache,
+        ANKI_VOCAB_CACHE,
+    },
     types::{
+        CardType,
         FieldMapping,
         Model,
         Vocab,
     },
 };
 use crate::{
-    anki::comprehensibility::comp_term,
+    anki::comprehensibility::{
+        comp_term,
+        comp_vocab,
+        fsrs_memory_state,
+        lapse_count,
+        DEFAULT_DESIRED_RETENTION,
+    },
     core::{
         utils::{
             normalize_japanese_text,
             FilterKana,
             NormalizeLongVowel,
         },
+        SuspendedCards,
         Term,
     },
     dictionary::frequency_manager::FrequencyManager,
 };
 
-const ANKI_VOCAB_CACHE: &str = "anki_vocab_cache.json";
-
 pub struct AnkiState {
     vocab: Vec<Vocab>,
     matcher: AnkiMatcher,
     relevance_map: HashMap<String, Vec<usize>>, // Map to indices
     known_interval: u32,                        // From settings, for calculating comprehension
+    now: i64,                                   // Unix seconds retrievability is evaluated at
+    suspended_cards: SuspendedCards,            // From settings, how suspended vocab counts
 }
 
 impl AnkiState {
     pub async fn new(
+        connection: &AnkiConnection,
         model_mapping: HashMap<String, FieldMapping>,
+        vocab_query: &str,
         frequency_manager: Arc<FrequencyManager>,
         known_interval: u32,
+        suspended_cards: SuspendedCards,
     ) -> Result<Self, reqwest::Error> {
         let start = Instant::now();
-        let mut vocab = get_total_vocab(&model_mapping).await?;
-        println!(
-            "Loaded {} vocab items from Anki ({:.1}s)",
-            vocab.len(),
-            start.elapsed().as_secs_f32()
-        );
-
-        // Fetch card intervals and set them on vocab
-        let card_ids: Vec<u64> = vocab.iter().filter_map(|v| v.card_id).collect();
-
-        let intervals_request_start = Instant::now();
-        let intervals = get_intervals(card_ids.clone()).await?;
-        println!(
-            "  getIntervals request: {} cards ({:.2}s)",
-            card_ids.len(),
-            intervals_request_start.elapsed().as_secs_f32()
-        );
-
-        let processing_start = Instant::now();
-        let card_intervals: HashMap<u64, i32> =
-            card_ids.into_iter().zip(intervals.into_iter()).collect();
-
-        // Set intervals on vocab items
-        let mut intervals_set = 0;
-        for vocab_item in &mut vocab {
-            if let Some(card_id) = vocab_item.card_id {
-                if let Some(&interval) = card_intervals.get(&card_id) {
-                    // Negative intervals are in seconds (learning/relearning), positive in days
-                    vocab_item.interval = Some(if interval >= 0 {
-                        interval as f32
-                    } else {
-                        interval.abs() as f32 / 86400.0
-                    });
-                    intervals_set += 1;
-                }
-            }
-        }
-        println!(
-            "  Processing intervals: {}/{} set ({:.2}s)",
-            intervals_set,
-            vocab.len(),
-            processing_start.elapsed().as_secs_f32()
-        );
-
-        // Persist the freshly fetched vocab so it can be reused offline / for fast loads
-        if vocab.is_empty() {
-            eprintln!("Anki returned no vocab; keeping the existing cache");
-        } else if let Err(e) = crate::persistence::save_json(&vocab, ANKI_VOCAB_CACHE) {
-            eprintln!("Failed to save Anki vocab cache: {}", e);
-        }
-
-        println!("AnkiState initialized ({:.1}s total)", start.elapsed().as_secs_f32());
-        Ok(Self::from_vocab(vocab, frequency_manager, known_interval))
+        let vocab = sync_vocab(connection, &model_mapping, vocab_query).await?;
+        eprintln!("AnkiState initialized ({:.1}s total)", start.elapsed().as_secs_f32());
+        Ok(Self::from_vocab(vocab, frequency_manager, known_interval, suspended_cards))
     }
 
     /// Build an `AnkiState` from an already-fetched vocab list (no network).
     fn from_vocab(
-        vocab: Vec<Vocab>,
+        mut vocab: Vec<Vocab>,
         frequency_manager: Arc<FrequencyManager>,
         known_interval: u32,
+        suspended_cards: SuspendedCards,
     ) -> Self {
+        if suspended_cards == SuspendedCards::Ignored {
+            vocab.retain(|v| !v.suspended);
+        }
         let relevance_map = Self::build_relevance_map(&vocab);
         let matcher = AnkiMatcher::new(frequency_manager);
-        Self { vocab, matcher, relevance_map, known_interval }
+        let now = chrono::Utc::now().timestamp();
+        Self { vocab, matcher, relevance_map, known_interval, now, suspended_cards }
     }
 
     /// Build an `AnkiState` from the on-disk vocab cache, if one exists. Returns
@@ -139,13 +116,14 @@ impl AnkiState {
     pub fn from_cache(
         frequency_manager: Arc<FrequencyManager>,
         known_interval: u32,
+        suspended_cards: SuspendedCards,
     ) -> Option<Self> {
-        let vocab: Vec<Vocab> = crate::persistence::load_json_or_default(ANKI_VOCAB_CACHE);
+        let vocab = VocabCache::load().vocab;
         if vocab.is_empty() {
             return None;
         }
-        println!("Loaded {} vocab items from Anki cache", vocab.len());
-        Some(Self::from_vocab(vocab, frequency_manager, known_interval))
+        eprintln!("Loaded {} vocab items from Anki cache", vocab.len());
+        Some(Self::from_vocab(vocab, frequency_manager, known_interval, suspended_cards))
     }
 
     /// One time map for the anki vocab to quickly find the potential matches by key
@@ -243,7 +221,7 @@ impl AnkiState {
             }
         }
 
-        println!(
+        eprintln!(
             "Term matching: {} unknown, {} known ({:.2}s)",
             filtered_terms.len(),
             known_terms.len(),
@@ -255,8 +233,11 @@ impl AnkiState {
     }
 
     /// Inclusivity score + comprehension (0..1) for one word form against its best Anki
-    /// match. Unmatched forms fall back to a 1-day interval — the exact rule used for
-    /// per-term/sentence/file comprehension. Shared so there's a single source of truth.
+    /// match: FSRS retrievability when the card has a memory state, else its interval.
+    /// Unmatched forms and cards without either fall back to a 1-day interval — the
+    /// exact rule used for per-term/sentence/file comprehension. Shared so there's a
+    /// single source of truth. A suspended match counting as unknown scores nothing,
+    /// so the term stays in the unknown table.
     fn match_form(
         &self,
         word: &str,
@@ -264,12 +245,18 @@ impl AnkiState {
         pos: &crate::segmentation::word::POS,
     ) -> (f32, f32) {
         let (score, vocab_idx) = self.highest_inclusivity_score(word, reading, pos);
-        let interval = if let Some(idx) = vocab_idx {
-            self.vocab[idx].interval.or(Some(1.0))
-        } else {
-            Some(1.0)
+        let comprehension = match vocab_idx.map(|idx| &self.vocab[idx]) {
+            Some(vocab) if self.counts_as_unknown(vocab) => return (0.0, 0.0),
+            Some(vocab)
+                if vocab.interval.is_some()
+                    || vocab.stability.is_some()
+                    || vocab.card_type != CardType::Review =>
+            {
+                comp_vocab(vocab, self.known_interval, self.now)
+            }
+            _ => comp_term(Some(1.0), self.known_interval),
         };
-        (score, comp_term(interval, self.known_interval))
+        (score, comprehension)
     }
 
     /// (in_anki, estimated comprehension)
@@ -290,49 +277,87 @@ impl AnkiState {
     pub fn vocab(&self) -> &[Vocab] {
         &self.vocab
     }
+
+    /// A suspended card under `SuspendedCards::Unknown`: in Anki, but neither
+    /// covered nor comprehended.
+    pub fn counts_as_unknown(&self, vocab: &Vocab) -> bool {
+        vocab.suspended && self.suspended_cards == SuspendedCards::Unknown
+    }
 }
 
 pub fn has_cached_vocab() -> bool {
     crate::persistence::data_file_exists(ANKI_VOCAB_CACHE)
 }
 
-pub async fn get_total_vocab(
+/// `note:` search for one note type, quoted when the name needs it.
+fn note_type_query(model_name: &str) -> String {
+    if model_name.contains(' ') || model_name.contains(':') || model_name.contains('"') {
+        format!("note:\"{}\"", model_name.replace('"', "\\\""))
+    } else {
+        format!("note:{}", model_name)
+    }
+}
+
+/// The Anki search for notes that count as known vocabulary: every mapped note
+/// type narrowed by its own query, all narrowed by the global `vocab_query`.
+/// `None` without mappings, when nothing can count.
+pub fn known_vocab_query(
     model_mapping: &HashMap<String, FieldMapping>,
-) -> Result<Vec<Vocab>, reqwest::Error> {
-    let deck_query = "deck:*";
-
-    let note_ids_start = Instant::now();
-    let note_ids = get_note_ids(&deck_query).await?;
-    println!(
-        "  findNotes request: {} notes ({:.2}s)",
-        note_ids.len(),
-        note_ids_start.elapsed().as_secs_f32()
-    );
+    vocab_query: &str,
+) -> Option<String> {
+    let mut names: Vec<&String> = model_mapping.keys().collect();
+    names.sort();
+    let note_types: Vec<String> = names
+        .into_iter()
+        .map(|name| {
+            let scope = model_mapping[name].query.as_deref().map(str::trim).unwrap_or_default();
+            if scope.is_empty() {
+                note_type_query(name)
+            } else {
+                format!("({} ({}))", note_type_query(name), scope)
+            }
+        })
+        .collect();
+    if note_types.is_empty() {
+        return None;
+    }
+    let note_types = note_types.join(" OR ");
+    Some(match vocab_query.trim() {
+        "" => note_types,
+        global => format!("({}) ({})", global, note_types),
+    })
+}
 
+/// Vocab (without card state) and sentence-field values of the given notes.
+pub(super) async fn fetch_vocab(
+    connection: &AnkiConnection,
+    note_ids: Vec<u64>,
+    model_mapping: &HashMap<String, FieldMapping>,
+) -> Result<(Vec<Vocab>, Vec<MinedSentence>), reqwest::Error> {
+    if note_ids.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
     let notes_start = Instant::now();
-    let notes = get_notes(note_ids).await?;
+    let notes = get_notes(connection, note_ids).await?;
     let notes_request_time = notes_start.elapsed();
-    println!(
+    eprintln!(
         "  notesInfo request: {} notes ({:.2}s)",
         notes.len(),
         notes_request_time.as_secs_f32()
     );
 
     // Harvest sentence-field values while every note is in hand (issue #3).
-    let mined_sentences: Vec<super::mined::MinedSentence> = notes
+    let mined_sentences: Vec<MinedSentence> = notes
         .iter()
         .filter_map(|note| {
             let mapping = model_mapping.get(&note.model_name)?;
             let sentence_field = mapping.sentence_field.as_ref()?;
             let value = &note.fields.get(sentence_field)?.value;
             let normalized = super::mined::normalize_sentence(value);
-            (!normalized.is_empty()).then_some(super::mined::MinedSentence {
-                note_id: note.note_id,
-                sentence: normalized,
-            })
+            (!normalized.is_empty())
+                .then_some(MinedSentence { note_id: note.note_id, sentence: normalized })
         })
         .collect();
-    super::mined::save_harvested_sentences(&mined_sentences);
 
     let processing_start = Instant::now();
     let relevant_models: HashSet<&String> = model_mapping.keys().collect();
@@ -352,8 +377,16 @@ pub async fn get_total_vocab(
                         return Some(Vocab {
                             term,
                             reading: reading.filter_kana().normalize_long_vowel().into_owned(),
+                            note_id: Some(note.note_id),
                             card_id: note.cards.first().copied(),
                             interval: None, // Will be set after fetching cards
+                            stability: None,
+                            difficulty: None,
+                            last_review: None,
+                            card_type: CardType::Review, // Set from the card queues
+                            suspended: false,
+                            buried: false,
+                            lapses: 0,
                         });
                     }
                 }
@@ -362,35 +395,126 @@ pub async fn get_total_vocab(
         })
         .collect();
 
-    println!(
+    eprintln!(
         "  Processing notes: {} vocab items ({:.2}s)",
         vocab.len(),
         processing_start.elapsed().as_secs_f32()
     );
 
-    Ok(vocab)
+    Ok((vocab, mined_sentences))
+}
+
+/// Refresh the card state of the vocab items whose card is in `card_ids`: type
+/// and queue from `cardsInfo`, the interval, and lapses plus, when FSRS
+/// scheduled the card, its memory state from the review log.
+pub(super) async fn fetch_card_state(
+    connection: &AnkiConnection,
+    card_ids: Vec<u64>,
+    vocab: &mut [Vocab],
+) -> Result<(), reqwest::Error> {
+    if card_ids.is_empty() {
+        return Ok(());
+    }
+
+    let cards_request_start = Instant::now();
+    let cards: HashMap<u64, CardState> = get_card_states(connection, card_ids.clone())
+        .await?
+        .into_iter()
+        .map(|card| (card.card_id, card))
+        .collect();
+    eprintln!(
+        "  cardsInfo request: {} cards ({:.2}s)",
+        cards.len(),
+        cards_request_start.elapsed().as_secs_f32()
+    );
+
+    let intervals_request_start = Instant::now();
+    let intervals = get_intervals(connection, card_ids.clone()).await?;
+    eprintln!(
+        "  getIntervals request: {} cards ({:.2}s)",
+        card_ids.len(),
+        intervals_request_start.elapsed().as_secs_f32()
+    );
+    let card_intervals: HashMap<u64, i32> =
+        card_ids.iter().copied().zip(intervals.into_iter()).collect();
+
+    for vocab_item in vocab.iter_mut() {
+        let Some(card_id) = vocab_item.card_id else { continue };
+        if let Some(card) = cards.get(&card_id) {
+            vocab_item.suspended = card.queue == -1;
+            vocab_item.buried = matches!(card.queue, -2 | -3);
+            vocab_item.card_type = match card.card_type {
+                0 => CardType::New,
+                1 => CardType::Learning,
+                3 => CardType::Relearning,
+                _ => CardType::Review,
+            };
+        }
+        if let Some(&interval) = card_intervals.get(&card_id) {
+            // Negative intervals are in seconds (learning/relearning), positive in days
+            vocab_item.interval =
+                Some(if interval >= 0 { interval as f32 } else { interval.abs() as f32 / 86400.0 });
+        }
+    }
+
+    // FSRS memory state from each card's review log, read at its deck's
+    // desired retention. Best-effort: without it comprehension falls back to
+    // the interval model.
+    let reviews_request_start = Instant::now();
+    let mut retention: HashMap<&str, f32> = HashMap::new();
+    for card in cards.values() {
+        if !retention.contains_key(card.deck_name.as_str()) {
+            let desired = get_desired_retention(connection, &card.deck_name)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(DEFAULT_DESIRED_RETENTION);
+            retention.insert(&card.deck_name, desired);
+        }
+    }
+    match get_reviews_of_cards(connection, card_ids).await {
+        Ok(reviews) => {
+            let mut states_set = 0;
+            for vocab_item in vocab.iter_mut() {
+                let Some(card_id) = vocab_item.card_id else { continue };
+                let Some(log) = reviews.get(&card_id.to_string()) else { continue };
+                let desired = cards
+                    .get(&card_id)
+                    .and_then(|card| retention.get(card.deck_name.as_str()))
+                    .copied()
+                    .unwrap_or(DEFAULT_DESIRED_RETENTION);
+                let state = fsrs_memory_state(log, desired);
+                vocab_item.lapses = lapse_count(log);
+                vocab_item.stability = state.map(|(stability, _, _)| stability);
+                vocab_item.difficulty = state.map(|(_, difficulty, _)| difficulty);
+                vocab_item.last_review = state.map(|(_, _, last_review)| last_review);
+                states_set += state.is_some() as usize;
+            }
+            eprintln!(
+                "  getReviewsOfCards: {}/{} FSRS states ({:.2}s)",
+                states_set,
+                cards.len(),
+                reviews_request_start.elapsed().as_secs_f32()
+            );
+        }
+        Err(e) => eprintln!("Failed to fetch review logs, using intervals: {}", e),
+    }
+    Ok(())
 }
 
-pub async fn get_models() -> Result<Vec<Model>, reqwest::Error> {
-    let model_ids = get_model_ids().await?;
+pub async fn get_models(connection: &AnkiConnection) -> Result<Vec<Model>, reqwest::Error> {
+    let model_ids = get_model_ids(connection).await?;
 
     let handles: Vec<_> = model_ids
         .into_iter()
         .map(|(model_name, id)| {
+            let connection = connection.clone();
             task::spawn(async move {
-                let fields = get_field_names(&model_name).await?;
+                let fields = get_field_names(&connection, &model_name).await?;
 
                 // Get note count for this model
-                let query = if model_name.contains(' ')
-                    || model_name.contains(':')
-                    || model_name.contains('"')
-                {
-                    format!("note:\"{}\"", model_name.replace('"', "\\\""))
-                } else {
-                    format!("note:{}", model_name)
-                };
-
-                let note_count = match get_note_ids(&query).await {
+                let query = note_type_query(&model_name);
+                let note_count = match get_note_ids(&connection, &query).await {
                     Ok(note_ids) => note_ids.len(),
                     Err(_) => 0,
                 };
@@ -422,15 +546,19 @@ pub async fn get_models() -> Result<Vec<Model>, reqwest::Error> {
     Ok(models)
 }
 
-pub async fn wait_awake(wait_time: u64, max_attempts: u32) -> Result<bool, reqwest::Error> {
+pub async fn wait_awake(
+    connection: &AnkiConnection,
+    wait_time: u64,
+    max_attempts: u32,
+) -> Result<bool, reqwest::Error> {
     for attempt in 1..=max_attempts {
-        match get_version().await {
+        match get_version(connection).await {
             Ok(version) => {
-                println!("AnkiConnect is online. Version: {}", version);
+                eprintln!("AnkiConnect is online. Version: {}", version);
                 return Ok(true);
             }
             Err(err) => {
-                println!(
+                eprintln!(
                     "AnkiConnect attempt {} of {} failed. Retrying in {} seconds... Error: {}",
                     attempt, max_attempts, wait_time, err
                 );
@@ -444,11 +572,12 @@ pub async fn wait_awake(wait_time: u64, max_attempts: u32) -> Result<bool, reqwe
 }
 
 pub async fn get_sample_note_for_model(
+    connection: &AnkiConnection,
     model_name: &str,
 ) -> Result<Option<HashMap<String, String>>, reqwest::Error> {
     use super::api::get_sample_note_for_model;
 
-    match get_sample_note_for_model(model_name).await? {
+    match get_sample_note_for_model(connection, model_name).await? {
         Some(note) => {
             let mut sample_fields = HashMap::new();
             for (field_name, field) in note.fields {
@@ -459,3 +588,77 @@ pub async fn get_sample_note_for_model(
         None => Ok(None),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segmentation::word::POS;
+
+    fn mapping(query: Option<&str>) -> FieldMapping {
+        FieldMapping {
+            term_field: "Word".to_string(),
+            reading_field: "Reading".to_string(),
+            sentence_field: None,
+            translation_field: None,
+            query: query.map(String::from),
+        }
+    }
+
+    #[test]
+    fn known_vocab_query_scopes_each_note_type_and_the_whole_harvest() {
+        assert_eq!(known_vocab_query(&HashMap::new(), "deck:Mining"), None);
+
+        let mappings = HashMap::from([
+            ("Lapis".to_string(), mapping(Some("deck:Mining"))),
+            ("Kaishi 1.5k".to_string(), mapping(Some("  "))),
+        ]);
+        assert_eq!(
+            known_vocab_query(&mappings, "").unwrap(),
+            "note:\"Kaishi 1.5k\" OR (note:Lapis (deck:Mining))"
+        );
+        assert_eq!(
+            known_vocab_query(&mappings, " -is:suspended ").unwrap(),
+            "(-is:suspended) (note:\"Kaishi 1.5k\" OR (note:Lapis (deck:Mining)))"
+        );
+    }
+
+    fn state(suspended_cards: SuspendedCards) -> AnkiState {
+        let card = |term: &str, reading: &str, suspended: bool| Vocab {
+            term: term.to_string(),
+            reading: reading.to_string(),
+            note_id: Some(1),
+            card_id: Some(1),
+            interval: Some(30.0),
+            stability: None,
+            difficulty: None,
+            last_review: None,
+            card_type: CardType::Review,
+            suspended,
+            buried: false,
+            lapses: 8,
+        };
+        AnkiState::from_vocab(
+            vec![card("猫", "ねこ", true), card("犬", "いぬ", false)],
+            Arc::new(FrequencyManager::from_dictionaries(Vec::new())),
+            21,
+            suspended_cards,
+        )
+    }
+
+    #[test]
+    fn suspended_cards_count_as_configured() {
+        assert_eq!(state(SuspendedCards::Known).word_stats("猫", "ねこ", &POS::Noun), (true, 1.0));
+        assert_eq!(
+            state(SuspendedCards::Unknown).word_stats("猫", "ねこ", &POS::Noun),
+            (false, 0.0)
+        );
+        let ignored = state(SuspendedCards::Ignored);
+        assert_eq!(ignored.word_stats("猫", "ねこ", &POS::Noun), (false, 0.0));
+        assert_eq!(ignored.vocab().len(), 1);
+
+        // Unsuspended cards are unaffected.
+        for setting in [SuspendedCards::Known, SuspendedCards::Unknown, SuspendedCards::Ignored] {
+            assert_eq!(state(setting).word_stats("犬", "いぬ", &POS::Noun), (true, 1.0));
+        }
+    }
+}
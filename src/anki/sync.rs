@@ -0,0 +1,331 @@
+//! Incremental Anki vocab sync. The vocab cache remembers when, and with which
+//! search and mappings, it was harvested, so a live refresh only fetches notes
+//! added, edited or reviewed since the last sync, drops notes that were deleted
+//! or left the search, and merges the rest into the cached `Vocab` list. A
+//! changed scope, a pre-sync cache or a week without a full harvest falls back
+//! to fetching everything. Card state (queue, interval, review log) changes
+//! without an edit, so every sync compares the cache's card modification times
+//! and re-reads only the cards that moved; a refetched note whose card didn't
+//! keeps its cached card state.
+
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+        HashSet,
+    },
+    time::Instant,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    api::{
+        get_cards_mod_time,
+        get_note_ids,
+        AnkiConnection,
+        CardModTime,
+    },
+    mined::{
+        merge_harvested_sentences,
+        save_harvested_sentences,
+    },
+    state::{
+        fetch_card_state,
+        fetch_vocab,
+        known_vocab_query,
+    },
+    types::{
+        FieldMapping,
+        Vocab,
+    },
+};
+
+pub const ANKI_VOCAB_CACHE: &str = "anki_vocab_cache.json";
+
+/// A full harvest at least this often catches what the `edited`/`rated`/`added`
+/// searches can't see, like manual reschedules.
+const FULL_SYNC_INTERVAL_SECS: i64 = 7 * 86_400;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// `anki_vocab_cache.json`: the harvested vocab plus what the next sync needs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VocabCache {
+    /// Endpoint, search and mappings the vocab was harvested with.
+    pub scope: String,
+    /// Unix seconds of the last sync, and of the last full harvest.
+    pub synced_at: i64,
+    pub full_synced_at: i64,
+    /// Every note the search matched, including ones that gave no vocab.
+    pub note_ids: Vec<u64>,
+    pub vocab: Vec<Vocab>,
+    /// Modification time of each vocab card when its state was last read.
+    #[serde(default)]
+    pub card_mods: HashMap<u64, i64>,
+}
+
+/// Caches written before incremental sync are a bare vocab list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredCache {
+    Synced(VocabCache),
+    Legacy(Vec<Vocab>),
+}
+
+impl Default for StoredCache {
+    fn default() -> Self {
+        StoredCache::Legacy(Vec::new())
+    }
+}
+
+impl VocabCache {
+    pub fn load() -> Self {
+        match crate::persistence::load_json_or_default::<StoredCache>(ANKI_VOCAB_CACHE) {
+            StoredCache::Synced(cache) => cache,
+            // No scope or sync time, so the next live refresh is a full one.
+            StoredCache::Legacy(vocab) => VocabCache { vocab, ..Default::default() },
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = crate::persistence::save_json(self, ANKI_VOCAB_CACHE) {
+            eprintln!("Failed to save Anki vocab cache: {}", e);
+        }
+    }
+}
+
+/// Fingerprint of everything the harvested vocab depends on besides the notes.
+fn sync_scope(
+    connection: &AnkiConnection,
+    model_mapping: &HashMap<String, FieldMapping>,
+    query: &str,
+) -> String {
+    let mappings: BTreeMap<&String, &FieldMapping> = model_mapping.iter().collect();
+    let mappings = serde_json::to_string(&mappings).unwrap_or_default();
+    format!("{}\u{1f}{}\u{1f}{}", connection.url, query, mappings)
+}
+
+/// `N` for Anki's `edited:N`-style searches covering everything since
+/// `synced_at`. Anki counts days from its day cutoff, so one spare day keeps a
+/// sync just before the cutoff covered.
+fn days_since(synced_at: i64, now: i64) -> i64 {
+    let elapsed = (now - synced_at).max(0);
+    (elapsed + SECONDS_PER_DAY - 1) / SECONDS_PER_DAY + 1
+}
+
+/// Notes to fetch: the changed ones plus any that newly match the search
+/// (moved into the deck, unsuspended), in `current` order.
+fn notes_to_fetch(cached: &[u64], current: &[u64], changed: &[u64]) -> Vec<u64> {
+    let cached: HashSet<u64> = cached.iter().copied().collect();
+    let changed: HashSet<u64> = changed.iter().copied().collect();
+    current.iter().copied().filter(|id| changed.contains(id) || !cached.contains(id)).collect()
+}
+
+/// Cached vocab minus notes that are gone or were refetched, plus the refetched.
+fn merge_vocab(
+    cached: Vec<Vocab>,
+    current: &HashSet<u64>,
+    refetched: &HashSet<u64>,
+    fetched: Vec<Vocab>,
+) -> Vec<Vocab> {
+    let mut vocab: Vec<Vocab> = cached
+        .into_iter()
+        .filter(|v| v.note_id.is_some_and(|id| current.contains(&id) && !refetched.contains(&id)))
+        .collect();
+    vocab.extend(fetched);
+    vocab
+}
+
+/// Give refetched vocab the card state its card had in the cache, and return
+/// the cards that had none.
+fn carry_card_state(fetched: &mut [Vocab], cached: &[Vocab]) -> HashSet<u64> {
+    let by_card: HashMap<u64, &Vocab> =
+        cached.iter().filter_map(|v| Some((v.card_id?, v))).collect();
+    let mut uncarried = HashSet::new();
+    for vocab in fetched {
+        let Some(card_id) = vocab.card_id else { continue };
+        match by_card.get(&card_id) {
+            Some(old) => {
+                vocab.interval = old.interval;
+                vocab.stability = old.stability;
+                vocab.difficulty = old.difficulty;
+                vocab.last_review = old.last_review;
+                vocab.card_type = old.card_type;
+                vocab.suspended = old.suspended;
+                vocab.buried = old.buried;
+                vocab.lapses = old.lapses;
+            }
+            None => {
+                uncarried.insert(card_id);
+            }
+        }
+    }
+    uncarried
+}
+
+/// Cards whose state needs re-reading: those without a cached state, and those
+/// modified since their state was read.
+fn cards_to_refresh(
+    cached: &HashMap<u64, i64>,
+    current: &[CardModTime],
+    uncarried: &HashSet<u64>,
+) -> Vec<u64> {
+    current
+        .iter()
+        .filter(|card| {
+            uncarried.contains(&card.card_id) || cached.get(&card.card_id) != Some(&card.modified)
+        })
+        .map(|card| card.card_id)
+        .collect()
+}
+
+/// Bring the vocab cache up to date with Anki and return its vocab.
+pub async fn sync_vocab(
+    connection: &AnkiConnection,
+    model_mapping: &HashMap<String, FieldMapping>,
+    vocab_query: &str,
+) -> Result<Vec<Vocab>, reqwest::Error> {
+    let Some(query) = known_vocab_query(model_mapping, vocab_query) else {
+        eprintln!("  No note types mapped; no known vocab to fetch");
+        return Ok(Vec::new());
+    };
+    let scope = sync_scope(connection, model_mapping, &query);
+    let now = chrono::Utc::now().timestamp();
+    let mut cache = VocabCache::load();
+
+    let note_ids_start = Instant::now();
+    let current_ids = get_note_ids(connection, &query).await?;
+    eprintln!(
+        "  findNotes request: {} notes ({:.2}s)",
+        current_ids.len(),
+        note_ids_start.elapsed().as_secs_f32()
+    );
+
+    let incremental = cache.scope == scope
+        && now - cache.full_synced_at < FULL_SYNC_INTERVAL_SECS
+        && cache.vocab.iter().all(|v| v.note_id.is_some());
+    let fetch_ids = if incremental {
+        let days = days_since(cache.synced_at, now);
+        let changed_query = format!("({query}) (edited:{days} OR rated:{days} OR added:{days})");
+        let changed = get_note_ids(connection, &changed_query).await?;
+        notes_to_fetch(&cache.note_ids, &current_ids, &changed)
+    } else {
+        current_ids.clone()
+    };
+
+    let (mut fetched, sentences) =
+        fetch_vocab(connection, fetch_ids.clone(), model_mapping).await?;
+    let uncarried = carry_card_state(&mut fetched, &cache.vocab);
+
+    if incremental {
+        let current: HashSet<u64> = current_ids.iter().copied().collect();
+        let refetched: HashSet<u64> = fetch_ids.into_iter().collect();
+        let before = cache.vocab.len();
+        merge_harvested_sentences(&current, &refetched, sentences);
+        cache.vocab = merge_vocab(std::mem::take(&mut cache.vocab), &current, &refetched, fetched);
+        eprintln!(
+            "Synced {} changed notes from Anki ({} → {} vocab items)",
+            refetched.len(),
+            before,
+            cache.vocab.len()
+        );
+    } else {
+        save_harvested_sentences(&sentences);
+        cache.vocab = fetched;
+        cache.scope = scope;
+        cache.full_synced_at = now;
+        eprintln!("Loaded {} vocab items from Anki (full sync)", cache.vocab.len());
+    }
+    let card_ids: Vec<u64> = cache.vocab.iter().filter_map(|v| v.card_id).collect();
+    let card_mods = get_cards_mod_time(connection, card_ids).await?;
+    let stale = cards_to_refresh(&cache.card_mods, &card_mods, &uncarried);
+    fetch_card_state(connection, stale, &mut cache.vocab).await?;
+    cache.card_mods = card_mods.into_iter().map(|card| (card.card_id, card.modified)).collect();
+    cache.synced_at = now;
+    cache.note_ids = current_ids;
+
+    // Persist the synced vocab so it can be reused offline / for fast loads
+    if cache.vocab.is_empty() {
+        eprintln!("Anki returned no vocab; keeping the existing cache");
+    } else {
+        cache.save();
+    }
+    Ok(cache.vocab)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anki::types::CardType;
+
+    fn vocab(term: &str, note_id: u64) -> Vocab {
+        Vocab {
+            term: term.to_string(),
+            reading: String::new(),
+            note_id: Some(note_id),
+            card_id: Some(note_id * 10),
+            interval: Some(5.0),
+            stability: None,
+            difficulty: None,
+            last_review: None,
+            card_type: CardType::Review,
+            suspended: false,
+            buried: false,
+            lapses: 0,
+        }
+    }
+
+    #[test]
+    fn incremental_sync_refetches_changed_and_new_notes_and_drops_deleted() {
+        let cached_ids = [1, 2, 3, 4];
+        // 2 was deleted, 3 edited, 5 moved into the searched deck.
+        let current_ids = [1, 3, 4, 5];
+        let fetch = notes_to_fetch(&cached_ids, &current_ids, &[3]);
+        assert_eq!(fetch, vec![3, 5]);
+
+        let cached = vec![vocab("猫", 1), vocab("犬", 2), vocab("鳥", 3), vocab("魚", 4)];
+        let current: HashSet<u64> = current_ids.into_iter().collect();
+        let refetched: HashSet<u64> = fetch.into_iter().collect();
+        let merged =
+            merge_vocab(cached, &current, &refetched, vec![vocab("鶏", 3), vocab("馬", 5)]);
+        let terms: Vec<&str> = merged.iter().map(|v| v.term.as_str()).collect();
+        assert_eq!(terms, ["猫", "魚", "鶏", "馬"]);
+    }
+
+    #[test]
+    fn card_state_is_reread_only_for_modified_or_unknown_cards() {
+        let mut reviewed = vocab("猫", 1);
+        reviewed.stability = Some(40.0);
+        let cached = vec![reviewed, vocab("犬", 2)];
+        // 猫's note was edited (its card wasn't), 鳥 is a new note.
+        let mut fetched = vec![vocab("猫", 1), vocab("鳥", 3)];
+        let uncarried = carry_card_state(&mut fetched, &cached);
+        assert_eq!(fetched[0].stability, Some(40.0));
+        assert_eq!(uncarried, HashSet::from([30]));
+
+        let cached_mods = HashMap::from([(10, 100), (20, 200)]);
+        let mod_time = |card_id, modified| CardModTime { card_id, modified };
+        // 犬 was suspended since the last sync.
+        let current = [mod_time(10, 100), mod_time(20, 250), mod_time(30, 300)];
+        assert_eq!(cards_to_refresh(&cached_mods, &current, &uncarried), vec![20, 30]);
+    }
+
+    #[test]
+    fn changed_note_search_covers_the_time_since_the_last_sync() {
+        assert_eq!(days_since(1_000, 1_000), 1);
+        assert_eq!(days_since(0, 60), 2);
+        assert_eq!(days_since(0, 3 * SECONDS_PER_DAY), 4);
+    }
+
+    #[test]
+    fn legacy_cache_loads_as_a_full_sync() {
+        let json = r#"[{"term":"猫","reading":"ねこ","card_id":7,"interval":3.0}]"#;
+        let StoredCache::Legacy(vocab) = serde_json::from_str(json).unwrap() else {
+            panic!("a bare vocab list is the legacy format");
+        };
+        assert_eq!(vocab[0].note_id, None);
+    }
+}
@@ -14,15 +14,58 @@ pub struct FieldMapping {
     /// Sentence field for already-mined detection (issue #3).
     #[serde(default)]
     pub sentence_field: Option<String>,
+    /// Field mined cards get the aligned secondary-subtitle line in.
+    #[serde(default)]
+    pub translation_field: Option<String>,
+    /// Anki search narrowing which of this note type's notes count as known
+    /// (e.g. `deck:Mining -is:suspended`); `None` = all of them.
+    #[serde(default)]
+    pub query: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Vocab {
     pub term: String,
     pub reading: String,
+    /// The note the vocab was harvested from; merges incremental syncs.
+    #[serde(default)]
+    pub note_id: Option<u64>,
     pub card_id: Option<u64>,
     pub interval: Option<f32>, // Interval in days (can be fractional for learning cards)
+    /// FSRS memory state of the card, when its last review was scheduled by FSRS.
+    /// Stability in days; difficulty on FSRS's 1..10 scale.
+    #[serde(default)]
+    pub stability: Option<f32>,
+    #[serde(default)]
+    pub difficulty: Option<f32>,
+    /// Unix seconds of the last review, the start of the forgetting curve.
+    #[serde(default)]
+    pub last_review: Option<i64>,
+    #[serde(default)]
+    pub card_type: CardType,
+    /// Queue state: suspended by hand or as a leech, buried until tomorrow.
+    #[serde(default)]
+    pub suspended: bool,
+    #[serde(default)]
+    pub buried: bool,
+    /// Times the card was forgotten in review.
+    #[serde(default)]
+    pub lapses: u32,
+}
+
+/// Anki's card type. Caches from before card states were recorded load as
+/// `Review`, the interval-only model they were scored with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardType {
+    /// Never studied.
+    New,
+    /// In its first learning steps.
+    Learning,
+    #[default]
+    Review,
+    /// Forgotten in review, back in learning steps.
+    Relearning,
 }
 
-//TODO: Comprehensibility via FSRS state
-//Tsunagi integration to fetch FSRS parameters
+//TODO: Tsunagi integration to fetch FSRS parameters
@@ -0,0 +1,142 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::core::{
+    models::RubyHint,
+    utils::is_kanji_char,
+};
+
+/// `［＃…］` editorial annotations, plus the `※` gaiji marker one may describe.
+static ANNOTATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"※?［＃[^］]*］").expect("Failed to compile aozora annotation regex")
+});
+
+/// Implicit ruby (`漢字《かんじ》`) — the sniffing signal when no explicit markers appear.
+static IMPLICIT_RUBY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\p{Han}《[\p{Hiragana}\p{Katakana}ー]+》")
+        .expect("Failed to compile aozora ruby regex")
+});
+
+/// Whether a plain-text file looks like Aozora Bunko markup: the notation legend,
+/// an editorial annotation, or a few implicit ruby pairs.
+pub fn is_aozora(text: &str) -> bool {
+    text.contains("【テキスト中に現れる記号について】")
+        || text.contains("［＃")
+        || IMPLICIT_RUBY.find_iter(text).take(3).count() == 3
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 10 && line.chars().all(|c| c == '-')
+}
+
+/// The readable body: drops the notation legend (fenced by the first two dashed
+/// rules) and everything from the `底本：` colophon on.
+pub fn body_lines(text: &str) -> Vec<&str> {
+    let lines: Vec<&str> = text.lines().collect();
+    let end =
+        lines.iter().position(|l| l.trim_start().starts_with("底本：")).unwrap_or(lines.len());
+
+    let rules: Vec<usize> =
+        lines[..end].iter().enumerate().filter(|(_, l)| is_rule(l)).map(|(i, _)| i).collect();
+    let legend = match rules.as_slice() {
+        [first, second, ..] => *first..=*second,
+        _ => 1..=0,
+    };
+
+    lines[..end].iter().enumerate().filter(|(i, _)| !legend.contains(i)).map(|(_, l)| *l).collect()
+}
+
+fn is_ruby_base_char(c: char) -> bool {
+    is_kanji_char(c) || matches!(c, '〆' | 'ヶ' | '〇')
+}
+
+/// Strip one line's markup. Ruby comes out as hints over the base text it
+/// annotated: `｜` marks an explicit base start, otherwise the base is the run
+/// of kanji before `《`. Hint offsets index into the returned text.
+pub fn strip_markup(line: &str) -> (String, Vec<RubyHint>) {
+    let line = ANNOTATION.replace_all(line, "");
+    let mut text = String::with_capacity(line.len());
+    let mut ruby = Vec::new();
+    let mut explicit_start = None;
+
+    let mut rest = line.as_ref();
+    while let Some(c) = rest.chars().next() {
+        if c == '｜' {
+            explicit_start = Some(text.len());
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if c == '《' {
+            if let Some(close) = rest.find('》') {
+                let reading = rest[c.len_utf8()..close].trim();
+                let start = explicit_start.take().unwrap_or_else(|| implicit_base_start(&text));
+                if start < text.len() && !reading.is_empty() {
+                    ruby.push(RubyHint { start, end: text.len(), reading: reading.to_string() });
+                }
+                rest = &rest[close + '》'.len_utf8()..];
+                continue;
+            }
+        }
+        text.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    (text, ruby)
+}
+
+fn implicit_base_start(text: &str) -> usize {
+    text.char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ruby_base_char(*c))
+        .last()
+        .map_or(text.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_ruby_into_hints() {
+        let (text, ruby) =
+            strip_markup("吾輩《わがはい》は｜猫又《ねこまた》である。何処《どこ》で生れたか");
+        assert_eq!(text, "吾輩は猫又である。何処で生れたか");
+        let spans: Vec<(&str, &str)> =
+            ruby.iter().map(|h| (&text[h.start..h.end], h.reading.as_str())).collect();
+        assert_eq!(spans, vec![("吾輩", "わがはい"), ("猫又", "ねこまた"), ("何処", "どこ")]);
+    }
+
+    #[test]
+    fn explicit_marker_overrides_the_kanji_run() {
+        // Without ｜ the ruby would claim the whole 東京特許 run.
+        let (text, ruby) = strip_markup("東京｜特許《とっきょ》許可局");
+        assert_eq!(text, "東京特許許可局");
+        assert_eq!(&text[ruby[0].start..ruby[0].end], "特許");
+    }
+
+    #[test]
+    fn drops_annotations_and_gaiji_markers() {
+        let (text, ruby) = strip_markup(
+            "［＃ここから２字下げ］彼は※［＃「てへん＋劣」、第3水準1-84-77］った［＃「った」に傍点］",
+        );
+        assert_eq!(text, "彼はった");
+        assert!(ruby.is_empty());
+    }
+
+    #[test]
+    fn body_skips_the_legend_and_colophon() {
+        let text =
+            "吾輩は猫である\n夏目漱石\n\n-------------------------------------------------------\n\
+                    【テキスト中に現れる記号について】\n《》：ルビ\n\
+                    -------------------------------------------------------\n\
+                    本文《ほんぶん》。\n\n底本：「夏目漱石全集1」\n入力：x";
+        assert!(is_aozora(text));
+        assert_eq!(
+            body_lines(text),
+            vec!["吾輩は猫である", "夏目漱石", "", "本文《ほんぶん》。", ""]
+        );
+        assert!(!is_aozora("普通のテキスト《注》です。"));
+    }
+}
@@ -0,0 +1,364 @@
+//! Headless Yomine: the processing pipeline, frequency analyzer and knowledge
+//! summary without the GUI, for scripts and batch jobs. Reads the app's data
+//! folder (UniDic, frequency dictionaries, ignore list, Anki vocab cache,
+//! `settings.json`), so results match what the app shows for the same file.
+//!
+//! Usage:
+//!   yomine-cli process <file> [--format csv|json|anki] [--limit N] [--output <path>]
+//!   yomine-cli analyze <dir> [--name <dict>] [--csv] [--exclude-hapax] [--output <dir>]
+//!   yomine-cli segment <sentence>
+//!   yomine-cli knowledge [--json]
+//!
+//! The engine logs its progress to stderr; stdout carries only the result.
+
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use yomine::{
+    core::{
+        pipeline::process_source_file,
+        settings::SettingsData,
+        text_filter::compile_filters,
+        IgnoreList,
+        LanguageTools,
+        Sentence,
+        SourceFile,
+        Term,
+        YomineError,
+    },
+    dictionary::{
+        frequency_manager::{
+            self,
+            FrequencyManager,
+        },
+        token_dictionary::DictType,
+    },
+    jlpt::JlptDatabase,
+    parser::ReadOptions,
+    persistence,
+    segmentation::tokenizer::{
+        extract_words,
+        init_vibrato,
+    },
+    tools::{
+        analysis::{
+            analyzer::{
+                analyze_files,
+                export_csv,
+                export_yomitan_zip,
+            },
+            find_supported_files_recursive,
+            ExportOptions,
+        },
+        knowledge_summary::compute_knowledge_summary,
+        term_export::{
+            render_terms,
+            TermExportFormat,
+        },
+    },
+};
+
+const USAGE: &str = "usage:
+  yomine-cli process <file> [--format csv|json|anki] [--limit N] [--output <path>]
+  yomine-cli analyze <dir> [--name <dict>] [--csv] [--exclude-hapax] [--output <dir>]
+  yomine-cli segment <sentence>
+  yomine-cli knowledge [--json]";
+
+/// Positional arguments plus `--flag` / `--option value` pairs, in any order.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+/// Options that take a value; any other `--name` is a boolean flag.
+const VALUE_OPTIONS: &[&str] = &["format", "limit", "output", "name"];
+
+impl Args {
+    fn parse(mut raw: impl Iterator<Item = String>) -> Result<Self, YomineError> {
+        let mut args = Args { positional: Vec::new(), options: HashMap::new(), flags: Vec::new() };
+        while let Some(arg) = raw.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if VALUE_OPTIONS.contains(&name) => {
+                    let value = raw.next().ok_or_else(|| {
+                        YomineError::Custom(format!("--{name} needs a value\n{USAGE}"))
+                    })?;
+                    args.options.insert(name.to_string(), value);
+                }
+                Some(name) => args.flags.push(name.to_string()),
+                None => args.positional.push(arg),
+            }
+        }
+        Ok(args)
+    }
+
+    /// Reject any option or flag the command doesn't take.
+    fn only(self, allowed: &[&str]) -> Result<Self, YomineError> {
+        match self.options.keys().chain(&self.flags).find(|name| !allowed.contains(&name.as_str()))
+        {
+            Some(name) => Err(YomineError::Custom(format!("unknown option --{name}\n{USAGE}"))),
+            None => Ok(self),
+        }
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+fn main() {
+    let mut raw = std::env::args().skip(1);
+    let command = raw.next().unwrap_or_default();
+    let result = Args::parse(raw).and_then(|args| match command.as_str() {
+        "process" => process(&args.only(&["format", "limit", "output"])?),
+        "analyze" => analyze(&args.only(&["name", "csv", "exclude-hapax", "output"])?),
+        "segment" => segment(&args.only(&[])?),
+        "knowledge" => knowledge(&args.only(&["json"])?),
+        _ => Err(YomineError::Custom(USAGE.to_string())),
+    });
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(2);
+    }
+}
+
+fn required<'a>(args: &'a Args, what: &str) -> Result<&'a str, YomineError> {
+    args.positional
+        .first()
+        .map(String::as_str)
+        .ok_or_else(|| YomineError::Custom(format!("missing <{what}>\n{USAGE}")))
+}
+
+/// Push saved weights / enabled flags into the manager, as the app does on load.
+fn apply_frequency_weights(manager: &FrequencyManager, settings: &SettingsData) {
+    for (name, state) in manager.dictionary_states().unwrap_or_default() {
+        let (weight, enabled) = match settings.frequency_weights.get(&name) {
+            Some(setting) => (setting.weight, setting.enabled),
+            None => (state.weight, state.enabled),
+        };
+        if let Err(e) = manager.set_dictionary_state(&name, weight.max(0.1), enabled) {
+            eprintln!("Failed to update dictionary state '{}': {}", name, e);
+        }
+    }
+}
+
+fn load_frequency_manager(settings: &SettingsData) -> Result<Arc<FrequencyManager>, YomineError> {
+    let manager = frequency_manager::process_frequency_dictionaries(None)?;
+    apply_frequency_weights(&manager, settings);
+    Ok(Arc::new(manager))
+}
+
+fn load_language_tools(settings: &SettingsData) -> Result<LanguageTools, YomineError> {
+    Ok(LanguageTools {
+        tokenizer: Arc::new(init_vibrato(&DictType::Unidic, None)?),
+        frequency_manager: load_frequency_manager(settings)?,
+        ignore_list: Arc::new(Mutex::new(IgnoreList::load()?)),
+        jlpt: Arc::new(JlptDatabase::load()),
+        known_interval: settings.anki_interval,
+        priority_weights: settings.priority_weights,
+        comprehension_model: settings.comprehension_model,
+        suspended_cards: settings.anki_suspended_cards,
+    })
+}
+
+fn load_settings() -> SettingsData {
+    persistence::load_json_or_default::<SettingsData>("settings.json")
+}
+
+fn output_path(args: &Args) -> Option<PathBuf> {
+    args.option("output").map(PathBuf::from)
+}
+
+fn write_output(path: Option<&Path>, text: &str) -> Result<(), YomineError> {
+    match path {
+        Some(path) => Ok(std::fs::write(path, text)?),
+        None => {
+            print!("{text}");
+            Ok(())
+        }
+    }
+}
+
+fn harmonic_rank(term: &Term) -> u32 {
+    term.frequencies.get("HARMONIC").copied().unwrap_or(u32::MAX)
+}
+
+/// `yomine-cli process <file>`: the app's load pipeline, then the unknown terms
+/// (not in Anki, not ignored) in the table's default priority order.
+fn process(args: &Args) -> Result<(), YomineError> {
+    let path = required(args, "file")?;
+    let settings = load_settings();
+    let language_tools = load_language_tools(&settings)?;
+
+    let file_name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("Unknown");
+    let source_file = SourceFile {
+        id: 0,
+        source: None,
+        file_type: yomine::mokuro::source_file_type(path),
+        title: file_name.to_string(),
+        creator: None,
+        original_file: path.to_string(),
+        epub_chapters: None,
+        epub_label: None,
+        encoding: None,
+        text_markup: None,
+        mkv_track: None,
+        secondary_subtitle: None,
+        excluded_styles: None,
+    };
+    let read_options = ReadOptions {
+        join_split_cues: settings.join_split_cues,
+        excluded_styles: settings.excluded_ass_styles.clone(),
+    };
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let (_, filter_result, sentences, _) = runtime.block_on(process_source_file(
+        &source_file,
+        &read_options,
+        &language_tools,
+        &compile_filters(&settings),
+    ))?;
+
+    let mut terms = filter_result.terms;
+    terms.sort_by(|a, b| {
+        b.priority.total_cmp(&a.priority).then_with(|| harmonic_rank(a).cmp(&harmonic_rank(b)))
+    });
+    if let Some(limit) = args.option("limit") {
+        let limit = limit
+            .parse()
+            .map_err(|_| YomineError::Custom(format!("--limit expects a number, got {limit}")))?;
+        terms.truncate(limit);
+    }
+
+    let format = match args.option("format").unwrap_or("csv") {
+        "csv" => TermExportFormat::Csv,
+        "json" => TermExportFormat::Json,
+        "anki" => TermExportFormat::AnkiTsv,
+        other => return Err(YomineError::Custom(format!("unknown --format {other}\n{USAGE}"))),
+    };
+    write_output(output_path(args).as_deref(), &render_terms(&terms, &sentences, format)?)
+}
+
+/// `yomine-cli analyze <dir>`: the Frequency Analyzer over every supported file
+/// below `dir`, exported as a Yomitan dictionary zip (and optionally CSV).
+fn analyze(args: &Args) -> Result<(), YomineError> {
+    let dir = Path::new(required(args, "dir")?);
+    let files = find_supported_files_recursive(dir);
+    if files.is_empty() {
+        return Err(YomineError::Custom(format!("No supported files found in {}", dir.display())));
+    }
+
+    let settings = load_settings();
+    let language_tools = load_language_tools(&settings)?;
+    let progress = Box::new(|idx, name, _size| eprintln!("[{idx}] {name}"));
+    let result = analyze_files(files, &language_tools, Some(progress), None)?;
+
+    let defaults = ExportOptions::default();
+    let name = args.option("name").unwrap_or(&defaults.dict_name);
+    let out_dir = output_path(args).unwrap_or_else(|| PathBuf::from("."));
+    let exclude_hapax = args.flag("exclude-hapax");
+    export_yomitan_zip(&result, name, None, None, None, &out_dir, false, exclude_hapax, None)?;
+    if args.flag("csv") {
+        export_csv(&result, &out_dir, name, exclude_hapax)?;
+    }
+
+    eprintln!(
+        "{} terms ({} unique) exported to {}",
+        result.total_terms,
+        result.unique_terms,
+        out_dir.display()
+    );
+    Ok(())
+}
+
+/// `yomine-cli segment <sentence>`: the terms and display segments the app would
+/// extract from one sentence, with the installed frequency dictionaries.
+fn segment(args: &Args) -> Result<(), YomineError> {
+    let text = args.positional.join(" ").trim().to_string();
+    if text.is_empty() {
+        return Err(YomineError::Custom(format!("missing <sentence>\n{USAGE}")));
+    }
+
+    let settings = load_settings();
+    let tokenizer = init_vibrato(&DictType::Unidic, None)?;
+    let manager = load_frequency_manager(&settings)?;
+    let mut sentences = vec![Sentence {
+        id: 0,
+        source_id: 0,
+        text,
+        segments: Vec::new(),
+        timestamp: None,
+        page: None,
+        comprehension: 0.0,
+        ruby: Vec::new(),
+        translation: None,
+        speaker: None,
+    }];
+    let terms = extract_words(tokenizer.new_worker(), &mut sentences, &manager);
+
+    for term in &terms {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            term.surface_form,
+            term.lemma_form,
+            term.lemma_reading,
+            term.part_of_speech,
+            harmonic_rank(term)
+        );
+    }
+    println!();
+    for (reading, pos, start, end) in &sentences[0].segments {
+        println!("[{start:3}..{end:3}] {} {reading} {pos:?}", &sentences[0].text[*start..*end]);
+    }
+    Ok(())
+}
+
+/// `yomine-cli knowledge`: JLPT and frequency-band coverage from the Anki vocab cache.
+fn knowledge(args: &Args) -> Result<(), YomineError> {
+    let settings = load_settings();
+    let summary = compute_knowledge_summary(
+        load_frequency_manager(&settings)?,
+        settings.anki_interval,
+        settings.anki_suspended_cards,
+    );
+
+    if args.flag("json") {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+    if summary.jlpt.is_empty() && summary.frequency.is_empty() {
+        return Err(YomineError::Custom(
+            "No Anki vocab cache yet — open a file in Yomine with Anki running first".to_string(),
+        ));
+    }
+
+    println!("{:8} {:>8} {:>14} {:>7}", "band", "coverage", "comprehension", "words");
+    let rows = summary
+        .jlpt
+        .iter()
+        .map(|(level, stats)| (level.label().to_string(), stats))
+        .chain(summary.frequency.iter().map(|(label, stats)| (label.clone(), stats)));
+    for (label, stats) in rows {
+        println!(
+            "{:8} {:>7.1}% {:>13.1}% {:>7}",
+            label,
+            stats.coverage * 100.0,
+            stats.comprehension * 100.0,
+            stats.total
+        );
+    }
+    Ok(())
+}
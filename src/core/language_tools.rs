@@ -6,7 +6,14 @@ use std::sync::{
 use vibrato::Tokenizer;
 
 use crate::{
-    core::IgnoreList,
+    core::{
+        settings::{
+            ComprehensionModel,
+            PriorityWeights,
+            SuspendedCards,
+        },
+        IgnoreList,
+    },
     dictionary::frequency_manager::FrequencyManager,
     jlpt::JlptDatabase,
 };
@@ -23,6 +30,9 @@ pub struct LanguageTools {
     pub ignore_list: Arc<Mutex<IgnoreList>>,
     pub jlpt: Arc<JlptDatabase>,
     pub known_interval: u32,
+    pub priority_weights: PriorityWeights,
+    pub comprehension_model: ComprehensionModel,
+    pub suspended_cards: SuspendedCards,
 }
 
 impl std::fmt::Debug for LanguageTools {
@@ -5,6 +5,7 @@ pub mod ignore_list;
 pub mod language_tools;
 pub mod models;
 pub mod pipeline;
+pub mod priority;
 pub mod recent_files;
 pub mod settings;
 pub mod text_filter;
@@ -24,7 +25,10 @@ pub use models::{
 };
 pub use settings::{
     AnkiModelInfo,
+    ComprehensionModel,
     FrequencyDictionarySetting,
+    PriorityWeights,
     SettingsData,
+    SuspendedCards,
     WebSocketSettings,
 };
@@ -11,8 +11,12 @@ use crate::segmentation::word::POS;
 pub enum SourceFileType {
     SRT,
     SSA,
+    VTT,
+    TTML,
     TXT,
     EPUB,
+    Mokuro,
+    MKV,
     Other(String),
 }
 
@@ -24,8 +28,12 @@ impl SourceFileType {
             match extension.to_lowercase().as_str() {
                 "srt" => SourceFileType::SRT,
                 "ass" | "ssa" => SourceFileType::SSA,
+                "vtt" => SourceFileType::VTT,
+                "ttml" | "dfxp" => SourceFileType::TTML,
                 "txt" => SourceFileType::TXT,
                 "epub" => SourceFileType::EPUB,
+                "mokuro" => SourceFileType::Mokuro,
+                "mkv" | "mks" => SourceFileType::MKV,
                 other => SourceFileType::Other(other.to_uppercase()),
             }
         } else {
@@ -34,13 +42,67 @@ impl SourceFileType {
     }
 
     pub fn supported_extensions() -> &'static [&'static str] {
-        &["srt", "ass", "ssa", "txt", "epub"]
+        &["srt", "ass", "ssa", "vtt", "ttml", "dfxp", "txt", "epub", "mokuro", "mkv", "mks"]
     }
 
     #[inline]
     pub fn is_supported(&self) -> bool {
         !matches!(self, SourceFileType::Other(_))
     }
+
+    /// Subtitle formats whose sentences carry timestamps.
+    #[inline]
+    pub fn is_timed(&self) -> bool {
+        matches!(
+            self,
+            SourceFileType::SRT
+                | SourceFileType::SSA
+                | SourceFileType::VTT
+                | SourceFileType::TTML
+                | SourceFileType::MKV
+        )
+    }
+}
+
+/// Character encoding of a text-based source file. Sniffed on load and
+/// recorded on the `SourceFile`, where the user can override a wrong guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    ShiftJis,
+    EucJp,
+}
+
+impl TextEncoding {
+    pub fn all() -> &'static [TextEncoding] {
+        &[
+            TextEncoding::Utf8,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+            TextEncoding::ShiftJis,
+            TextEncoding::EucJp,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::ShiftJis => "Shift-JIS",
+            TextEncoding::EucJp => "EUC-JP",
+        }
+    }
+}
+
+/// Markup convention of a plain-text source. `Aozora` is Aozora Bunko's
+/// `｜漢字《かんじ》` ruby + `［＃…］` annotation format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TextMarkup {
+    Plain,
+    Aozora,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -55,6 +117,16 @@ pub struct SourceFile {
     pub epub_chapters: Option<Vec<usize>>, // Selected epub part ids; None = whole book
     #[serde(default)]
     pub epub_label: Option<String>, // Picker-built selection summary, shown beside the title
+    #[serde(default)]
+    pub encoding: Option<TextEncoding>, // Detected or user-forced encoding; None = sniff on read
+    #[serde(default)]
+    pub text_markup: Option<TextMarkup>, // TXT only: detected or user-forced markup; None = sniff
+    #[serde(default)]
+    pub mkv_track: Option<u64>, // MKV only: subtitle TrackNumber; None = preferred Japanese track
+    #[serde(default)]
+    pub secondary_subtitle: Option<String>, // Timed sources: translation subtitle file aligned by overlap
+    #[serde(default)]
+    pub excluded_styles: Option<Vec<String>>, // ASS/SSA: style names dropped; None = the settings' globs
 }
 
 impl Default for SourceFile {
@@ -68,6 +140,11 @@ impl Default for SourceFile {
             original_file: String::new(),
             epub_chapters: None,
             epub_label: None,
+            encoding: None,
+            text_markup: None,
+            mkv_track: None,
+            secondary_subtitle: None,
+            excluded_styles: None,
         }
     }
 }
@@ -78,6 +155,40 @@ pub struct TimeStamp {
     pub end: Time,
 }
 
+fn time_to_nanos(t: Time) -> u64 {
+    let (h, m, s, ns) = t.as_hms_nano();
+    ((h as u64 * 60 + m as u64) * 60 + s as u64) * 1_000_000_000 + ns as u64
+}
+
+fn time_from_nanos(n: u64) -> Result<Time, time::error::ComponentRange> {
+    Time::from_hms_nano(
+        (n / 3_600_000_000_000) as u8,
+        (n / 60_000_000_000 % 60) as u8,
+        (n / 1_000_000_000 % 60) as u8,
+        (n % 1_000_000_000) as u32,
+    )
+}
+
+// `time` is built without its serde feature; nanoseconds since midnight keep
+// the (file cache) round trip lossless.
+impl serde::Serialize for TimeStamp {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (time_to_nanos(self.start), time_to_nanos(self.end)).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TimeStamp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let (start, end) = <(u64, u64)>::deserialize(deserializer)?;
+        Ok(Self {
+            start: time_from_nanos(start).map_err(D::Error::custom)?,
+            end: time_from_nanos(end).map_err(D::Error::custom)?,
+        })
+    }
+}
+
 impl TimeStamp {
     pub fn to_secs(&self) -> (f32, f32) {
         fn secs(t: Time) -> f32 {
@@ -108,14 +219,34 @@ impl TimeStamp {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Where a manga sentence sits in its volume — the page number (1-based, as
+/// printed) and the text block on that page. Stands in for `TimeStamp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PageLocation {
+    pub page: usize,
+    pub block: usize,
+}
+
+/// An author-supplied reading (Aozora ruby) for `text[start..end]` of a sentence.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RubyHint {
+    pub start: usize, // Byte offsets into the sentence text
+    pub end: usize,
+    pub reading: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Sentence {
     pub id: usize,                                  // Unique identifier
     pub source_id: u32,                             // Reference to a SourceFile
     pub text: String,                               // Sentence content
     pub segments: Vec<(String, POS, usize, usize)>, // List of segments (reading, POS, start, end) for the sentence
     pub timestamp: Option<TimeStamp>,
-    pub comprehension: f32, // 0.0 to 1.0, average of term comprehensions
+    pub page: Option<PageLocation>, // Manga (mokuro) sources, in place of a timestamp
+    pub comprehension: f32,         // 0.0 to 1.0, average of term comprehensions
+    pub ruby: Vec<RubyHint>,        // Reading hints the segmentation prefers over the dictionary's
+    pub translation: Option<String>, // Aligned line(s) from the secondary subtitle, if one is loaded
+    pub speaker: Option<String>,     // ASS/SSA actor (`Name` column), when the script records one
 }
 
 #[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -145,7 +276,7 @@ impl PartOfSpeech {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Term {
     pub id: u32,
     pub lemma_form: String, // Base form of the term aka lemma form.. what is found in a dictionary
@@ -161,6 +292,8 @@ pub struct Term {
     pub comprehension: f32,           // 0.0 to 1.0, calculated from Anki interval and ignore list
     #[serde(default)]
     pub jlpt_level: Option<crate::jlpt::JlptLevel>,
+    #[serde(default)]
+    pub priority: f32, // 0.0 to 1.0, mining priority from `core::priority`
 }
 
 impl Term {
@@ -188,6 +321,7 @@ impl Term {
             sentence_references: Vec::new(),
             comprehension: 0.0,
             jlpt_level: None,
+            priority: 0.0,
         }
     }
 }
@@ -19,8 +19,13 @@ pub struct FilterResult {
 
 /// Selects where the Anki "known terms" knowledge comes from when filtering.
 pub enum AnkiFilter {
-    /// Fetch live from Anki; also refreshes the on-disk vocab cache.
-    Live(HashMap<String, FieldMapping>),
+    /// Fetch live from Anki; also refreshes the on-disk vocab cache. Known
+    /// vocabulary is limited to the mapped note types and `vocab_query`.
+    Live {
+        connection: AnkiConnection,
+        model_mapping: HashMap<String, FieldMapping>,
+        vocab_query: String,
+    },
     /// Use the on-disk vocab snapshot (offline, fast).
     Cached,
     /// Partition by an explicit set of known lemma forms (ignore-list refresh).
@@ -28,11 +33,13 @@ pub enum AnkiFilter {
 }
 use crate::{
     anki::{
-        comprehensibility::calculate_sentence_comprehension,
+        api::AnkiConnection,
+        comprehensibility::calculate_sentence_comprehensions,
         AnkiState,
         FieldMapping,
     },
     core::{
+        priority::PriorityContext,
         text_filter::{
             apply_to_text,
             CompiledFilter,
@@ -42,21 +49,108 @@ use crate::{
         SourceFile,
         Term,
     },
-    parser,
-    segmentation::tokenizer::extract_words,
+    parser::{
+        self,
+        ReadOptions,
+    },
+    persistence::file_cache,
+    segmentation::tokenizer::extract_words_parallel,
 };
 
 pub async fn process_source_file(
     source_file: &SourceFile,
+    read_options: &ReadOptions,
+    language_tools: &LanguageTools,
+    text_filters: &[CompiledFilter],
+) -> Result<(Vec<Term>, FilterResult, Vec<Sentence>, f32), YomineError> {
+    let total_start = Instant::now();
+    let (sentences, terms) =
+        tokenize_source_file(source_file, read_options, language_tools, text_filters)?;
+    filter_and_score(sentences, terms, language_tools, total_start).await
+}
+
+/// A multi-file session (a season folder, several volumes) processed as one
+/// source: sentence ids run on across the files, each sentence's `source_id`
+/// is its file's `id`, and terms are deduplicated across files so one row
+/// carries every episode's references.
+pub async fn process_source_files(
+    source_files: &[SourceFile],
+    read_options: &ReadOptions,
     language_tools: &LanguageTools,
     text_filters: &[CompiledFilter],
 ) -> Result<(Vec<Term>, FilterResult, Vec<Sentence>, f32), YomineError> {
+    let total_start = Instant::now();
+    let mut parts = Vec::with_capacity(source_files.len());
+    for source_file in source_files {
+        eprintln!("Processing {}", source_file.original_file);
+        parts.push(tokenize_source_file(source_file, read_options, language_tools, text_filters)?);
+    }
+    let (sentences, terms) = merge_sources(parts);
+    eprintln!("Merged {} files into {} unique terms", source_files.len(), terms.len());
+    filter_and_score(sentences, terms, language_tools, total_start).await
+}
+
+/// Parse and tokenize one file, or load both from the file cache.
+fn tokenize_source_file(
+    source_file: &SourceFile,
+    read_options: &ReadOptions,
+    language_tools: &LanguageTools,
+    text_filters: &[CompiledFilter],
+) -> Result<(Vec<Sentence>, Vec<Term>), YomineError> {
+    // A reopened file skips parsing and tokenization; only the filters and
+    // comprehension depend on Anki / the ignore list.
+    let cache_key = file_cache::cache_key(source_file, read_options, language_tools, text_filters);
+    if let Some((mut sentences, terms)) = cache_key.as_deref().and_then(file_cache::load) {
+        eprintln!("Loaded {} sentences from the file cache", sentences.len());
+        for sentence in &mut sentences {
+            sentence.source_id = source_file.id;
+        }
+        return Ok((sentences, terms));
+    }
+
     // Parse the source file
-    let sentences =
-        parser::read(source_file).map_err(|e| YomineError::FailedToLoadFile(e.to_string()))?;
-    println!("Parsed {} sentences", sentences.len());
+    let sentences = parser::read_with(source_file, read_options)
+        .map_err(|e| YomineError::FailedToLoadFile(e.to_string()))?;
+    eprintln!("Parsed {} sentences", sentences.len());
 
-    process_sentences(sentences, language_tools, text_filters).await
+    let (sentences, terms) = tokenize_sentences(sentences, language_tools, text_filters);
+    if let Some(key) = &cache_key {
+        file_cache::store(key, &sentences, &terms);
+    }
+    Ok((sentences, terms))
+}
+
+/// Concatenate per-file tokenizer output in file order. Term references hold
+/// sentence positions, so they are offset past the previous files and every
+/// sentence is renumbered to its merged position (a file's own ids can skip
+/// cues that cleaned to nothing). A term whose lemma + reading (kana-folded,
+/// as `tokenize_sentences` dedups) an earlier file already had folds into that
+/// first term, which keeps its place.
+fn merge_sources(parts: Vec<(Vec<Sentence>, Vec<Term>)>) -> (Vec<Sentence>, Vec<Term>) {
+    let mut sentences: Vec<Sentence> = Vec::new();
+    let mut terms: Vec<Term> = Vec::new();
+    let mut by_pair: HashMap<(String, String), usize> = HashMap::new();
+    for (file_sentences, file_terms) in parts {
+        let offset = sentences.len();
+        for mut sentence in file_sentences {
+            sentence.id = sentences.len();
+            sentences.push(sentence);
+        }
+        for mut term in file_terms {
+            for (id, _) in &mut term.sentence_references {
+                *id += offset;
+            }
+            let pair = (term.lemma_form.clone(), term.lemma_reading.to_hiragana());
+            match by_pair.get(&pair) {
+                Some(&idx) => terms[idx].sentence_references.append(&mut term.sentence_references),
+                None => {
+                    by_pair.insert(pair, terms.len());
+                    terms.push(term);
+                }
+            }
+        }
+    }
+    (sentences, terms)
 }
 
 /// The shared tail of file processing: tokenize/segment `sentences`, dedupe
@@ -64,15 +158,29 @@ pub async fn process_source_file(
 /// Split from `process_source_file` so non-file sources (the asbplayer subtitle
 /// importer, issue #105) run the identical pipeline.
 pub async fn process_sentences(
-    mut sentences: Vec<Sentence>,
+    sentences: Vec<Sentence>,
     language_tools: &LanguageTools,
     text_filters: &[CompiledFilter],
 ) -> Result<(Vec<Term>, FilterResult, Vec<Sentence>, f32), YomineError> {
     let total_start = Instant::now();
+    let (sentences, terms) = tokenize_sentences(sentences, language_tools, text_filters);
+    filter_and_score(sentences, terms, language_tools, total_start).await
+}
 
+/// Text filters, segmentation and term dedup: everything the file cache keeps.
+fn tokenize_sentences(
+    mut sentences: Vec<Sentence>,
+    language_tools: &LanguageTools,
+    text_filters: &[CompiledFilter],
+) -> (Vec<Sentence>, Vec<Term>) {
     if !text_filters.is_empty() {
         for sentence in &mut sentences {
-            sentence.text = apply_to_text(text_filters, &sentence.text);
+            let filtered = apply_to_text(text_filters, &sentence.text);
+            // Ruby offsets only survive where the filter left their base text in place.
+            sentence.ruby.retain(|hint| {
+                filtered.get(hint.start..hint.end) == sentence.text.get(hint.start..hint.end)
+            });
+            sentence.text = filtered;
         }
         sentences.retain(|s| !s.text.is_empty());
         for (id, sentence) in sentences.iter_mut().enumerate() {
@@ -81,8 +189,8 @@ pub async fn process_sentences(
     }
 
     // Extract and deduplicate terms
-    let mut terms = extract_words(
-        language_tools.tokenizer.new_worker(),
+    let mut terms = extract_words_parallel(
+        &language_tools.tokenizer,
         &mut sentences,
         &language_tools.frequency_manager,
     );
@@ -94,8 +202,18 @@ pub async fn process_sentences(
         a.lemma_form == b.lemma_form
             && a.lemma_reading.to_hiragana() == b.lemma_reading.to_hiragana()
     });
-    println!("Extracted {} unique terms", terms.len());
+    eprintln!("Extracted {} unique terms", terms.len());
 
+    (sentences, terms)
+}
+
+/// JLPT levels, the ignore + cached-Anki filters, comprehension and priority.
+async fn filter_and_score(
+    mut sentences: Vec<Sentence>,
+    mut terms: Vec<Term>,
+    language_tools: &LanguageTools,
+    total_start: Instant,
+) -> Result<(Vec<Term>, FilterResult, Vec<Sentence>, f32), YomineError> {
     for term in &mut terms {
         term.jlpt_level =
             language_tools.jlpt.level_for(&term.lemma_form, &term.lemma_reading.to_hiragana());
@@ -103,7 +221,7 @@ pub async fn process_sentences(
 
     // Apply filters using the cached Anki snapshot for a fast, offline-safe load.
     // The GUI refreshes against live Anki in the background when connected.
-    let filter_result = apply_filters(terms, language_tools, AnkiFilter::Cached).await?;
+    let mut filter_result = apply_filters(terms, language_tools, AnkiFilter::Cached).await?;
 
     // Reconstruct base_terms from all three sets, gathering comprehension metrics from each
     let mut base_terms = Vec::new();
@@ -111,22 +229,39 @@ pub async fn process_sentences(
     base_terms.extend(filter_result.anki_filtered.iter().cloned());
     base_terms.extend(filter_result.ignore_filtered.iter().cloned());
 
-    for sentence in &mut sentences {
-        calculate_sentence_comprehension(sentence, &base_terms);
-    }
+    let file_comprehension =
+        score_file(&mut sentences, &mut base_terms, &mut filter_result.terms, language_tools);
 
-    let file_comprehension = if !sentences.is_empty() {
-        sentences.iter().map(|s| s.comprehension).sum::<f32>() / sentences.len() as f32
-    } else {
-        0.0
-    };
-
-    println!("Overall comprehension: {:.1}%", file_comprehension * 100.0);
-    println!("Processing completed ({:.1}s)", total_start.elapsed().as_secs_f32());
+    eprintln!("Overall comprehension: {:.1}%", file_comprehension * 100.0);
+    eprintln!("Processing completed ({:.1}s)", total_start.elapsed().as_secs_f32());
 
     Ok((base_terms, filter_result, sentences, file_comprehension))
 }
 
+/// Sentence comprehension under the tools' model, then term priority (which
+/// reads it); returns the file's mean comprehension. `base_terms` is the whole
+/// file and keeps the scores so later re-partitions (ignore list, live Anki)
+/// don't lose them; `unknown_terms` is the table's copy of its unknown terms.
+pub fn score_file(
+    sentences: &mut [Sentence],
+    base_terms: &mut [Term],
+    unknown_terms: &mut [Term],
+    language_tools: &LanguageTools,
+) -> f32 {
+    calculate_sentence_comprehensions(sentences, base_terms, language_tools.comprehension_model);
+
+    let priority = PriorityContext::new(base_terms, sentences);
+    for term in base_terms.iter_mut().chain(unknown_terms.iter_mut()) {
+        term.priority = priority.score(term, &language_tools.priority_weights);
+    }
+
+    if sentences.is_empty() {
+        0.0
+    } else {
+        sentences.iter().map(|s| s.comprehension).sum::<f32>() / sentences.len() as f32
+    }
+}
+
 fn apply_ignore_filter(
     base_terms: Vec<Term>,
     language_tools: &LanguageTools,
@@ -163,23 +298,27 @@ pub async fn apply_filters(
             match AnkiState::from_cache(
                 language_tools.frequency_manager.clone(),
                 language_tools.known_interval,
+                language_tools.suspended_cards,
             ) {
                 Some(state) => state.filter_existing_terms(not_ignored),
                 None => (not_ignored, Vec::new()),
             }
         }
-        AnkiFilter::Live(model_mapping) => {
+        AnkiFilter::Live { connection, model_mapping, vocab_query } => {
             match AnkiState::new(
+                &connection,
                 model_mapping,
+                &vocab_query,
                 language_tools.frequency_manager.clone(),
                 language_tools.known_interval,
+                language_tools.suspended_cards,
             )
             .await
             {
                 Ok(state) => {
                     let filter_anki_start = Instant::now();
                     let (unknown, known) = state.filter_existing_terms(not_ignored);
-                    println!(
+                    eprintln!(
                         "Anki filtering completed ({:.1}s)",
                         filter_anki_start.elapsed().as_secs_f32()
                     );
@@ -195,3 +334,93 @@ pub async fn apply_filters(
 
     Ok(FilterResult { terms: unknown_terms, anki_filtered, ignore_filtered })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segmentation::word::POS;
+
+    fn sentence(id: usize, source_id: u32) -> Sentence {
+        Sentence {
+            id,
+            source_id,
+            text: String::new(),
+            segments: Vec::new(),
+            timestamp: None,
+            page: None,
+            comprehension: 0.0,
+            ruby: Vec::new(),
+            translation: None,
+            speaker: None,
+        }
+    }
+
+    fn term(lemma: &str, reading: &str, surface: &str, references: &[(usize, usize)]) -> Term {
+        Term {
+            id: 0,
+            lemma_form: lemma.to_string(),
+            lemma_reading: reading.to_string(),
+            surface_form: surface.to_string(),
+            surface_reading: reading.to_string(),
+            is_kana: false,
+            part_of_speech: POS::Noun,
+            frequencies: HashMap::new(),
+            full_segment: String::new(),
+            full_segment_reading: String::new(),
+            sentence_references: references.to_vec(),
+            comprehension: 0.0,
+            jlpt_level: None,
+            priority: 0.0,
+        }
+    }
+
+    #[test]
+    fn merged_sources_share_terms_and_offset_references() {
+        let first = (
+            vec![sentence(0, 0), sentence(1, 0)],
+            vec![term("猫", "ネコ", "猫", &[(1, 0)]), term("犬", "イヌ", "犬", &[(0, 3)])],
+        );
+        let second = (
+            vec![sentence(0, 1), sentence(1, 1), sentence(2, 1)],
+            vec![
+                term("鳥", "トリ", "鳥", &[(1, 0)]),
+                term("猫", "ねこ", "ネコ", &[(0, 6), (2, 0)]),
+                term("猫", "ビョウ", "猫", &[(2, 4)]),
+            ],
+        );
+
+        let (sentences, terms) = merge_sources(vec![first, second]);
+
+        let ids: Vec<(usize, u32)> = sentences.iter().map(|s| (s.id, s.source_id)).collect();
+        assert_eq!(ids, [(0, 0), (1, 0), (2, 1), (3, 1), (4, 1)]);
+
+        let pairs: Vec<(&str, &str)> =
+            terms.iter().map(|t| (t.lemma_form.as_str(), t.lemma_reading.as_str())).collect();
+        assert_eq!(pairs, [("猫", "ネコ"), ("犬", "イヌ"), ("鳥", "トリ"), ("猫", "ビョウ")]);
+        assert_eq!(terms[0].surface_form, "猫");
+        assert_eq!(terms[0].sentence_references, [(1, 0), (2, 6), (4, 0)]);
+        assert_eq!(terms[2].sentence_references, [(3, 0)]);
+        assert_eq!(terms[3].sentence_references, [(4, 4)]);
+    }
+
+    #[test]
+    fn merged_sentences_are_renumbered_past_gaps_in_a_files_ids() {
+        // The first file dropped its cue 1 (it cleaned to nothing), so its ids
+        // skip one while its references hold positions.
+        let first =
+            (vec![sentence(0, 0), sentence(2, 0)], vec![term("猫", "ネコ", "猫", &[(1, 0)])]);
+        let second =
+            (vec![sentence(0, 1), sentence(1, 1)], vec![term("犬", "イヌ", "犬", &[(0, 0)])]);
+
+        let (sentences, terms) = merge_sources(vec![first, second]);
+
+        let ids: Vec<(usize, u32)> = sentences.iter().map(|s| (s.id, s.source_id)).collect();
+        assert_eq!(ids, [(0, 0), (1, 0), (2, 1), (3, 1)]);
+        for term in &terms {
+            for (id, _) in &term.sentence_references {
+                assert_eq!(sentences[*id].id, *id);
+            }
+        }
+        assert_eq!(terms[1].sentence_references, [(2, 0)]);
+    }
+}
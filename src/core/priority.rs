@@ -0,0 +1,166 @@
+//! Mining priority: one score per term that blends how common a word is in
+//! Japanese overall with how much it matters in the loaded file, so the default
+//! table order puts the words most worth mining first.
+
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+
+use crate::{
+    core::{
+        settings::PriorityWeights,
+        Sentence,
+        Term,
+    },
+    jlpt::JlptLevel,
+};
+
+/// Harmonic ranks at or past this carry no frequency signal.
+const RANK_CEILING: f32 = 100_000.0;
+
+/// The file-wide figures each signal is normalized against: the largest
+/// occurrence and distinct-sentence counts, and every sentence's comprehension.
+pub struct PriorityContext {
+    max_occurrences: usize,
+    max_sentences: usize,
+    comprehension: HashMap<usize, f32>,
+}
+
+fn distinct_sentences(term: &Term) -> usize {
+    term.sentence_references.iter().map(|(id, _)| *id).collect::<HashSet<_>>().len()
+}
+
+/// `ln(1 + n)` against the file's largest count, so a handful of repeats
+/// already counts for a lot and a wall of repeats saturates.
+fn log_share(count: usize, max: usize) -> f32 {
+    if max == 0 {
+        return 0.0;
+    }
+    ((count as f32).ln_1p() / (max as f32).ln_1p()).clamp(0.0, 1.0)
+}
+
+fn frequency_signal(term: &Term) -> f32 {
+    match term.frequencies.get("HARMONIC") {
+        Some(&rank) if rank != u32::MAX => {
+            (1.0 - (rank.max(1) as f32).ln() / RANK_CEILING.ln()).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+fn jlpt_signal(level: Option<JlptLevel>) -> f32 {
+    match level {
+        Some(JlptLevel::N5) => 1.0,
+        Some(JlptLevel::N4) => 0.8,
+        Some(JlptLevel::N3) => 0.6,
+        Some(JlptLevel::N2) => 0.4,
+        Some(JlptLevel::N1) => 0.2,
+        None => 0.0,
+    }
+}
+
+impl PriorityContext {
+    /// `terms` is the whole file (known and ignored included) and `sentences`
+    /// must already carry their comprehension.
+    pub fn new(terms: &[Term], sentences: &[Sentence]) -> Self {
+        Self {
+            max_occurrences: terms.iter().map(|t| t.sentence_references.len()).max().unwrap_or(0),
+            max_sentences: terms.iter().map(distinct_sentences).max().unwrap_or(0),
+            comprehension: sentences.iter().map(|s| (s.id, s.comprehension)).collect(),
+        }
+    }
+
+    /// Weighted mean of the five signals, each in `0.0..=1.0`; all weights at
+    /// zero gives 0.0 for every term.
+    pub fn score(&self, term: &Term, weights: &PriorityWeights) -> f32 {
+        let best_context = term
+            .sentence_references
+            .iter()
+            .filter_map(|(id, _)| self.comprehension.get(id).copied())
+            .fold(0.0, f32::max);
+
+        let signals = [
+            (weights.frequency, frequency_signal(term)),
+            (weights.occurrences, log_share(term.sentence_references.len(), self.max_occurrences)),
+            (weights.sentences, log_share(distinct_sentences(term), self.max_sentences)),
+            (weights.jlpt, jlpt_signal(term.jlpt_level)),
+            (weights.comprehension, best_context),
+        ];
+        let total: f32 = signals.iter().map(|(weight, _)| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        signals.iter().map(|(weight, signal)| weight.max(0.0) * signal).sum::<f32>() / total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segmentation::word::POS;
+
+    fn term(rank: Option<u32>, references: &[(usize, usize)], jlpt: Option<JlptLevel>) -> Term {
+        Term {
+            id: 0,
+            lemma_form: String::new(),
+            lemma_reading: String::new(),
+            surface_form: String::new(),
+            surface_reading: String::new(),
+            is_kana: false,
+            part_of_speech: POS::Noun,
+            frequencies: rank.map(|r| ("HARMONIC".to_string(), r)).into_iter().collect(),
+            full_segment: String::new(),
+            full_segment_reading: String::new(),
+            sentence_references: references.to_vec(),
+            comprehension: 0.0,
+            jlpt_level: jlpt,
+            priority: 0.0,
+        }
+    }
+
+    fn context(max_occurrences: usize, max_sentences: usize) -> PriorityContext {
+        PriorityContext {
+            max_occurrences,
+            max_sentences,
+            comprehension: [(0, 0.9), (1, 0.5), (2, 0.2)].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn common_and_repeated_words_rank_first() {
+        let weights = PriorityWeights::default();
+        let ctx = context(4, 3);
+
+        let common_repeated = term(Some(800), &[(0, 0), (1, 0), (2, 0), (2, 9)], None);
+        let common_once = term(Some(800), &[(2, 0)], None);
+        let rare_repeated = term(Some(60_000), &[(0, 0), (1, 0), (2, 0), (2, 9)], None);
+        let unranked = term(None, &[(2, 0)], None);
+
+        let scores = [&common_repeated, &common_once, &rare_repeated, &unranked]
+            .map(|t| ctx.score(t, &weights));
+        assert!(scores[0] > scores[1] && scores[0] > scores[2]);
+        assert!(scores[1] > scores[3] && scores[2] > scores[3]);
+        assert!(scores.iter().all(|s| (0.0..=1.0).contains(s)));
+    }
+
+    #[test]
+    fn weights_select_the_signals() {
+        let only_jlpt = PriorityWeights {
+            frequency: 0.0,
+            occurrences: 0.0,
+            sentences: 0.0,
+            jlpt: 1.0,
+            comprehension: 0.0,
+        };
+        let ctx = context(1, 1);
+        assert_eq!(ctx.score(&term(Some(1), &[(0, 0)], Some(JlptLevel::N4)), &only_jlpt), 0.8);
+        assert_eq!(ctx.score(&term(Some(1), &[(0, 0)], None), &only_jlpt), 0.0);
+
+        let only_context = PriorityWeights { jlpt: 0.0, comprehension: 2.0, ..only_jlpt };
+        assert_eq!(ctx.score(&term(None, &[(1, 0), (2, 0)], None), &only_context), 0.5);
+
+        let nothing = PriorityWeights { comprehension: 0.0, ..only_context };
+        assert_eq!(ctx.score(&term(Some(1), &[(0, 0)], None), &nothing), 0.0);
+    }
+}
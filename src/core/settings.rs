@@ -7,7 +7,13 @@
 
 use std::collections::HashMap;
 
-use crate::anki::FieldMapping;
+use crate::anki::{
+    api::{
+        AnkiConnection,
+        DEFAULT_ANKI_CONNECT_URL,
+    },
+    FieldMapping,
+};
 
 #[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize, Debug)]
 pub struct FrequencyDictionarySetting {
@@ -52,6 +58,63 @@ impl<'de> serde::Deserialize<'de> for SentenceColoring {
     }
 }
 
+/// How a sentence's comprehension is derived from its terms'
+/// (`anki::comprehensibility`); also drives the file's overall comprehension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComprehensionModel {
+    /// Plain mean over the distinct terms.
+    #[default]
+    Average,
+    /// Mean over occurrences, so a word repeated in the line counts each time.
+    TokenWeighted,
+    /// Mean over vocabulary words only; particles and names don't count.
+    ContentWords,
+    /// Occurrences weighted by their length in characters.
+    CharacterWeighted,
+    /// Probability the whole line is understood: the product over its
+    /// vocabulary words, so one unknown word sinks it.
+    WholeSentence,
+}
+
+// Manual for the same fallback as `SentenceColoring`.
+impl<'de> serde::Deserialize<'de> for ComprehensionModel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "token_weighted" => Self::TokenWeighted,
+            "content_words" => Self::ContentWords,
+            "character_weighted" => Self::CharacterWeighted,
+            "whole_sentence" => Self::WholeSentence,
+            _ => Self::Average,
+        })
+    }
+}
+
+/// How vocab on suspended Anki cards counts (`anki::state::AnkiState`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuspendedCards {
+    /// Like any other card, by its interval or FSRS state.
+    #[default]
+    Known,
+    /// Back in the unknown table with no comprehension, e.g. suspended leeches.
+    Unknown,
+    /// Left out, as if the note weren't in the search.
+    Ignored,
+}
+
+// Manual so an unrecognized value loads as the default, `Known`, rather
+// than failing the whole settings.json load.
+impl<'de> serde::Deserialize<'de> for SuspendedCards {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match String::deserialize(deserializer)?.as_str() {
+            "unknown" => Self::Unknown,
+            "ignored" => Self::Ignored,
+            _ => Self::Known,
+        })
+    }
+}
+
 /// Per-state visibility of the knowledge underlines (issue #94).
 #[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct UnderlineToggles {
@@ -71,6 +134,28 @@ impl Default for UnderlineToggles {
     }
 }
 
+/// Weights of the mining priority score (`core::priority`); 0 drops a signal.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PriorityWeights {
+    /// Harmonic frequency rank across the enabled dictionaries.
+    pub frequency: f32,
+    /// Occurrences in the file (`sentence_references`).
+    pub occurrences: f32,
+    /// Distinct sentences the term appears in.
+    pub sentences: f32,
+    /// JLPT level, N5 highest.
+    pub jlpt: f32,
+    /// Comprehension of the term's most understandable sentence.
+    pub comprehension: f32,
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        Self { frequency: 1.0, occurrences: 0.6, sentences: 0.4, jlpt: 0.3, comprehension: 0.5 }
+    }
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct TableColumn {
     pub id: String,
@@ -97,6 +182,18 @@ pub struct SettingsData {
     pub anki_model_mappings: HashMap<String, FieldMapping>,
     #[serde(default = "default_interval")]
     pub anki_interval: u32,
+    /// Anki search every known-vocabulary harvest is limited to, on top of the
+    /// per-note-type `FieldMapping::query`; empty = the whole collection.
+    #[serde(default)]
+    pub anki_vocab_query: String,
+    #[serde(default)]
+    pub anki_suspended_cards: SuspendedCards,
+    /// AnkiConnect endpoint, for Anki in another container or on another port.
+    #[serde(default = "default_anki_connect_url")]
+    pub anki_connect_url: String,
+    /// Sent as `key` when AnkiConnect's `apiKey` is set.
+    #[serde(default)]
+    pub anki_connect_api_key: Option<String>,
     #[serde(default)]
     pub websocket_settings: WebSocketSettings,
     #[serde(default)]
@@ -162,12 +259,33 @@ pub struct SettingsData {
     /// Preset id → enabled (`text_filter::presets`); missing = off.
     #[serde(default)]
     pub text_filter_presets: HashMap<String, bool>,
+    /// Merge subtitle cues that split one sentence across lines when parsing.
+    #[serde(default)]
+    pub join_split_cues: bool,
+    /// ASS/SSA style or actor globs dropped when parsing (signs, karaoke, OP/ED);
+    /// a file's own style picks override them.
+    #[serde(default = "default_excluded_ass_styles")]
+    pub excluded_ass_styles: Vec<String>,
+    /// Weights of the term table's default "Priority" order.
+    #[serde(default)]
+    pub priority_weights: PriorityWeights,
+    /// How sentence (and file) comprehension weighs the terms.
+    #[serde(default)]
+    pub comprehension_model: ComprehensionModel,
 }
 
 const fn default_font_scale() -> f32 {
     1.0
 }
 
+fn default_excluded_ass_styles() -> Vec<String> {
+    crate::parser::DEFAULT_EXCLUDED_STYLES.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_anki_connect_url() -> String {
+    DEFAULT_ANKI_CONNECT_URL.to_string()
+}
+
 fn default_yomitan_url() -> String {
     "http://127.0.0.1:19633".to_string()
 }
@@ -201,6 +319,10 @@ impl Default for SettingsData {
         Self {
             anki_model_mappings: HashMap::new(),
             anki_interval: default_interval(),
+            anki_vocab_query: String::new(),
+            anki_suspended_cards: SuspendedCards::default(),
+            anki_connect_url: default_anki_connect_url(),
+            anki_connect_api_key: None,
             websocket_settings: WebSocketSettings::default(),
             frequency_weights: HashMap::new(),
             pos_filters: HashMap::new(),
@@ -226,6 +348,23 @@ impl Default for SettingsData {
             table_columns: Vec::new(),
             text_filters: Vec::new(),
             text_filter_presets: HashMap::new(),
+            join_split_cues: false,
+            excluded_ass_styles: default_excluded_ass_styles(),
+            priority_weights: PriorityWeights::default(),
+            comprehension_model: ComprehensionModel::default(),
+        }
+    }
+}
+
+impl SettingsData {
+    /// The AnkiConnect endpoint every `anki::api` call goes through; a blank
+    /// URL means the default and a blank key means none.
+    pub fn anki_connection(&self) -> AnkiConnection {
+        let url = self.anki_connect_url.trim();
+        let api_key = self.anki_connect_api_key.as_deref().map(str::trim);
+        AnkiConnection {
+            url: if url.is_empty() { DEFAULT_ANKI_CONNECT_URL } else { url }.to_string(),
+            api_key: api_key.filter(|key| !key.is_empty()).map(String::from),
         }
     }
 }
@@ -52,6 +52,11 @@ impl CompiledFilter {
     pub fn new(pattern: &str, replacement: &str) -> Result<Self, regex::Error> {
         Ok(Self { regex: Regex::new(pattern)?, replacement: replacement.to_string() })
     }
+
+    /// Pattern and replacement, for keying cached results by the active filters.
+    pub fn fingerprint(&self) -> String {
+        format!("{}\u{1f}{}", self.regex.as_str(), self.replacement)
+    }
 }
 
 pub fn compile_filters(settings: &SettingsData) -> Vec<CompiledFilter> {
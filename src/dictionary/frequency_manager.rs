@@ -352,7 +352,7 @@ fn parse_term_meta_bank(folder_path: &Path) -> Result<Vec<TermMetaBankV3>, Yomin
         })
         .collect();
 
-    println!("Parsed {} entries from term meta bank files.", term_meta_list.len());
+    eprintln!("Parsed {} entries from term meta bank files.", term_meta_list.len());
 
     Ok(term_meta_list)
 }
@@ -425,7 +425,7 @@ fn ensure_default_frequency_dict(
         }
     }
 
-    println!("No frequency dictionaries found. Downloading default dictionary...");
+    eprintln!("No frequency dictionaries found. Downloading default dictionary...");
 
     if let Err(e) = fs::create_dir_all(&freq_dict_dir) {
         eprintln!("Failed to create frequency dictionary directory: {}", e);
@@ -435,11 +435,11 @@ fn ensure_default_frequency_dict(
     match try_download_from_urls(DEFAULT_FREQ_DICT_URLS, &zip_path, &[&zip_path], progress_callback)
     {
         Ok(_) => {
-            println!("Downloaded default frequency dictionary");
+            eprintln!("Downloaded default frequency dictionary");
 
             match extract_zip(&zip_path, &extract_dir) {
                 Ok(_) => {
-                    println!("Default frequency dictionary ready");
+                    eprintln!("Default frequency dictionary ready");
                     fs::remove_file(&zip_path).ok();
                     None
                 }
@@ -462,7 +462,7 @@ pub fn process_frequency_dictionaries(
     let mut manager = FrequencyManager::new(None);
     let start = Instant::now();
 
-    println!("Loading frequency dictionaries...");
+    eprintln!("Loading frequency dictionaries...");
     let dir_path = get_frequency_dict_dir();
     fs::create_dir_all(&dir_path)?;
 
@@ -517,7 +517,7 @@ pub fn process_frequency_dictionaries(
                 Ok(cached_dict) => {
                     if cached_dict.revision == index.revision {
                         let duration = load_start.elapsed();
-                        println!(
+                        eprintln!(
                             "Loaded '{}' from cache in {:?}: {} entries",
                             dict_name,
                             duration,
@@ -535,14 +535,14 @@ pub fn process_frequency_dictionaries(
                         cache_hits += 1;
                         continue;
                     } else {
-                        println!(
+                        eprintln!(
                             "Revision mismatch for '{}': cache={}, index={}",
                             dict_name, cached_dict.revision, index.revision
                         );
                     }
                 }
                 Err(e) => {
-                    println!(
+                    eprintln!(
                         "Failed to load cache for '{}': {}, rebuilding from JSON",
                         dict_name, e
                     );
@@ -562,7 +562,7 @@ pub fn process_frequency_dictionaries(
                 let freq_dict =
                     FrequencyDictionary::new(index.title.clone(), index.revision, term_meta_list);
                 let build_duration = build_start.elapsed();
-                println!("Built '{}' from JSON in {:?}", dict_name, build_duration);
+                eprintln!("Built '{}' from JSON in {:?}", dict_name, build_duration);
 
                 // Add to manager
                 manager.add_dictionary(dict_name.clone(), freq_dict.clone());
@@ -570,19 +570,19 @@ pub fn process_frequency_dictionaries(
 
                 // Save to cache
                 if let Err(e) = save_cached_dict(&freq_dict, &cache_path) {
-                    println!("Failed to save cache for '{}': {}", dict_name, e);
+                    eprintln!("Failed to save cache for '{}': {}", dict_name, e);
                 }
             } else {
-                println!("Failed to parse term meta bank for '{}'", dict_name);
+                eprintln!("Failed to parse term meta bank for '{}'", dict_name);
             }
         } else {
-            println!("Skipping {:?} due to unsupported format version.", path);
+            eprintln!("Skipping {:?} due to unsupported format version.", path);
         }
     }
 
     let total_duration = start.elapsed();
-    println!("Total processing time: {:?}", total_duration);
-    println!("Cache performance: {} hits, {} rebuilds", cache_hits, cache_rebuilds);
+    eprintln!("Total processing time: {:?}", total_duration);
+    eprintln!("Cache performance: {} hits, {} rebuilds", cache_hits, cache_rebuilds);
 
     if manager.dictionaries.is_empty() {
         eprintln!("Warning: No frequency dictionaries loaded. The app will continue but term frequencies will not be available.");
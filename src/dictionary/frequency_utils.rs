@@ -20,7 +20,7 @@ pub fn copy_frequency_dictionaries(
 
             // Skip if file already exists in destination
             if destination.exists() {
-                println!(
+                eprintln!(
                     "Skipping '{}' - already exists in frequency dictionary folder",
                     filename.to_string_lossy()
                 );
@@ -31,7 +31,7 @@ pub fn copy_frequency_dictionaries(
             fs::copy(&zip_path, &destination)?;
             copied_count += 1;
 
-            println!(
+            eprintln!(
                 "Copied frequency dictionary: {} -> {}",
                 zip_path.display(),
                 destination.display()
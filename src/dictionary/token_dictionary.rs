@@ -44,13 +44,31 @@ impl DictType {
         }
     }
 
-    fn folder_name(&self) -> &str {
+    pub(crate) fn folder_name(&self) -> &str {
         match self {
             DictType::Unidic => "bccwj-suw+unidic-cwj-3_1_1",
             DictType::Ipadic => "ipadic-mecab-2_7_0",
         }
     }
 
+    /// The installed dictionary's revision: its folder plus the size and
+    /// modification time of `system.dic.zst`, so a re-download or a swapped
+    /// file reads as a different dictionary.
+    pub(crate) fn revision(&self) -> String {
+        let path = get_tokenizer_dict_dir().join(self.folder_name()).join("system.dic.zst");
+        let stamp = fs::metadata(&path)
+            .map(|metadata| {
+                let modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map_or(0, |since| since.as_secs());
+                format!("{}:{}", metadata.len(), modified)
+            })
+            .unwrap_or_default();
+        format!("{}@{}", self.folder_name(), stamp)
+    }
+
     // lemma_form index, lemma_reading index
     pub fn lemma_indices(&self) -> (usize, usize) {
         match self {
@@ -64,7 +82,7 @@ impl DictType {
 
 fn cleanup_files(folder_path: &Path, keep_files: &[&str]) -> Result<(), YomineError> {
     let keep_paths: Vec<PathBuf> = keep_files.iter().map(|f| folder_path.join(f)).collect();
-    println!("Cleaning up intermediate files...");
+    eprintln!("Cleaning up intermediate files...");
 
     // Iterate through all files and directories in the folder
     for entry in fs::read_dir(folder_path).map_err(|e| {
@@ -98,7 +116,7 @@ fn cleanup_files(folder_path: &Path, keep_files: &[&str]) -> Result<(), YomineEr
         }
     }
 
-    println!("Cleanup complete. Retained files: {:?}", keep_files);
+    eprintln!("Cleanup complete. Retained files: {:?}", keep_files);
     Ok(())
 }
 
@@ -162,9 +180,9 @@ fn extract_and_finalize(
     callback_message("Cleaning up temporary files", progress_callback);
     let keep_files = ["system.dic.zst", "BSD", "NOTICE"];
     cleanup_files(extract_path, &keep_files)?;
-    println!("Removing download {:?}", download_path);
+    eprintln!("Removing download {:?}", download_path);
     fs::remove_file(download_path)?;
-    println!("Removing tar {:?}", tar_path);
+    eprintln!("Removing tar {:?}", tar_path);
     fs::remove_file(tar_path)?;
 
     Ok(final_zst_path.to_path_buf())
@@ -233,7 +251,7 @@ pub fn load_dictionary(
 }
 
 fn callback_message(message: &str, callback: &Option<Box<dyn Fn(String) + Send>>) {
-    println!("{}", message);
+    eprintln!("{}", message);
     if let Some(ref cb) = callback {
         cb(message.to_string());
     }
@@ -1,14 +1,18 @@
 pub mod anki;
+pub mod aozora;
 pub mod core;
 pub mod dictionary;
 pub mod epub;
 pub mod jlpt;
+pub mod mkv;
+pub mod mokuro;
 pub mod mpv;
 pub mod parser;
 pub mod persistence;
 pub mod player;
 pub mod segmentation;
 pub mod tools;
+pub mod ttml;
 pub mod websocket;
 pub mod yomitan;
 
@@ -0,0 +1,755 @@
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        Cursor,
+        Read,
+        Seek,
+        SeekFrom,
+    },
+};
+
+use serde::Serialize;
+use time::Time;
+
+use crate::core::YomineError;
+
+// Matroska element IDs (marker bits included, as they appear in the file).
+const EBML_HEADER: u32 = 0x1A45DFA3;
+const SEGMENT: u32 = 0x18538067;
+const SEEK_HEAD: u32 = 0x114D9B74;
+const INFO: u32 = 0x1549A966;
+const TIMESTAMP_SCALE: u32 = 0x2AD7B1;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_NUMBER: u32 = 0xD7;
+const TRACK_TYPE: u32 = 0x83;
+const FLAG_DEFAULT: u32 = 0x88;
+const FLAG_FORCED: u32 = 0x55AA;
+const DEFAULT_DURATION: u32 = 0x23E383;
+const NAME: u32 = 0x536E;
+const LANGUAGE: u32 = 0x22B59C;
+const LANGUAGE_BCP47: u32 = 0x22B59D;
+const CODEC_ID: u32 = 0x86;
+const CODEC_PRIVATE: u32 = 0x63A2;
+const CONTENT_ENCODINGS: u32 = 0x6D80;
+const CONTENT_ENCODING: u32 = 0x6240;
+const CONTENT_ENCODING_SCOPE: u32 = 0x5032;
+const CONTENT_ENCODING_TYPE: u32 = 0x5033;
+const CONTENT_COMPRESSION: u32 = 0x5034;
+const CONTENT_COMP_ALGO: u32 = 0x4254;
+const CONTENT_COMP_SETTINGS: u32 = 0x4255;
+const CLUSTER: u32 = 0x1F43B675;
+const CLUSTER_TIMESTAMP: u32 = 0xE7;
+const BLOCK_GROUP: u32 = 0xA0;
+const BLOCK: u32 = 0xA1;
+const BLOCK_DURATION: u32 = 0x9B;
+const SIMPLE_BLOCK: u32 = 0xA3;
+const CUES: u32 = 0x1C53BB6B;
+const CHAPTERS: u32 = 0x1043A770;
+const TAGS: u32 = 0x1254C367;
+const ATTACHMENTS: u32 = 0x1941A469;
+
+const TRACK_TYPE_SUBTITLE: u64 = 17;
+/// Tracks/Info/CodecPrivate are read whole; anything bigger isn't a sane header.
+const MAX_ELEMENT_LEN: u64 = 16 * 1024 * 1024;
+
+/// Top-level Segment children — where an unknown-size Cluster implicitly ends.
+fn is_segment_child(id: u32) -> bool {
+    matches!(id, SEEK_HEAD | INFO | TRACKS | CLUSTER | CUES | CHAPTERS | TAGS | ATTACHMENTS)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Compression {
+    Zlib,
+    HeaderStripping(Vec<u8>),
+    Unsupported(String),
+}
+
+/// A subtitle track as listed in the file's Tracks element.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubtitleTrack {
+    /// Matroska TrackNumber — what `read_track` and `SourceFile.mkv_track` take.
+    pub number: u64,
+    /// mpv's `--sid`: 1-based position among the file's subtitle tracks.
+    pub sid: usize,
+    pub codec: String,
+    pub language: String,
+    pub name: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+    #[serde(skip)]
+    default_duration: Option<u64>,
+    #[serde(skip)]
+    codec_private: Vec<u8>,
+    #[serde(skip)]
+    compression: Option<Compression>, // Applies to block payloads
+    #[serde(skip)]
+    private_compression: Option<Compression>,
+}
+
+impl SubtitleTrack {
+    /// Text codecs Yomine can read; PGS/VobSub bitmaps can't be tokenized.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self.codec.as_str(),
+            "S_TEXT/UTF8" | "S_TEXT/ASCII" | "S_TEXT/ASS" | "S_TEXT/SSA" | "S_TEXT/WEBVTT"
+        )
+    }
+
+    pub fn is_japanese(&self) -> bool {
+        let language = self.language.to_ascii_lowercase();
+        language == "jpn" || language == "ja" || language.starts_with("ja-")
+    }
+
+    fn is_ssa(&self) -> bool {
+        matches!(self.codec.as_str(), "S_TEXT/ASS" | "S_TEXT/SSA")
+    }
+
+    fn decode(
+        &self,
+        compression: Option<&Compression>,
+        data: Vec<u8>,
+    ) -> Result<Vec<u8>, YomineError> {
+        match compression {
+            None => Ok(data),
+            Some(Compression::Zlib) => {
+                miniz_oxide::inflate::decompress_to_vec_zlib(&data).map_err(|e| {
+                    YomineError::Custom(format!("Failed to inflate MKV subtitle block: {e:?}"))
+                })
+            }
+            Some(Compression::HeaderStripping(header)) => {
+                let mut full = header.clone();
+                full.extend(data);
+                Ok(full)
+            }
+            Some(Compression::Unsupported(what)) => Err(YomineError::Custom(format!(
+                "MKV subtitle track {} uses unsupported {}",
+                self.number, what
+            ))),
+        }
+    }
+}
+
+/// A text track's content: ASS/SSA tracks come back as a reassembled script
+/// (for the SSA parser), the rest as `(start, end, text)` cues.
+pub enum TrackText {
+    Ssa(String),
+    Cues(Vec<(Time, Time, String)>),
+}
+
+/// The track to read when the user hasn't picked one: the first Japanese text
+/// track (unforced, then default-flagged first), else the first text track.
+pub fn preferred_track(tracks: &[SubtitleTrack]) -> Option<&SubtitleTrack> {
+    let text = || tracks.iter().filter(|t| t.is_text());
+    text()
+        .filter(|t| t.is_japanese())
+        .min_by_key(|t| (t.forced, !t.default))
+        .or_else(|| text().next())
+}
+
+/// Small on purpose: skipping each video/audio frame re-fills the buffer from
+/// the seek target, and only the few header bytes there are ever used.
+fn open(path: &str) -> Result<BufReader<File>, YomineError> {
+    Ok(BufReader::with_capacity(1024, File::open(path)?))
+}
+
+/// Every subtitle track in an `.mkv`/`.mks`, in file order. Stops reading at the
+/// first Cluster, so this is cheap on multi-gigabyte files.
+pub fn subtitle_tracks(path: &str) -> Result<Vec<SubtitleTrack>, YomineError> {
+    Ok(scan(open(path)?, None)?.tracks)
+}
+
+/// Read one text subtitle track (by TrackNumber) out of a Matroska file.
+pub fn read_track(path: &str, number: u64) -> Result<TrackText, YomineError> {
+    track_text(open(path)?, number)
+}
+
+fn track_text<R: Read + Seek>(reader: R, number: u64) -> Result<TrackText, YomineError> {
+    let Scan { tracks, blocks, timestamp_scale } = scan(reader, Some(number))?;
+    let track = tracks
+        .iter()
+        .find(|t| t.number == number)
+        .ok_or_else(|| YomineError::Custom(format!("MKV has no subtitle track number {number}")))?;
+    if !track.is_text() {
+        return Err(YomineError::UnsupportedFileType(format!(
+            "{} (image-based subtitles can't be read as text)",
+            track.codec
+        )));
+    }
+
+    let mut events = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let data = track.decode(track.compression.as_ref(), block.data)?;
+        let text = String::from_utf8_lossy(&data).trim_end_matches('\0').to_string();
+        let start = block.time.saturating_mul(timestamp_scale as i64);
+        let duration = block
+            .duration
+            .map(|d| d.saturating_mul(timestamp_scale))
+            .or(track.default_duration)
+            .unwrap_or(0);
+        events.push((start, start.saturating_add(duration as i64), text));
+    }
+
+    if track.is_ssa() {
+        let private =
+            track.decode(track.private_compression.as_ref(), track.codec_private.clone())?;
+        return Ok(TrackText::Ssa(ssa_script(&String::from_utf8_lossy(&private), events)));
+    }
+
+    events.sort_by_key(|(start, _, _)| *start);
+    Ok(TrackText::Cues(
+        events
+            .into_iter()
+            .map(|(start, end, text)| (ns_to_time(start), ns_to_time(end), text))
+            .collect(),
+    ))
+}
+
+/// Matroska stores ASS events as `ReadOrder,Layer,Style,Name,MarginL,MarginR,
+/// MarginV,Effect,Text` with the timing in the block; put the timing back and
+/// append them, in read order, to the header kept in CodecPrivate.
+fn ssa_script(header: &str, events: Vec<(i64, i64, String)>) -> String {
+    let mut lines: Vec<(u64, String)> = events
+        .into_iter()
+        .filter_map(|(start, end, event)| {
+            let fields: Vec<&str> = event.splitn(9, ',').collect();
+            let [read_order, layer, rest @ ..] = fields.as_slice() else {
+                return None;
+            };
+            let read_order = read_order.trim().parse().unwrap_or(u64::MAX);
+            Some((
+                read_order,
+                format!(
+                    "Dialogue: {},{},{},{}",
+                    layer,
+                    ass_time(start),
+                    ass_time(end),
+                    rest.join(",")
+                ),
+            ))
+        })
+        .collect();
+    lines.sort_by_key(|(read_order, _)| *read_order);
+
+    let mut script = header.trim_end_matches('\0').trim_end().to_string();
+    if !script.contains("[Events]") {
+        script.push_str(
+            "\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text",
+        );
+    }
+    for (_, line) in lines {
+        script.push('\n');
+        script.push_str(&line);
+    }
+    script.push('\n');
+    script
+}
+
+/// `H:MM:SS.cc`, the ASS timestamp format (hours don't wrap).
+fn ass_time(ns: i64) -> String {
+    let cs = ns.max(0) / 10_000_000;
+    format!("{}:{:02}:{:02}.{:02}", cs / 360_000, cs / 6_000 % 60, cs / 100 % 60, cs % 100)
+}
+
+/// Subtitle timestamps wrap at 24h, like the other formats.
+fn ns_to_time(ns: i64) -> Time {
+    let ms = ns.max(0) / 1_000_000;
+    let secs = ms / 1000 % 86_400;
+    Time::from_hms_milli(
+        (secs / 3600) as u8,
+        (secs / 60 % 60) as u8,
+        (secs % 60) as u8,
+        (ms % 1000) as u16,
+    )
+    .unwrap_or(Time::MIDNIGHT)
+}
+
+struct Block {
+    time: i64, // In TimestampScale units, cluster-relative offset applied
+    duration: Option<u64>,
+    data: Vec<u8>,
+}
+
+struct Scan {
+    tracks: Vec<SubtitleTrack>,
+    blocks: Vec<Block>,
+    timestamp_scale: u64,
+}
+
+struct Header {
+    id: u32,
+    start: u64,
+    data: u64,
+    size: Option<u64>, // `None` = unknown size (live-muxed files)
+}
+
+impl Header {
+    fn end(&self) -> Option<u64> {
+        self.size.map(|size| self.data + size)
+    }
+}
+
+fn invalid(what: &str) -> YomineError {
+    YomineError::Custom(format!("Invalid MKV file: {what}"))
+}
+
+struct Ebml<R> {
+    reader: R,
+    pos: u64,
+}
+
+impl<R: Read + Seek> Ebml<R> {
+    fn new(mut reader: R) -> Result<Self, YomineError> {
+        let pos = reader.stream_position()?;
+        Ok(Self { reader, pos })
+    }
+
+    fn byte(&mut self) -> Result<Option<u8>, YomineError> {
+        let mut buf = [0u8];
+        loop {
+            match self.reader.read(&mut buf) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {
+                    self.pos += 1;
+                    return Ok(Some(buf[0]));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Raw variable-length integer (marker bit kept) and its byte length.
+    fn vint(&mut self) -> Result<Option<(u64, u32)>, YomineError> {
+        let Some(first) = self.byte()? else {
+            return Ok(None);
+        };
+        let len = first.leading_zeros() + 1;
+        if len > 8 {
+            return Err(invalid("bad variable-length integer"));
+        }
+        let mut value = first as u64;
+        for _ in 1..len {
+            let next = self.byte()?.ok_or_else(|| invalid("truncated integer"))?;
+            value = (value << 8) | next as u64;
+        }
+        Ok(Some((value, len)))
+    }
+
+    /// Strip the length marker; all value bits set means "unknown".
+    fn size(&mut self) -> Result<Option<u64>, YomineError> {
+        let (raw, len) = self.vint()?.ok_or_else(|| invalid("truncated element size"))?;
+        let mask = (1u64 << (7 * len)) - 1;
+        let value = raw & mask;
+        Ok((value != mask).then_some(value))
+    }
+
+    fn header(&mut self) -> Result<Option<Header>, YomineError> {
+        let start = self.pos;
+        let Some((id, len)) = self.vint()? else {
+            return Ok(None);
+        };
+        if len > 4 {
+            return Err(invalid("bad element ID"));
+        }
+        let size = self.size()?;
+        Ok(Some(Header { id: id as u32, start, data: self.pos, size }))
+    }
+
+    fn seek(&mut self, pos: u64) -> Result<(), YomineError> {
+        self.reader.seek(SeekFrom::Start(pos))?;
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn skip(&mut self, header: &Header) -> Result<(), YomineError> {
+        let end = header.end().ok_or_else(|| invalid("unknown-size element can't be skipped"))?;
+        self.seek(end)
+    }
+
+    fn read_exact(&mut self, len: u64) -> Result<Vec<u8>, YomineError> {
+        if len > MAX_ELEMENT_LEN {
+            return Err(invalid("oversized element"));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.reader.read_exact(&mut buf)?;
+        self.pos += len;
+        Ok(buf)
+    }
+
+    fn body(&mut self, header: &Header) -> Result<Vec<u8>, YomineError> {
+        let size = header.size.ok_or_else(|| invalid("unknown-size element"))?;
+        self.read_exact(size)
+    }
+}
+
+fn uint(bytes: &[u8]) -> u64 {
+    bytes.iter().take(8).fold(0, |acc, b| (acc << 8) | *b as u64)
+}
+
+fn string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string()
+}
+
+/// The `(id, body)` children of an in-memory master element.
+fn children(body: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, YomineError> {
+    let mut ebml = Ebml::new(Cursor::new(body))?;
+    let mut out = Vec::new();
+    while let Some(header) = ebml.header()? {
+        let data = ebml.body(&header)?;
+        out.push((header.id, data));
+    }
+    Ok(out)
+}
+
+fn parse_tracks(body: &[u8]) -> Result<Vec<SubtitleTrack>, YomineError> {
+    let mut tracks = Vec::new();
+    for (_, entry) in children(body)?.into_iter().filter(|(id, _)| *id == TRACK_ENTRY) {
+        let mut track_type = 0;
+        let mut track = SubtitleTrack {
+            number: 0,
+            sid: 0,
+            codec: String::new(),
+            language: "eng".to_string(), // The Matroska default when absent
+            name: None,
+            default: true,
+            forced: false,
+            default_duration: None,
+            codec_private: Vec::new(),
+            compression: None,
+            private_compression: None,
+        };
+        let mut bcp47 = None;
+        for (id, data) in children(&entry)? {
+            match id {
+                TRACK_NUMBER => track.number = uint(&data),
+                TRACK_TYPE => track_type = uint(&data),
+                CODEC_ID => track.codec = string(&data),
+                CODEC_PRIVATE => track.codec_private = data,
+                LANGUAGE => track.language = string(&data),
+                LANGUAGE_BCP47 => bcp47 = Some(string(&data)),
+                NAME => track.name = Some(string(&data)).filter(|n| !n.trim().is_empty()),
+                FLAG_DEFAULT => track.default = uint(&data) != 0,
+                FLAG_FORCED => track.forced = uint(&data) != 0,
+                DEFAULT_DURATION => track.default_duration = Some(uint(&data)),
+                CONTENT_ENCODINGS => parse_encodings(&data, &mut track)?,
+                _ => {}
+            }
+        }
+        if track_type == TRACK_TYPE_SUBTITLE {
+            if let Some(bcp47) = bcp47 {
+                track.language = bcp47;
+            }
+            track.sid = tracks.len() + 1;
+            tracks.push(track);
+        }
+    }
+    Ok(tracks)
+}
+
+fn parse_encodings(body: &[u8], track: &mut SubtitleTrack) -> Result<(), YomineError> {
+    for (_, encoding) in children(body)?.into_iter().filter(|(id, _)| *id == CONTENT_ENCODING) {
+        let mut scope = 1;
+        let mut kind = 0;
+        let mut algo = 0;
+        let mut settings = Vec::new();
+        for (id, data) in children(&encoding)? {
+            match id {
+                CONTENT_ENCODING_SCOPE => scope = uint(&data),
+                CONTENT_ENCODING_TYPE => kind = uint(&data),
+                CONTENT_COMPRESSION => {
+                    for (id, data) in children(&data)? {
+                        match id {
+                            CONTENT_COMP_ALGO => algo = uint(&data),
+                            CONTENT_COMP_SETTINGS => settings = data,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let compression = match (kind, algo) {
+            (1, _) => Compression::Unsupported("encryption".to_string()),
+            (_, 0) => Compression::Zlib,
+            (_, 3) => Compression::HeaderStripping(settings),
+            (_, other) => Compression::Unsupported(format!("compression algorithm {other}")),
+        };
+        // Scope bits: 1 = block payloads, 2 = CodecPrivate.
+        if scope & 2 != 0 {
+            track.private_compression = Some(compression.clone());
+        }
+        if scope & 1 != 0 {
+            track.compression = Some(compression);
+        }
+    }
+    Ok(())
+}
+
+/// Walk the Segment. With `want = None` stop once the tracks are known (at the
+/// first Cluster); otherwise collect that track's blocks from every Cluster.
+fn scan<R: Read + Seek>(reader: R, want: Option<u64>) -> Result<Scan, YomineError> {
+    let mut ebml = Ebml::new(reader)?;
+
+    let header = ebml
+        .header()?
+        .filter(|h| h.id == EBML_HEADER)
+        .ok_or_else(|| YomineError::UnsupportedFileType("not a Matroska file".to_string()))?;
+    ebml.skip(&header)?;
+
+    let segment = loop {
+        let header = ebml.header()?.ok_or_else(|| invalid("no Segment"))?;
+        if header.id == SEGMENT {
+            break header;
+        }
+        ebml.skip(&header)?;
+    };
+
+    let mut scan = Scan { tracks: Vec::new(), blocks: Vec::new(), timestamp_scale: 1_000_000 };
+    let mut tracks_seen = false;
+    while segment.end().is_none_or(|end| ebml.pos < end) {
+        let Some(child) = ebml.header()? else {
+            break;
+        };
+        match child.id {
+            INFO => {
+                for (id, data) in children(&ebml.body(&child)?)? {
+                    if id == TIMESTAMP_SCALE {
+                        scan.timestamp_scale = uint(&data).max(1);
+                    }
+                }
+            }
+            TRACKS => {
+                scan.tracks = parse_tracks(&ebml.body(&child)?)?;
+                tracks_seen = true;
+            }
+            CLUSTER => {
+                let Some(want) = want else {
+                    if tracks_seen {
+                        break;
+                    }
+                    read_cluster(&mut ebml, &child, 0, &mut scan.blocks)?;
+                    continue;
+                };
+                // Guard against collecting a video track's frames into memory.
+                if tracks_seen && !scan.tracks.iter().any(|t| t.number == want) {
+                    return Err(YomineError::Custom(format!(
+                        "MKV has no subtitle track number {want}"
+                    )));
+                }
+                read_cluster(&mut ebml, &child, want, &mut scan.blocks)?;
+            }
+            _ => ebml.skip(&child)?,
+        }
+    }
+
+    if !tracks_seen {
+        return Err(invalid("no Tracks element"));
+    }
+    Ok(scan)
+}
+
+fn read_cluster<R: Read + Seek>(
+    ebml: &mut Ebml<R>,
+    cluster: &Header,
+    want: u64,
+    blocks: &mut Vec<Block>,
+) -> Result<(), YomineError> {
+    let mut cluster_time = 0;
+    while cluster.end().is_none_or(|end| ebml.pos < end) {
+        let Some(child) = ebml.header()? else {
+            break;
+        };
+        if cluster.size.is_none() && is_segment_child(child.id) {
+            ebml.seek(child.start)?;
+            break;
+        }
+        match child.id {
+            CLUSTER_TIMESTAMP => cluster_time = uint(&ebml.body(&child)?) as i64,
+            SIMPLE_BLOCK => {
+                if let Some((offset, data)) = read_block(ebml, &child, want)? {
+                    blocks.push(Block { time: cluster_time + offset, duration: None, data });
+                }
+            }
+            BLOCK_GROUP => {
+                let end = child.end().ok_or_else(|| invalid("unknown-size BlockGroup"))?;
+                let mut block = None;
+                let mut duration = None;
+                while ebml.pos < end {
+                    let inner = ebml.header()?.ok_or_else(|| invalid("truncated BlockGroup"))?;
+                    match inner.id {
+                        BLOCK => block = read_block(ebml, &inner, want)?,
+                        BLOCK_DURATION => duration = Some(uint(&ebml.body(&inner)?)),
+                        _ => ebml.skip(&inner)?,
+                    }
+                }
+                if let Some((offset, data)) = block {
+                    blocks.push(Block { time: cluster_time + offset, duration, data });
+                }
+            }
+            _ => ebml.skip(&child)?,
+        }
+    }
+    Ok(())
+}
+
+/// A (Simple)Block's timestamp offset and payload if it belongs to `want`;
+/// other tracks' frames are seeked past unread.
+fn read_block<R: Read + Seek>(
+    ebml: &mut Ebml<R>,
+    block: &Header,
+    want: u64,
+) -> Result<Option<(i64, Vec<u8>)>, YomineError> {
+    let end = block.end().ok_or_else(|| invalid("unknown-size Block"))?;
+    let (raw, len) = ebml.vint()?.ok_or_else(|| invalid("truncated Block"))?;
+    let track = raw & ((1u64 << (7 * len)) - 1);
+    if track != want {
+        ebml.seek(end)?;
+        return Ok(None);
+    }
+    let head = ebml.read_exact(3)?;
+    let offset = i16::from_be_bytes([head[0], head[1]]) as i64;
+    let laced = head[2] & 0x06 != 0;
+    let data = ebml.read_exact(end.saturating_sub(ebml.pos))?;
+    // Subtitle muxers never lace; a laced frame would need splitting, so drop it.
+    Ok((!laced).then_some((offset, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(id: u32, body: &[u8]) -> Vec<u8> {
+        let mut out: Vec<u8> = id.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        out.push(0x01); // 8-byte size
+        out.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn unknown_size(id: u32, body: &[u8]) -> Vec<u8> {
+        let mut out: Vec<u8> = id.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        out.push(0xFF);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn uint_element(id: u32, value: u64) -> Vec<u8> {
+        element(id, &value.to_be_bytes())
+    }
+
+    fn track(number: u64, kind: u64, codec: &str, language: &str, extra: &[u8]) -> Vec<u8> {
+        let mut body = uint_element(TRACK_NUMBER, number);
+        body.extend(uint_element(TRACK_TYPE, kind));
+        body.extend(element(CODEC_ID, codec.as_bytes()));
+        body.extend(element(LANGUAGE, language.as_bytes()));
+        body.extend_from_slice(extra);
+        element(TRACK_ENTRY, &body)
+    }
+
+    fn block(track: u8, offset: i16, payload: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x80 | track];
+        body.extend(offset.to_be_bytes());
+        body.push(0);
+        body.extend_from_slice(payload);
+        body
+    }
+
+    fn group(track: u8, offset: i16, duration: u64, payload: &[u8]) -> Vec<u8> {
+        let mut body = element(BLOCK, &block(track, offset, payload));
+        body.extend(uint_element(BLOCK_DURATION, duration));
+        element(BLOCK_GROUP, &body)
+    }
+
+    fn mkv(tracks: &[Vec<u8>], clusters: &[Vec<u8>]) -> Cursor<Vec<u8>> {
+        let mut segment = element(INFO, &uint_element(TIMESTAMP_SCALE, 1_000_000));
+        segment.extend(element(TRACKS, &tracks.concat()));
+        segment.extend(clusters.concat());
+        let mut file = element(EBML_HEADER, &element(0x4282, b"matroska"));
+        file.extend(element(SEGMENT, &segment));
+        Cursor::new(file)
+    }
+
+    #[test]
+    fn lists_subtitle_tracks_with_mpv_sids() {
+        let file = mkv(
+            &[
+                track(1, 1, "V_MPEG4/ISO/AVC", "und", &[]),
+                track(2, 17, "S_HDMV/PGS", "jpn", &[]),
+                track(3, 17, "S_TEXT/ASS", "eng", &uint_element(FLAG_DEFAULT, 1)),
+                track(4, 17, "S_TEXT/UTF8", "jpn", &element(NAME, b"Japanese")),
+            ],
+            &[element(CLUSTER, &uint_element(CLUSTER_TIMESTAMP, 0))],
+        );
+
+        let tracks = scan(file, None).unwrap().tracks;
+        let summary: Vec<(u64, usize, &str)> =
+            tracks.iter().map(|t| (t.number, t.sid, t.codec.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![(2, 1, "S_HDMV/PGS"), (3, 2, "S_TEXT/ASS"), (4, 3, "S_TEXT/UTF8")]
+        );
+        assert_eq!(tracks[2].name.as_deref(), Some("Japanese"));
+        // The PGS track is Japanese but not text.
+        assert_eq!(preferred_track(&tracks).map(|t| t.number), Some(4));
+    }
+
+    #[test]
+    fn rebuilds_ass_script_in_read_order() {
+        let header = "[Script Info]\nScriptType: v4.00+\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n";
+        let file = mkv(
+            &[track(1, 17, "S_TEXT/ASS", "jpn", &element(CODEC_PRIVATE, header.as_bytes()))],
+            &[element(
+                CLUSTER,
+                &[
+                    uint_element(CLUSTER_TIMESTAMP, 61_000),
+                    group(1, 2_000, 1_500, b"1,0,Default,,0,0,0,,\xe5\xbe\x8c, then"),
+                    group(1, 0, 1_000, b"0,0,Default,,0,0,0,,{\\i1}\xe5\x85\x88"),
+                ]
+                .concat(),
+            )],
+        );
+
+        let TrackText::Ssa(script) = track_text(file, 1).unwrap() else {
+            panic!("expected an SSA script");
+        };
+        assert!(script.ends_with(
+            "Dialogue: 0,0:01:01.00,0:01:02.00,Default,,0,0,0,,{\\i1}先\n\
+             Dialogue: 0,0:01:03.00,0:01:04.50,Default,,0,0,0,,後, then\n"
+        ));
+    }
+
+    #[test]
+    fn reads_compressed_text_from_unknown_size_clusters() {
+        let zlib = {
+            let mut body = uint_element(CONTENT_ENCODING_SCOPE, 1);
+            body.extend(element(CONTENT_COMPRESSION, &uint_element(CONTENT_COMP_ALGO, 0)));
+            element(CONTENT_ENCODINGS, &element(CONTENT_ENCODING, &body))
+        };
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib("こんにちは".as_bytes(), 6);
+        let file = mkv(
+            &[track(5, 17, "S_TEXT/UTF8", "jpn", &zlib)],
+            &[
+                unknown_size(
+                    CLUSTER,
+                    &[
+                        uint_element(CLUSTER_TIMESTAMP, 1_000),
+                        element(SIMPLE_BLOCK, &block(1, 0, b"video frame")),
+                        group(5, 500, 2_000, &compressed),
+                    ]
+                    .concat(),
+                ),
+                element(CLUSTER, &uint_element(CLUSTER_TIMESTAMP, 10_000)),
+            ],
+        );
+
+        let TrackText::Cues(cues) = track_text(file, 5).unwrap() else {
+            panic!("expected cues");
+        };
+        assert_eq!(cues.len(), 1);
+        let (start, end, text) = &cues[0];
+        assert_eq!((start.second(), start.millisecond(), end.second()), (1, 500, 3));
+        assert_eq!(text, "こんにちは");
+    }
+}
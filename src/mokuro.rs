@@ -0,0 +1,255 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use serde::Deserialize;
+
+use crate::core::{
+    models::SourceFileType,
+    YomineError,
+};
+
+/// A `.mokuro` volume: mokuro's single-file output, every page's OCR inline.
+#[derive(Deserialize)]
+struct Volume {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    volume: Option<String>,
+    pages: Vec<Page>,
+}
+
+/// One page, either inside a `.mokuro` volume or as its own `_ocr` JSON.
+#[derive(Deserialize)]
+struct Page {
+    #[serde(default)]
+    img_width: Option<u32>,
+    blocks: Vec<Block>,
+}
+
+#[derive(Deserialize)]
+struct Block {
+    #[serde(default)]
+    lines: Vec<String>,
+}
+
+/// One speech bubble / text box. `page` is 1-based (as printed), `block` is
+/// the 0-based index into that page's blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBlock {
+    pub page: usize,
+    pub block: usize,
+    pub text: String,
+}
+
+fn read_volume(path: &Path) -> Result<Volume, YomineError> {
+    let raw = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&raw)?)
+}
+
+fn read_page(path: &Path) -> Option<Page> {
+    let raw = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<Page>(&raw).ok().filter(|page| page.img_width.is_some())
+}
+
+/// Digit runs compared by value, so `page_2.json` sorts before `page_10.json`.
+pub fn natural_key(name: &str) -> Vec<(String, u64)> {
+    let mut key = Vec::new();
+    let mut rest = name;
+    while !rest.is_empty() {
+        let text_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let (text, tail) = rest.split_at(text_len);
+        let digit_len = tail.find(|c: char| !c.is_ascii_digit()).unwrap_or(tail.len());
+        let (digits, tail) = tail.split_at(digit_len);
+        key.push((text.to_lowercase(), digits.parse().unwrap_or(0)));
+        rest = tail;
+    }
+    key
+}
+
+fn page_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        })
+        .collect();
+    files.sort_by_cached_key(|path| {
+        natural_key(path.file_name().and_then(|n| n.to_str()).unwrap_or_default())
+    });
+    files
+}
+
+/// Whether `dir` is a volume of mokuro page JSONs (the legacy `_ocr/<volume>/`
+/// layout). Only the first page is parsed.
+pub fn is_page_dir(dir: &Path) -> bool {
+    dir.is_dir() && page_files(dir).first().is_some_and(|first| read_page(first).is_some())
+}
+
+/// A path's source type for loading: `SourceFileType::from_extension`, except
+/// that a directory of mokuro page JSONs is a Mokuro volume. Reads the
+/// directory, so it belongs at load time rather than in the type itself.
+pub fn source_file_type(path: &str) -> SourceFileType {
+    if is_page_dir(Path::new(path)) {
+        SourceFileType::Mokuro
+    } else {
+        SourceFileType::from_extension(path)
+    }
+}
+
+/// Whether a page directory is the `_ocr` cache of a volume that also has a
+/// `.mokuro` file next to `_ocr` — scanning both would count the volume twice.
+pub fn is_cached_volume(dir: &Path) -> bool {
+    let (Some(name), Some(ocr)) = (dir.file_name(), dir.parent()) else {
+        return false;
+    };
+    if ocr.file_name().and_then(|n| n.to_str()) != Some("_ocr") {
+        return false;
+    }
+    let Some(root) = ocr.parent() else {
+        return false;
+    };
+    let mut volume = name.to_os_string();
+    volume.push(".mokuro");
+    root.join(volume).is_file()
+}
+
+/// "Title Volume" from a `.mokuro` file's metadata; `None` for page directories
+/// or when mokuro recorded neither.
+pub fn volume_title(path: &str) -> Option<String> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        return None;
+    }
+    let volume = read_volume(path).ok()?;
+    let title = [volume.title, volume.volume]
+        .into_iter()
+        .flatten()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Every non-empty text block of a volume, in reading order: a `.mokuro` file or
+/// a directory of page JSONs. A block's OCR lines are the columns of one bubble,
+/// so they join without a separator.
+pub fn text_blocks(path: &str) -> Result<Vec<TextBlock>, YomineError> {
+    let path = Path::new(path);
+    let pages = if path.is_dir() {
+        let files = page_files(path);
+        if files.is_empty() {
+            return Err(YomineError::Custom(format!(
+                "No mokuro page files found in {}",
+                path.display()
+            )));
+        }
+        // Numbered by file position, so a skipped page leaves a gap rather
+        // than shifting every later page.
+        files
+            .iter()
+            .enumerate()
+            .filter_map(|(page_idx, file)| match read_page(file) {
+                Some(page) => Some((page_idx, page)),
+                None => {
+                    eprintln!("Skipping unreadable mokuro page {}", file.display());
+                    None
+                }
+            })
+            .collect()
+    } else {
+        read_volume(path)?.pages.into_iter().enumerate().collect::<Vec<_>>()
+    };
+
+    Ok(pages
+        .into_iter()
+        .flat_map(|(page_idx, page)| {
+            page.blocks.into_iter().enumerate().filter_map(move |(block_idx, block)| {
+                let text: String = block.lines.iter().map(|line| line.trim()).collect();
+                (!text.is_empty()).then(|| TextBlock { page: page_idx + 1, block: block_idx, text })
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"{"version": "0.2.1", "img_width": 1654, "img_height": 2400, "blocks": [
+        {"box": [1, 2, 3, 4], "vertical": true, "font_size": 30.0, "lines": ["なんだ", "これは…"]},
+        {"box": [5, 6, 7, 8], "vertical": true, "font_size": 30.0, "lines": ["  "]},
+        {"box": [5, 6, 7, 8], "vertical": true, "font_size": 30.0, "lines": ["行くぞ！"]}
+    ]}"#;
+
+    #[test]
+    fn volume_blocks_keep_page_and_block_position() {
+        let dir = std::env::temp_dir().join(format!("yomine_mokuro_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("vol.mokuro");
+        std::fs::write(
+            &file,
+            format!(
+                r#"{{"version": "0.2.1", "title": "ワンパンマン", "volume": "第01巻",
+                    "pages": [{{"img_width": 1, "blocks": []}}, {PAGE}]}}"#
+            ),
+        )
+        .unwrap();
+
+        let path = file.to_str().unwrap();
+        let blocks = text_blocks(path).unwrap();
+        let title = volume_title(path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            blocks,
+            vec![
+                TextBlock { page: 2, block: 0, text: "なんだこれは…".to_string() },
+                TextBlock { page: 2, block: 2, text: "行くぞ！".to_string() },
+            ]
+        );
+        assert_eq!(title.as_deref(), Some("ワンパンマン 第01巻"));
+    }
+
+    #[test]
+    fn page_dir_reads_in_natural_order() {
+        let root = std::env::temp_dir().join(format!("yomine_mokuro_ocr_{}", std::process::id()));
+        let dir = root.join("_ocr").join("vol");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["p10.json", "p2.json"] {
+            std::fs::write(dir.join(name), PAGE.replace("なんだ", name)).unwrap();
+        }
+
+        let blocks = text_blocks(dir.to_str().unwrap()).unwrap();
+        let detected = is_page_dir(&dir);
+        let cached_before = is_cached_volume(&dir);
+        std::fs::write(root.join("vol.mokuro"), "{}").unwrap();
+        let cached_after = is_cached_volume(&dir);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(detected);
+        assert_eq!(blocks[0].text, "p2.jsonこれは…");
+        assert_eq!((blocks[2].page, blocks[2].text.as_str()), (2, "p10.jsonこれは…"));
+        assert!(!cached_before && cached_after);
+    }
+
+    #[test]
+    fn unreadable_page_keeps_later_page_numbers() {
+        let dir = std::env::temp_dir().join(format!("yomine_mokuro_gap_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("p1.json"), PAGE).unwrap();
+        std::fs::write(dir.join("p2.json"), "{ truncated").unwrap();
+        std::fs::write(dir.join("p3.json"), PAGE).unwrap();
+
+        let blocks = text_blocks(dir.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let pages: Vec<usize> = blocks.iter().map(|b| b.page).collect();
+        assert_eq!(pages, [1, 1, 3, 3]);
+    }
+}
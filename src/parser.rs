@@ -1,22 +1,40 @@
 use std::{
     fs,
+    io::Read,
     sync::LazyLock,
 };
 
+use encoding_rs::{
+    Encoding,
+    EUC_JP,
+    SHIFT_JIS,
+    UTF_16BE,
+    UTF_16LE,
+    UTF_8,
+};
 use regex::Regex;
 use rsubs_lib::{
+    ssa::SSAEventLineType,
     SRT,
     SSA,
 };
+use time::Time;
 
-use crate::core::{
-    models::{
-        SourceFileType,
-        TimeStamp,
+use crate::{
+    core::{
+        models::{
+            PageLocation,
+            RubyHint,
+            SourceFileType,
+            TextEncoding,
+            TextMarkup,
+            TimeStamp,
+        },
+        Sentence,
+        SourceFile,
+        YomineError,
     },
-    Sentence,
-    SourceFile,
-    YomineError,
+    mkv::TrackText,
 };
 
 // Regex now handles any parathesis (full or half width) that contains only hiragana. Not sure if we should include Katakana
@@ -26,12 +44,24 @@ static KANA_READING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
         .expect("Failed to compile kana-reading regex")
 });
 
+static SENTENCE_SPLIT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"([。！？｡!?.]+)").expect("Failed to compile sentence split regex")
+});
+
 static STRIP_INLINE_TAGS: LazyLock<Regex> = LazyLock::new(|| {
     // Any HTML/WebVTT-style tag (a whitelist kept leaking) + ASS {\...} overrides.
     Regex::new(r"(?i)</?[a-z][^<>]*>|\{\\[^}]*\}")
         .expect("Failed to compile inline_strip_tags regex")
 });
 
+// Ruby annotations (<rt>/<rp>) carry the reading, not the line: drop them with their
+// content before the tag strip would splice the furigana into the text. WebVTT lets
+// `</rt>` be implied by `</ruby>`, so the close tag is optional.
+static RUBY_ANNOTATIONS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)<(?:rt|rp)\b[^<>]*>[^<]*(?:</(?:rt|rp)>)?")
+        .expect("Failed to compile ruby-annotation regex")
+});
+
 // Single pass so &amp;lt; yields the literal &lt; instead of double-decoding.
 fn decode_html_entities(text: &str) -> String {
     let mut out = String::with_capacity(text.len());
@@ -73,6 +103,107 @@ fn decode_html_entities(text: &str) -> String {
     out
 }
 
+fn codec(encoding: TextEncoding) -> &'static Encoding {
+    match encoding {
+        TextEncoding::Utf8 => UTF_8,
+        TextEncoding::Utf16Le => UTF_16LE,
+        TextEncoding::Utf16Be => UTF_16BE,
+        // WHATWG Shift_JIS is CP932 — NEC/IBM extensions included.
+        TextEncoding::ShiftJis => SHIFT_JIS,
+        TextEncoding::EucJp => EUC_JP,
+    }
+}
+
+/// How much a decoding looks like Japanese text. Kana weigh most; half-width
+/// katakana count against, since that is what EUC-JP bytes turn into when read
+/// as Shift-JIS.
+fn japanese_score(text: &str) -> i64 {
+    text.chars()
+        .map(|c| match c {
+            '\u{3040}'..='\u{30FF}' => 2,
+            '\u{3000}'..='\u{303F}' | '\u{4E00}'..='\u{9FFF}' | '\u{FF01}'..='\u{FF5E}' => 1,
+            '\u{FF61}'..='\u{FF9F}' => -2,
+            '\u{FFFD}' => -4,
+            c if c.is_control() && !c.is_whitespace() => -4,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Sniff the encoding of raw file bytes: a BOM wins outright; NUL bytes mean
+/// BOM-less UTF-16 (their parity gives the byte order); valid UTF-8 is taken as
+/// is; anything else is whichever of Shift-JIS and EUC-JP decodes cleanly, or
+/// reads more like Japanese when both (or neither) do.
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return if encoding == UTF_16LE {
+            TextEncoding::Utf16Le
+        } else if encoding == UTF_16BE {
+            TextEncoding::Utf16Be
+        } else {
+            TextEncoding::Utf8
+        };
+    }
+
+    // Real text never contains NUL; UTF-16 puts one beside every ASCII character.
+    if bytes.contains(&0) {
+        let (even, odd) = bytes.chunks_exact(2).fold((0usize, 0usize), |(even, odd), pair| {
+            (even + usize::from(pair[0] == 0), odd + usize::from(pair[1] == 0))
+        });
+        if odd > even {
+            return TextEncoding::Utf16Le;
+        }
+        if even > odd {
+            return TextEncoding::Utf16Be;
+        }
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        return TextEncoding::Utf8;
+    }
+
+    let (sjis, _, sjis_errors) = SHIFT_JIS.decode(bytes);
+    let (euc, _, euc_errors) = EUC_JP.decode(bytes);
+    match (sjis_errors, euc_errors) {
+        (false, true) => TextEncoding::ShiftJis,
+        (true, false) => TextEncoding::EucJp,
+        // Ties go to Shift-JIS — by far the more common of the two for subtitles.
+        _ if japanese_score(&euc) > japanese_score(&sjis) => TextEncoding::EucJp,
+        _ => TextEncoding::ShiftJis,
+    }
+}
+
+/// Bytes of a file's head that `detect_file_encoding` sniffs.
+const ENCODING_SNIFF_BYTES: u64 = 64 * 1024;
+
+/// Sniff a file's encoding from its first `ENCODING_SNIFF_BYTES`. A cut-short
+/// head is trimmed back to its last newline, so a multi-byte character split at
+/// the cut doesn't read as malformed (`\n` is never a trail byte in Shift-JIS
+/// or EUC-JP).
+pub fn detect_file_encoding(path: &str) -> Result<TextEncoding, YomineError> {
+    let mut head = Vec::new();
+    fs::File::open(path)?.take(ENCODING_SNIFF_BYTES).read_to_end(&mut head)?;
+    if head.len() as u64 == ENCODING_SNIFF_BYTES {
+        if let Some(end) = head.iter().rposition(|&b| b == b'\n') {
+            head.truncate(end + 1);
+        }
+    }
+    Ok(detect_encoding(&head))
+}
+
+/// Decode with a BOM (if present) stripped; malformed sequences become U+FFFD
+/// rather than failing, so a wrong override still shows *something* to correct.
+pub fn decode_text(bytes: &[u8], encoding: TextEncoding) -> String {
+    codec(encoding).decode_with_bom_removal(bytes).0.into_owned()
+}
+
+/// A source file's text in its recorded encoding, sniffing one when unset.
+fn read_text(source_file: &SourceFile) -> Result<String, YomineError> {
+    let bytes = fs::read(&source_file.original_file)?;
+    let encoding = source_file.encoding.unwrap_or_else(|| detect_encoding(&bytes));
+    Ok(decode_text(&bytes, encoding))
+}
+
 /// Shared subtitle-text cleanup: decode HTML entities, collapse whitespace,
 /// strip kana-reading parentheses and inline styling tags. Used by the
 /// subtitle parsers and the asbplayer subtitle importer (issue #105).
@@ -81,34 +212,52 @@ pub fn clean_subtitle_text(raw: &str) -> String {
     let text = decode_html_entities(raw);
     let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
     let text = KANA_READING_REGEX.replace_all(&text, "");
+    let text = RUBY_ANNOTATIONS.replace_all(&text, "");
     STRIP_INLINE_TAGS.replace_all(&text, "").trim().to_string()
 }
 
-fn parse_srt(srt: SRT, source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
-    let sentences: Vec<Sentence> = srt
-        .lines
-        .iter()
-        .filter(|s| !s.text.is_empty())
+/// Timed cues `(start, end, raw text)` → timestamped sentences. Every subtitle
+/// reader funnels through here so cues are cleaned and numbered identically.
+fn sentences_from_cues<S: AsRef<str>>(
+    cues: impl IntoIterator<Item = (Time, Time, S)>,
+    source_file: &SourceFile,
+) -> Result<Vec<Sentence>, YomineError> {
+    sentences_from_voiced_cues(
+        cues.into_iter().map(|(start, end, text)| (start, end, text, None)),
+        source_file,
+    )
+}
+
+/// `sentences_from_cues` for formats that name who speaks each cue.
+fn sentences_from_voiced_cues<S: AsRef<str>>(
+    cues: impl IntoIterator<Item = (Time, Time, S, Option<String>)>,
+    source_file: &SourceFile,
+) -> Result<Vec<Sentence>, YomineError> {
+    let sentences: Vec<Sentence> = cues
+        .into_iter()
+        .filter(|(_, _, text, _)| !text.as_ref().is_empty())
         .enumerate()
-        .filter_map(|(id, entry)| {
-            let text = clean_subtitle_text(&entry.text);
+        .filter_map(|(id, (start, end, text, speaker))| {
+            let text = clean_subtitle_text(text.as_ref());
 
             if text.is_empty() {
                 return None;
             }
 
-            let timestamp = TimeStamp { start: entry.start, end: entry.end };
-
-            Some(Ok(Sentence {
-                id: id,
+            Some(Sentence {
+                id,
                 source_id: source_file.id, // Reference to the SourceFile ID
                 segments: vec![],          // segments are generated after tokenization
-                text: text,
-                timestamp: Some(timestamp),
+                text,
+                timestamp: Some(TimeStamp { start, end }),
+                page: None,
                 comprehension: 0.0, // Will be calculated after term matching
-            }))
+                ruby: Vec::new(),
+                translation: None,
+                speaker,
+            })
         })
-        .collect::<Result<Vec<_>, YomineError>>()?;
+        .collect();
 
     if sentences.is_empty() {
         return Err(YomineError::Custom("No subtitles found in the file.".to_string()));
@@ -117,39 +266,388 @@ fn parse_srt(srt: SRT, source_file: &SourceFile) -> Result<Vec<Sentence>, Yomine
     Ok(sentences)
 }
 
+/// Longest pause between two cues that can still be one sentence.
+const CUE_JOIN_MAX_GAP_SECS: f32 = 0.75;
+
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end().ends_with([
+        '。', '！', '？', '!', '?', '.', '…', '‥', '」', '』', '）', ')', '♪', '～', '〜', '―', '—',
+    ])
+}
+
+fn ends_clause(text: &str) -> bool {
+    text.trim_end().ends_with(['、', '，', ','])
+}
+
+/// A leading dash or speaker tag marks a new voice, never a continuation.
+fn starts_new_speaker(text: &str) -> bool {
+    text.starts_with(['-', '－', '‐', '（', '('])
+}
+
+/// Merge adjacent timed sentences a cue break split in two (「だから、」 +
+/// 「行かないって言ったのに」): the second must start within
+/// `CUE_JOIN_MAX_GAP_SECS` of the first ending, and the first must end mid-
+/// sentence. Many subtitle files never use 。, so there "mid-sentence" means a
+/// trailing 、 — otherwise every line would chain into one. The merged sentence
+/// spans both cues' timestamps.
+pub fn join_split_cues(sentences: Vec<Sentence>) -> Vec<Sentence> {
+    let timed: Vec<&Sentence> = sentences.iter().filter(|s| s.timestamp.is_some()).collect();
+    let punctuated = timed.iter().filter(|s| ends_sentence(&s.text)).count() * 3 >= timed.len();
+
+    let mut joined: Vec<Sentence> = Vec::with_capacity(sentences.len());
+    for sentence in sentences {
+        if let Some(prev) = joined.last_mut() {
+            let continues =
+                if punctuated { !ends_sentence(&prev.text) } else { ends_clause(&prev.text) };
+            let close = match (&prev.timestamp, &sentence.timestamp) {
+                (Some(a), Some(b)) => {
+                    let ((a_start, a_end), (b_start, _)) = (a.to_secs(), b.to_secs());
+                    b_start >= a_start && b_start - a_end <= CUE_JOIN_MAX_GAP_SECS
+                }
+                _ => false,
+            };
+            let same_voice = prev.speaker == sentence.speaker;
+            if continues && close && same_voice && !starts_new_speaker(&sentence.text) {
+                merge_into(prev, sentence);
+                continue;
+            }
+        }
+        joined.push(sentence);
+    }
+
+    for (id, sentence) in joined.iter_mut().enumerate() {
+        sentence.id = id;
+    }
+    joined
+}
+
+fn merge_into(prev: &mut Sentence, next: Sentence) {
+    // Latin text (romaji, English) needs its word break back; Japanese doesn't.
+    let spaced = prev.text.ends_with(|c: char| c.is_ascii_alphanumeric() || c == ',')
+        && next.text.starts_with(|c: char| c.is_ascii_alphanumeric());
+    if spaced {
+        prev.text.push(' ');
+    }
+    let offset = prev.text.len();
+    prev.text.push_str(&next.text);
+    prev.ruby.extend(next.ruby.into_iter().map(|hint| RubyHint {
+        start: hint.start + offset,
+        end: hint.end + offset,
+        ..hint
+    }));
+    if let (Some(a), Some(b)) = (prev.timestamp.as_mut(), next.timestamp) {
+        a.end = a.end.max(b.end);
+    }
+}
+
+/// The translation track: any timed subtitle format, read like a primary file.
+fn read_secondary(path: &str, options: &ReadOptions) -> Result<Vec<Sentence>, YomineError> {
+    let file_type = SourceFileType::from_extension(path);
+    if !file_type.is_timed() {
+        return Err(YomineError::Custom(format!(
+            "Translation subtitles must be a timed subtitle file, not {path}"
+        )));
+    }
+    let secondary = SourceFile { file_type, original_file: path.to_string(), ..Default::default() };
+    read_primary(&secondary, options)
+}
+
+fn overlap(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (a.1.min(b.1) - a.0.max(b.0)).max(0.0)
+}
+
+/// Index of the span in `spans` that overlaps `target` the longest, if any does.
+fn best_overlap(target: (f32, f32), spans: &[Option<(f32, f32)>]) -> Option<usize> {
+    spans
+        .iter()
+        .enumerate()
+        .filter_map(|(i, span)| Some((i, overlap(target, (*span)?))))
+        .filter(|(_, secs)| *secs > 0.0)
+        .fold(None, |best: Option<(usize, f32)>, (i, secs)| match best {
+            Some((_, best_secs)) if best_secs >= secs => best,
+            _ => Some((i, secs)),
+        })
+        .map(|(i, _)| i)
+}
+
+/// Attach translation cues to timed sentences by overlap. Each cue goes to the
+/// sentence it overlaps most, so a translation split across two cues lands on
+/// one line in order; a sentence no cue chose borrows its best-overlapping cue,
+/// so two short lines spoken under one long translation both carry it.
+pub fn align_translations(sentences: &mut [Sentence], secondary: &[Sentence]) {
+    let spans: Vec<Option<(f32, f32)>> =
+        sentences.iter().map(|s| s.timestamp.as_ref().map(TimeStamp::to_secs)).collect();
+    let cue_spans: Vec<Option<(f32, f32)>> =
+        secondary.iter().map(|s| s.timestamp.as_ref().map(TimeStamp::to_secs)).collect();
+
+    let mut assigned: Vec<Vec<usize>> = vec![Vec::new(); sentences.len()];
+    for (cue, span) in cue_spans.iter().enumerate() {
+        if let Some(sentence) = span.and_then(|span| best_overlap(span, &spans)) {
+            assigned[sentence].push(cue);
+        }
+    }
+
+    for ((sentence, span), cues) in sentences.iter_mut().zip(&spans).zip(&mut assigned) {
+        if cues.is_empty() {
+            cues.extend(span.and_then(|span| best_overlap(span, &cue_spans)));
+        }
+        let text = cues.iter().map(|&cue| secondary[cue].text.as_str()).collect::<Vec<_>>();
+        sentence.translation = (!text.is_empty()).then(|| text.join(" "));
+    }
+}
+
+fn parse_srt(srt: SRT, source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
+    sentences_from_cues(
+        srt.lines.iter().map(|entry| (entry.start, entry.end, entry.text.as_str())),
+        source_file,
+    )
+}
+
 pub fn read_srt(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
     //So far we only know netflix uses this formatting as per (https://partnerhelp.netflixstudios.com/hc/en-us/articles/215767517-Japanese-Timed-Text-Style-Guide)
     // let delete_readings = source_file.creator.as_deref() == Some("Netflix");
 
-    let raw_srt = fs::read_to_string(&source_file.original_file)?;
-    //new rsubs doesn't like utf-8 BOM at the beginning of the file:
-    let raw_srt = raw_srt.trim_start_matches('\u{feff}');
-    let srt = SRT::parse(raw_srt)
+    //new rsubs doesn't like utf-8 BOM at the beginning of the file; read_text strips it.
+    let raw_srt = read_text(source_file)?;
+    let srt = SRT::parse(&raw_srt)
         .map_err(|err| YomineError::Custom(format!("Error Parsing SRT File: {}", err)))?;
 
     parse_srt(srt, source_file)
 }
 
-fn read_ssa(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
-    let raw_file = fs::read_to_string(&source_file.original_file)?;
-    let raw_file = raw_file.trim_start_matches('\u{feff}');
+/// A style (or actor) glob from `ReadOptions::excluded_styles`: `*` matches any
+/// run of characters. Case-insensitive unless the pattern has an uppercase
+/// letter, so `*sign*` catches `Sign`, while `*OP*` catches `OP-JP` but not `Top`.
+pub fn style_pattern_matches(pattern: &str, name: &str) -> bool {
+    let name = if pattern.chars().any(char::is_uppercase) {
+        name.to_string()
+    } else {
+        name.to_lowercase()
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let [first, middle @ .., last] = parts.as_slice() else {
+        return pattern == name;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
 
-    let ssa = SSA::parse_lenient(raw_file)
-        .map_err(|err| YomineError::Custom(format!("Error Parsing SSA/ASS File: {}", err)))?;
+/// Whether an event is dropped: the file's own style list when the user picked
+/// one, else the option globs against its style or actor.
+fn is_excluded_style(
+    style: &str,
+    actor: &str,
+    source_file: &SourceFile,
+    options: &ReadOptions,
+) -> bool {
+    match &source_file.excluded_styles {
+        Some(names) => names.iter().any(|name| name == style),
+        None => options.excluded_styles.iter().any(|pattern| {
+            style_pattern_matches(pattern, style)
+                || (!actor.is_empty() && style_pattern_matches(pattern, actor))
+        }),
+    }
+}
 
-    let srt = ssa.to_srt();
+fn parse_ssa_script(raw: &str) -> Result<SSA, YomineError> {
+    SSA::parse_lenient(raw)
+        .map_err(|err| YomineError::Custom(format!("Error Parsing SSA/ASS File: {}", err)))
+}
 
-    parse_srt(srt, source_file)
+/// Dialogue events only (no comments), minus excluded styles/actors and vector
+/// drawings (`{\p1}`), in time order; each keeps its actor as the speaker.
+fn parse_ssa(
+    raw: &str,
+    source_file: &SourceFile,
+    options: &ReadOptions,
+) -> Result<Vec<Sentence>, YomineError> {
+    static DRAWING: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\\p[1-9]").expect("Failed to compile ass drawing-mode regex")
+    });
+
+    let ssa = parse_ssa_script(raw)?;
+    let mut events: Vec<_> = ssa
+        .events
+        .iter()
+        .filter(|event| matches!(event.line_type, SSAEventLineType::Dialogue))
+        .filter(|event| !is_excluded_style(&event.style, &event.name, source_file, options))
+        .filter(|event| !DRAWING.is_match(&event.text))
+        .collect();
+    events.sort_by_key(|event| event.start);
+
+    sentences_from_voiced_cues(
+        events.into_iter().map(|event| {
+            let text = event.text.replace("\\N", "\n").replace("\\n", "\n").replace("\\h", " ");
+            let actor = event.name.trim();
+            (event.start, event.end, text, (!actor.is_empty()).then(|| actor.to_string()))
+        }),
+        source_file,
+    )
+}
+
+fn read_ssa(source_file: &SourceFile, options: &ReadOptions) -> Result<Vec<Sentence>, YomineError> {
+    let raw_file = read_text(source_file)?;
+
+    parse_ssa(&raw_file, source_file, options)
+}
+
+/// One ASS/SSA style of a file, for the per-file style picker.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SubtitleStyle {
+    pub name: String,
+    /// Dialogue events using the style.
+    pub lines: usize,
+    /// Dropped when the file is read (`SourceFile.excluded_styles`, else the globs).
+    pub excluded: bool,
+}
+
+/// The styles of an ASS/SSA file or MKV ASS track, declared ones first, then any
+/// only events name. Empty for other formats.
+pub fn subtitle_styles(
+    source_file: &SourceFile,
+    options: &ReadOptions,
+) -> Result<Vec<SubtitleStyle>, YomineError> {
+    let raw = match source_file.file_type {
+        SourceFileType::SSA => read_text(source_file)?,
+        SourceFileType::MKV => match mkv_track_text(source_file)? {
+            TrackText::Ssa(script) => script,
+            TrackText::Cues(_) => return Ok(Vec::new()),
+        },
+        _ => return Ok(Vec::new()),
+    };
+    let ssa = parse_ssa_script(&raw)?;
+
+    let mut styles: Vec<SubtitleStyle> = Vec::new();
+    let declared = ssa.styles.iter().map(|style| style.name.as_str());
+    let used = ssa.events.iter().map(|event| event.style.as_str());
+    for name in declared.chain(used) {
+        if !styles.iter().any(|style| style.name == name) {
+            styles.push(SubtitleStyle {
+                name: name.to_string(),
+                lines: 0,
+                excluded: is_excluded_style(name, "", source_file, options),
+            });
+        }
+    }
+    for event in ssa.events.iter().filter(|e| matches!(e.line_type, SSAEventLineType::Dialogue)) {
+        if let Some(style) = styles.iter_mut().find(|style| style.name == event.style) {
+            style.lines += 1;
+        }
+    }
+    Ok(styles)
+}
+
+/// `hh:mm:ss.ttt` or `mm:ss.ttt`; hours wrap at 24h like the other subtitle formats.
+fn parse_vtt_timestamp(stamp: &str) -> Option<Time> {
+    let (clock, millis) = stamp.split_once('.')?;
+    if millis.len() != 3 {
+        return None;
+    }
+    let millis: u16 = millis.parse().ok()?;
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [m, s] => (0, *m, *s),
+        [h, m, s] => (h.parse::<u64>().ok()?, *m, *s),
+        _ => return None,
+    };
+    if minutes.len() != 2 || seconds.len() != 2 {
+        return None;
+    }
+
+    Time::from_hms_milli((hours % 24) as u8, minutes.parse().ok()?, seconds.parse().ok()?, millis)
+        .ok()
+}
+
+/// Raw `(start, end, text)` cues from a WebVTT document. Header metadata,
+/// `NOTE`/`STYLE`/`REGION` blocks, cue identifiers and cue settings are dropped;
+/// a malformed cue is skipped rather than failing the whole file.
+fn parse_vtt(raw: &str) -> Result<Vec<(Time, Time, String)>, YomineError> {
+    // Karaoke-style inline timestamps (<00:01:02.500>) aren't tags the cleanup knows.
+    static VTT_TIMESTAMP_TAGS: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"<\d+(?::\d{2}){1,2}\.\d{3}>")
+            .expect("Failed to compile vtt timestamp-tag regex")
+    });
+
+    let raw = raw.replace("\r\n", "\n").replace('\r', "\n");
+    let mut lines = raw.lines();
+
+    let header = lines.next().unwrap_or_default();
+    if header != "WEBVTT" && !header.starts_with("WEBVTT ") && !header.starts_with("WEBVTT\t") {
+        return Err(YomineError::Custom(
+            "Error Parsing VTT File: missing WEBVTT header".to_string(),
+        ));
+    }
+
+    // Blocks are separated by blank lines; header metadata lands in the first one
+    // and falls out below for lacking a timing line.
+    let mut blocks: Vec<Vec<&str>> = Vec::new();
+    let mut current = Vec::new();
+    for line in lines {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    let cues = blocks
+        .into_iter()
+        .filter_map(|block| {
+            let keyword = block[0].split_whitespace().next().unwrap_or_default();
+            if matches!(keyword, "NOTE" | "STYLE" | "REGION") && !block[0].contains("-->") {
+                return None;
+            }
+
+            // The timing line is first, or second after a cue identifier.
+            let timing = block.iter().take(2).position(|line| line.contains("-->"))?;
+            let (start, rest) = block[timing].split_once("-->")?;
+            // Anything after the end time is cue settings (position, line, align...).
+            let end = rest.split_whitespace().next()?;
+            let start = parse_vtt_timestamp(start.trim())?;
+            let end = parse_vtt_timestamp(end)?;
+
+            let text = block[timing + 1..].join("\n");
+            Some((start, end, VTT_TIMESTAMP_TAGS.replace_all(&text, "").into_owned()))
+        })
+        .collect();
+
+    Ok(cues)
+}
+
+pub fn read_vtt(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
+    let raw_file = read_text(source_file)?;
+
+    let cues = parse_vtt(&raw_file)?;
+
+    sentences_from_cues(cues, source_file)
+}
+
+fn read_ttml(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
+    let raw_file = read_text(source_file)?;
+
+    let cues = crate::ttml::parse_cues(&raw_file)?;
+
+    sentences_from_cues(cues, source_file)
 }
 
 fn sentences_from_lines<'a>(
     lines: impl Iterator<Item = &'a str>,
     source_file: &SourceFile,
 ) -> Vec<Sentence> {
-    static SENTENCE_SPLIT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"([。！？｡!?.]+)").expect("Failed to compile sentence split regex")
-    });
-
     let mut sentences = Vec::new();
     let mut sentence_id = 0;
 
@@ -180,7 +678,11 @@ fn sentences_from_lines<'a>(
                     segments: vec![],
                     text,
                     timestamp: None,
+                    page: None,
                     comprehension: 0.0,
+                    ruby: Vec::new(),
+                    translation: None,
+                    speaker: None,
                 });
                 sentence_id += 1;
             }
@@ -190,11 +692,84 @@ fn sentences_from_lines<'a>(
     sentences
 }
 
+/// Aozora Bunko lines → sentences with the ruby lifted out as reading hints.
+/// Split like `sentences_from_lines`, but offset-tracked so each hint lands on
+/// its sentence; the markup strip replaces the HTML/paren cleanup.
+fn sentences_from_aozora<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    source_file: &SourceFile,
+) -> Vec<Sentence> {
+    let mut sentences = Vec::new();
+
+    for line in lines {
+        let (line, ruby) = crate::aozora::strip_markup(line);
+
+        let mut bounds = Vec::new();
+        let mut last = 0;
+        for delimiter in SENTENCE_SPLIT_REGEX.find_iter(&line) {
+            bounds.push(last..delimiter.start());
+            last = delimiter.end();
+        }
+        bounds.push(last..line.len());
+
+        for range in bounds {
+            let part = &line[range.clone()];
+            let text = part.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let start = range.start + (part.len() - part.trim_start().len());
+            let end = start + text.len();
+
+            sentences.push(Sentence {
+                id: sentences.len(),
+                source_id: source_file.id,
+                segments: vec![],
+                text: text.to_string(),
+                timestamp: None,
+                page: None,
+                comprehension: 0.0,
+                ruby: ruby
+                    .iter()
+                    .filter(|hint| hint.start >= start && hint.end <= end)
+                    .map(|hint| RubyHint {
+                        start: hint.start - start,
+                        end: hint.end - start,
+                        reading: hint.reading.clone(),
+                    })
+                    .collect(),
+                translation: None,
+                speaker: None,
+            });
+        }
+    }
+
+    sentences
+}
+
+pub fn detect_text_markup(text: &str) -> TextMarkup {
+    if crate::aozora::is_aozora(text) {
+        TextMarkup::Aozora
+    } else {
+        TextMarkup::Plain
+    }
+}
+
+/// Sniff a plain-text source's markup (in its recorded encoding).
+pub fn detect_file_markup(source_file: &SourceFile) -> Result<TextMarkup, YomineError> {
+    Ok(detect_text_markup(&read_text(source_file)?))
+}
+
 pub fn read_txt(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
-    let raw_text = fs::read_to_string(&source_file.original_file)?;
-    let raw_text = raw_text.trim_start_matches('\u{feff}');
+    let raw_text = read_text(source_file)?;
 
-    let sentences = sentences_from_lines(raw_text.lines(), source_file);
+    let markup = source_file.text_markup.unwrap_or_else(|| detect_text_markup(&raw_text));
+    let sentences = match markup {
+        TextMarkup::Plain => sentences_from_lines(raw_text.lines(), source_file),
+        TextMarkup::Aozora => {
+            sentences_from_aozora(crate::aozora::body_lines(&raw_text).into_iter(), source_file)
+        }
+    };
 
     if sentences.is_empty() {
         return Err(YomineError::Custom("No text found in the file.".to_string()));
@@ -218,24 +793,150 @@ pub fn read_epub(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError>
     Ok(sentences)
 }
 
+/// An embedded subtitle track (`mkv_track`, else the preferred Japanese text
+/// track), parsed like the standalone `.ass`/`.srt` it was muxed from.
+pub fn read_mkv(
+    source_file: &SourceFile,
+    options: &ReadOptions,
+) -> Result<Vec<Sentence>, YomineError> {
+    match mkv_track_text(source_file)? {
+        TrackText::Ssa(script) => parse_ssa(&script, source_file, options),
+        TrackText::Cues(cues) => sentences_from_cues(cues, source_file),
+    }
+}
+
+fn mkv_track_text(source_file: &SourceFile) -> Result<TrackText, YomineError> {
+    let path = &source_file.original_file;
+    let number = match source_file.mkv_track {
+        Some(number) => number,
+        None => {
+            let tracks = crate::mkv::subtitle_tracks(path)?;
+            crate::mkv::preferred_track(&tracks).map(|track| track.number).ok_or_else(|| {
+                YomineError::Custom("No text subtitle tracks found in the MKV file.".to_string())
+            })?
+        }
+    };
+
+    crate::mkv::read_track(path, number)
+}
+
+/// One sentence per mokuro text block, located by page + block instead of time.
+pub fn read_mokuro(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
+    let blocks = crate::mokuro::text_blocks(&source_file.original_file)?;
+
+    let sentences: Vec<Sentence> = blocks
+        .into_iter()
+        .enumerate()
+        .map(|(id, block)| Sentence {
+            id,
+            source_id: source_file.id,
+            segments: vec![],
+            text: block.text,
+            timestamp: None,
+            page: Some(PageLocation { page: block.page, block: block.block }),
+            comprehension: 0.0,
+            ruby: Vec::new(),
+            translation: None,
+            speaker: None,
+        })
+        .collect();
+
+    if sentences.is_empty() {
+        return Err(YomineError::Custom("No text found in the file.".to_string()));
+    }
+
+    Ok(sentences)
+}
+
+/// ASS/SSA styles dropped unless the settings say otherwise: signs, songs,
+/// OP/ED and karaoke carry no dialogue.
+pub const DEFAULT_EXCLUDED_STYLES: &[&str] = &["*sign*", "*OP*", "*ED*", "*song*", "*karaoke*"];
+
+/// Parse-time options that aren't recorded on the `SourceFile`.
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    /// Merge adjacent subtitle cues that split one sentence (`join_split_cues`).
+    pub join_split_cues: bool,
+    /// ASS/SSA style or actor globs (`style_pattern_matches`) whose events are
+    /// dropped — signs, karaoke, OP/ED. `SourceFile.excluded_styles` overrides them.
+    pub excluded_styles: Vec<String>,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            join_split_cues: false,
+            excluded_styles: DEFAULT_EXCLUDED_STYLES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
 pub fn read(source_file: &SourceFile) -> Result<Vec<Sentence>, YomineError> {
+    read_with(source_file, &ReadOptions::default())
+}
+
+pub fn read_with(
+    source_file: &SourceFile,
+    options: &ReadOptions,
+) -> Result<Vec<Sentence>, YomineError> {
+    let mut sentences = read_primary(source_file, options)?;
+    if options.join_split_cues {
+        sentences = join_split_cues(sentences);
+    }
+    // The translation track is optional: a bad one leaves the primary untranslated.
+    if let Some(path) = &source_file.secondary_subtitle {
+        match read_secondary(path, options) {
+            Ok(secondary) => align_translations(&mut sentences, &secondary),
+            Err(e) => eprintln!("Skipping translation subtitles {}: {}", path, e),
+        }
+    }
+    Ok(sentences)
+}
+
+fn read_primary(
+    source_file: &SourceFile,
+    options: &ReadOptions,
+) -> Result<Vec<Sentence>, YomineError> {
     match source_file.file_type {
         SourceFileType::SRT => read_srt(source_file),
-        SourceFileType::SSA => read_ssa(source_file),
+        SourceFileType::SSA => read_ssa(source_file, options),
+        SourceFileType::VTT => read_vtt(source_file),
+        SourceFileType::TTML => read_ttml(source_file),
         SourceFileType::TXT => read_txt(source_file),
         SourceFileType::EPUB => read_epub(source_file),
+        SourceFileType::Mokuro => read_mokuro(source_file),
+        SourceFileType::MKV => read_mkv(source_file, options),
         SourceFileType::Other(ref format) => Err(YomineError::UnsupportedFileType(format.clone())),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use encoding_rs::{
+        EUC_JP,
+        SHIFT_JIS,
+    };
+
     use super::{
+        align_translations,
         clean_subtitle_text,
+        decode_text,
+        detect_encoding,
+        detect_file_encoding,
+        join_split_cues,
+        parse_ssa,
+        parse_vtt,
         read_txt,
+        sentences_from_aozora,
+        sentences_from_cues,
         sentences_from_lines,
+        style_pattern_matches,
+        ReadOptions,
+    };
+    use crate::core::{
+        models::TextEncoding,
+        SourceFile,
     };
-    use crate::core::SourceFile;
 
     #[test]
     fn sentences_from_lines_matches_read_txt() {
@@ -298,4 +999,229 @@ mod tests {
         assert_eq!(clean_subtitle_text("パンケーキ&エッグ"), "パンケーキ&エッグ");
         assert_eq!(clean_subtitle_text("値段は3&lt;5"), "値段は3<5");
     }
+
+    #[test]
+    fn strips_ruby_annotations_with_their_readings() {
+        assert_eq!(clean_subtitle_text("<ruby>漢字<rt>かんじ</rt></ruby>を読む"), "漢字を読む");
+        assert_eq!(
+            clean_subtitle_text("<ruby>明日<rp>(</rp><rt>あした</rt><rp>)</rp></ruby>"),
+            "明日"
+        );
+        // WebVTT may leave </rt> implied by </ruby>.
+        assert_eq!(clean_subtitle_text("<ruby>今日<rt>きょう</ruby>は"), "今日は");
+    }
+
+    #[test]
+    fn parses_vtt_cues_skipping_metadata_blocks() {
+        let vtt = "WEBVTT - ripped\r\nKind: captions\r\nLanguage: ja\r\n\r\n\
+            STYLE\r\n::cue(.japanese) { color: white }\r\n\r\n\
+            NOTE this block\r\nspans lines --> and is ignored\r\n\r\n\
+            1\r\n00:00:01.000 --> 00:00:02.500 position:10% align:start\r\n<c.japanese>こんにちは</c>\r\n\r\n\
+            intro-2\r\n00:03.000 --> 00:04.000\r\n<ruby>世界<rt>せかい</rt></ruby>へ\r\nようこそ\r\n\r\n\
+            01:00:05.000 --> 01:00:06.000\r\n<00:00:05.000>一<00:00:05.500>二\r\n\r\n\
+            00:00:07.000 --> bogus\r\n壊れた\r\n";
+
+        let cues = parse_vtt(vtt).unwrap();
+        assert_eq!(cues.len(), 3);
+
+        let source_file = SourceFile::default();
+        let sentences = sentences_from_cues(cues, &source_file).unwrap();
+        let texts: Vec<&str> = sentences.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["こんにちは", "世界へ ようこそ", "一二"]);
+
+        let first = sentences[0].timestamp.as_ref().unwrap();
+        assert_eq!((first.to_secs().0, first.to_secs().1), (1.0, 2.5));
+        let second = sentences[1].timestamp.as_ref().unwrap();
+        assert_eq!(second.to_secs().0, 3.0);
+        let third = sentences[2].timestamp.as_ref().unwrap();
+        assert_eq!(third.to_secs().0, 3605.0);
+    }
+
+    #[test]
+    fn rejects_vtt_without_header() {
+        assert!(parse_vtt("1\n00:00:01.000 --> 00:00:02.000\nテスト\n").is_err());
+    }
+
+    const LEGACY_SAMPLE: &str =
+        "1\n00:00:01,000 --> 00:00:02,000\n今日はいい天気ですね。\nカタカナと漢字も読める？\n";
+
+    #[test]
+    fn detects_legacy_japanese_encodings() {
+        let sjis = SHIFT_JIS.encode(LEGACY_SAMPLE).0;
+        assert_eq!(detect_encoding(&sjis), TextEncoding::ShiftJis);
+        assert_eq!(decode_text(&sjis, TextEncoding::ShiftJis), LEGACY_SAMPLE);
+
+        let euc = EUC_JP.encode(LEGACY_SAMPLE).0;
+        assert_eq!(detect_encoding(&euc), TextEncoding::EucJp);
+        assert_eq!(decode_text(&euc, TextEncoding::EucJp), LEGACY_SAMPLE);
+    }
+
+    #[test]
+    fn detects_utf16_with_and_without_bom() {
+        let le: Vec<u8> = LEGACY_SAMPLE.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let be: Vec<u8> = LEGACY_SAMPLE.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(detect_encoding(&le), TextEncoding::Utf16Le);
+        assert_eq!(detect_encoding(&be), TextEncoding::Utf16Be);
+
+        let with_bom = [&[0xFF, 0xFE][..], &le].concat();
+        assert_eq!(detect_encoding(&with_bom), TextEncoding::Utf16Le);
+        assert_eq!(decode_text(&with_bom, TextEncoding::Utf16Le), LEGACY_SAMPLE);
+    }
+
+    #[test]
+    fn utf8_bom_is_detected_and_stripped() {
+        let bytes = [&[0xEF, 0xBB, 0xBF][..], LEGACY_SAMPLE.as_bytes()].concat();
+        assert_eq!(detect_encoding(&bytes), TextEncoding::Utf8);
+        assert_eq!(decode_text(&bytes, TextEncoding::Utf8), LEGACY_SAMPLE);
+    }
+
+    #[test]
+    fn read_txt_decodes_shift_jis_and_honours_an_override() {
+        let path = std::env::temp_dir().join(format!("yomine_sjis_{}.txt", std::process::id()));
+        std::fs::write(&path, SHIFT_JIS.encode("吾輩は猫である。名前はまだ無い。").0).unwrap();
+        let mut source_file =
+            SourceFile { original_file: path.display().to_string(), ..Default::default() };
+
+        let detected = read_txt(&source_file).unwrap();
+        source_file.encoding = Some(TextEncoding::EucJp);
+        let forced = read_txt(&source_file).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let texts: Vec<&str> = detected.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["吾輩は猫である", "名前はまだ無い"]);
+        assert_ne!(forced[0].text, detected[0].text);
+    }
+
+    #[test]
+    fn file_encoding_is_sniffed_from_the_head_only() {
+        // 17-byte lines: the 64 KiB cut lands inside a two-byte character.
+        let text = "吾輩は猫である。\n".repeat(5_000);
+        let path = std::env::temp_dir().join(format!("yomine_head_{}.txt", std::process::id()));
+        std::fs::write(&path, SHIFT_JIS.encode(&text).0).unwrap();
+        let detected = detect_file_encoding(&path.display().to_string());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(detected.unwrap(), TextEncoding::ShiftJis);
+    }
+
+    #[test]
+    fn aozora_ruby_follows_its_sentence() {
+        let source_file = SourceFile::default();
+        let sentences = sentences_from_aozora(
+            ["　吾輩《わがはい》は猫である。名前はまだ無《な》い。"].into_iter(),
+            &source_file,
+        );
+
+        let texts: Vec<&str> = sentences.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["吾輩は猫である", "名前はまだ無い"]);
+        let hint = &sentences[1].ruby[0];
+        assert_eq!((&sentences[1].text[hint.start..hint.end], hint.reading.as_str()), ("無", "な"));
+        assert_eq!(
+            &sentences[0].text[sentences[0].ruby[0].start..sentences[0].ruby[0].end],
+            "吾輩"
+        );
+    }
+
+    #[test]
+    fn aligns_translation_cues_by_overlap() {
+        let source_file = SourceFile::default();
+        let japanese = "WEBVTT\n\n00:01.000 --> 00:03.000\nおはよう\n\n\
+            00:03.000 --> 00:04.000\nえっ\n\n00:04.000 --> 00:05.000\nまた？\n\n\
+            00:10.000 --> 00:12.000\n静かだ\n";
+        let english = "WEBVTT\n\n00:00.900 --> 00:02.000\nGood\n\n\
+            00:02.000 --> 00:03.100\nmorning.\n\n00:03.200 --> 00:05.000\nHuh? Again?\n";
+        let mut sentences =
+            sentences_from_cues(parse_vtt(japanese).unwrap(), &source_file).unwrap();
+        let secondary = sentences_from_cues(parse_vtt(english).unwrap(), &source_file).unwrap();
+
+        align_translations(&mut sentences, &secondary);
+
+        let translations: Vec<Option<&str>> =
+            sentences.iter().map(|s| s.translation.as_deref()).collect();
+        assert_eq!(
+            translations,
+            vec![Some("Good morning."), Some("Huh? Again?"), Some("Huh? Again?"), None]
+        );
+    }
+
+    #[test]
+    fn joins_cues_that_split_a_sentence() {
+        let vtt = "WEBVTT\n\n00:01.000 --> 00:02.000\nだから、\n\n\
+            00:02.200 --> 00:04.000\n行かないって言ったのに\n\n\
+            00:04.100 --> 00:05.000\n- ごめん\n\n00:08.000 --> 00:09.000\nもう\n\n\
+            00:09.100 --> 00:10.000\nいいよ。\n\n00:10.100 --> 00:11.000\n本当に。\n";
+        let sentences = sentences_from_cues(parse_vtt(vtt).unwrap(), &SourceFile::default());
+
+        let joined = join_split_cues(sentences.unwrap());
+
+        let texts: Vec<&str> = joined.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["だから、行かないって言ったのに", "- ごめん", "もういいよ。", "本当に。"]
+        );
+        let first = joined[0].timestamp.as_ref().unwrap().to_secs();
+        assert_eq!(first, (1.0, 4.0));
+        assert_eq!(joined.iter().map(|s| s.id).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn unpunctuated_files_only_join_after_a_comma() {
+        let vtt = "WEBVTT\n\n00:01.000 --> 00:02.000\nそうか\n\n\
+            00:02.100 --> 00:03.000\nじゃあ、\n\n00:03.100 --> 00:04.000\n行こう\n";
+        let sentences = sentences_from_cues(parse_vtt(vtt).unwrap(), &SourceFile::default());
+
+        let joined = join_split_cues(sentences.unwrap());
+
+        let texts: Vec<&str> = joined.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["そうか", "じゃあ、行こう"]);
+    }
+
+    #[test]
+    fn style_patterns_are_smart_case_globs() {
+        assert!(style_pattern_matches("*sign*", "Sign_Big"));
+        assert!(style_pattern_matches("*OP*", "OP-JP"));
+        assert!(!style_pattern_matches("*OP*", "Top"));
+        assert!(style_pattern_matches("ED*", "ED Romaji"));
+        assert!(!style_pattern_matches("ED*", "Default"));
+        assert!(style_pattern_matches("default", "Default"));
+    }
+
+    const ASS: &str = "[Script Info]\nScriptType: v4.00+\n\n[V4+ Styles]\n\
+        Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+        Style: Default,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,2,10,10,10,1\n\
+        Style: Sign,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,8,10,10,10,1\n\
+        Style: OP-JP,Arial,20,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,2,2,8,10,10,10,1\n\n\
+        [Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+        Dialogue: 0,0:00:05.00,0:00:07.00,Default,ハルヒ,0,0,0,,遅い！\\N罰金！\n\
+        Dialogue: 0,0:00:01.00,0:00:03.00,OP-JP,,0,0,0,,{\\k20}ハレ晴レ\n\
+        Dialogue: 0,0:00:02.00,0:00:04.00,Sign,,0,0,0,,北高\n\
+        Comment: 0,0:00:02.00,0:00:04.00,Default,,0,0,0,,没\n\
+        Dialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,{\\p1}m 0 0 l 10 0{\\p0}\n\
+        Dialogue: 0,0:00:04.00,0:00:05.00,Default,キョン,0,0,0,,やれやれ\n";
+
+    #[test]
+    fn default_options_drop_the_default_styles() {
+        let sentences = parse_ssa(ASS, &SourceFile::default(), &ReadOptions::default()).unwrap();
+        let texts: Vec<&str> = sentences.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["やれやれ", "遅い！ 罰金！"]);
+    }
+
+    #[test]
+    fn ssa_drops_excluded_styles_and_keeps_speakers() {
+        let options = ReadOptions {
+            excluded_styles: vec!["*sign*".to_string(), "*OP*".to_string()],
+            ..Default::default()
+        };
+        let sentences = parse_ssa(ASS, &SourceFile::default(), &options).unwrap();
+
+        let lines: Vec<(&str, Option<&str>)> =
+            sentences.iter().map(|s| (s.text.as_str(), s.speaker.as_deref())).collect();
+        assert_eq!(lines, vec![("やれやれ", Some("キョン")), ("遅い！ 罰金！", Some("ハルヒ"))]);
+
+        // A per-file pick replaces the globs outright.
+        let source_file =
+            SourceFile { excluded_styles: Some(vec!["Sign".to_string()]), ..Default::default() };
+        let sentences = parse_ssa(ASS, &source_file, &options).unwrap();
+        let texts: Vec<&str> = sentences.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["ハレ晴レ", "やれやれ", "遅い！ 罰金！"]);
+    }
 }
@@ -0,0 +1,260 @@
+//! On-disk cache of tokenized files: the segmented `Sentence`s and unfiltered,
+//! deduplicated `Term`s of a parse, so reopening a file only re-runs the
+//! ignore/Anki filters and comprehension. Entries are keyed by everything the
+//! tokenized output depends on — file content, parse options, tokenizer
+//! dictionary, frequency dictionaries and text filters — so any change is a
+//! miss rather than a stale hit.
+
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        Read,
+        Seek,
+        SeekFrom,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::get_app_data_dir;
+use crate::{
+    core::{
+        text_filter::CompiledFilter,
+        LanguageTools,
+        Sentence,
+        SourceFile,
+        Term,
+    },
+    dictionary::token_dictionary::DictType,
+    parser::ReadOptions,
+};
+
+/// Bump when the cached layout or the meaning of a cached field changes.
+const CACHE_FORMAT: u32 = 1;
+/// Newest entries kept; older ones are pruned on store.
+const MAX_ENTRIES: usize = 40;
+/// Files up to this size are hashed whole; past it (MKV video), the head,
+/// the tail and the length stand in for the content.
+const FULL_HASH_LIMIT: u64 = 64 * 1024 * 1024;
+const PARTIAL_HASH_CHUNK: u64 = 4 * 1024 * 1024;
+
+fn cache_dir() -> PathBuf {
+    get_app_data_dir().join("cache").join("files")
+}
+
+/// 64-bit FNV-1a: stable across builds and platforms, unlike `DefaultHasher`.
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+fn hash_str(text: &str) -> u64 {
+    let mut hasher = Fnv::new();
+    hasher.write(text.as_bytes());
+    hasher.0
+}
+
+/// Content fingerprint of a file; `None` for folders (mokuro `_ocr` pages)
+/// and unreadable paths, which are never cached.
+fn file_fingerprint(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let metadata = file.metadata().ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let len = metadata.len();
+    let mut hasher = Fnv::new();
+    if len <= FULL_HASH_LIMIT {
+        let mut bytes = Vec::with_capacity(len as usize);
+        file.read_to_end(&mut bytes).ok()?;
+        hasher.write(&bytes);
+    } else {
+        let mut chunk = vec![0; PARTIAL_HASH_CHUNK as usize];
+        file.read_exact(&mut chunk).ok()?;
+        hasher.write(&chunk);
+        file.seek(SeekFrom::End(-(PARTIAL_HASH_CHUNK as i64))).ok()?;
+        file.read_exact(&mut chunk).ok()?;
+        hasher.write(&chunk);
+    }
+    Some(format!("{:016x}:{len}", hasher.0))
+}
+
+/// The full cache key for parsing `source_file` with these tools and filters,
+/// or `None` when the source can't be fingerprinted.
+pub fn cache_key(
+    source_file: &SourceFile,
+    read_options: &ReadOptions,
+    language_tools: &LanguageTools,
+    text_filters: &[CompiledFilter],
+) -> Option<String> {
+    let content = file_fingerprint(Path::new(&source_file.original_file))?;
+    let secondary = match &source_file.secondary_subtitle {
+        Some(path) => file_fingerprint(Path::new(path))?,
+        None => String::new(),
+    };
+    let parse = serde_json::to_string(&(
+        &source_file.file_type,
+        &source_file.epub_chapters,
+        &source_file.encoding,
+        &source_file.text_markup,
+        source_file.mkv_track,
+        &source_file.excluded_styles,
+        read_options.join_split_cues,
+        &read_options.excluded_styles,
+    ))
+    .ok()?;
+
+    // Weights and toggles change the harmonic ranks, and with them deinflection
+    // and phrase promotion, so they key the entry along with the revisions.
+    let manager = &language_tools.frequency_manager;
+    let revisions = manager.dictionary_revisions();
+    let mut dictionaries: Vec<String> = manager
+        .dictionary_states()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, state)| {
+            let revision = revisions.get(&name).map(String::as_str).unwrap_or_default();
+            format!("{name}@{revision}:{}:{}", state.weight, state.enabled)
+        })
+        .collect();
+    dictionaries.sort();
+
+    let filters: Vec<String> = text_filters.iter().map(CompiledFilter::fingerprint).collect();
+
+    Some(format!(
+        "{CACHE_FORMAT}|{}|{}|{content}|{secondary}|{parse}|{}|{}",
+        env!("CARGO_PKG_VERSION"),
+        DictType::Unidic.revision(),
+        dictionaries.join(","),
+        filters.join("\u{1f}")
+    ))
+}
+
+fn entry_path(key: &str) -> PathBuf {
+    cache_dir().join(format!("{:016x}.bin", hash_str(key)))
+}
+
+#[derive(Serialize)]
+struct CachedFileRef<'a> {
+    key: &'a str,
+    sentences: &'a [Sentence],
+    terms: &'a [Term],
+}
+
+#[derive(Deserialize)]
+struct CachedFile {
+    key: String,
+    sentences: Vec<Sentence>,
+    terms: Vec<Term>,
+}
+
+/// The cached sentences and terms for `key`, if present and intact.
+pub fn load(key: &str) -> Option<(Vec<Sentence>, Vec<Term>)> {
+    let bytes = fs::read(entry_path(key)).ok()?;
+    let (cached, _): (CachedFile, usize) =
+        bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).ok()?;
+    // The file name is only a hash of the key; the stored key settles collisions.
+    (cached.key == key).then_some((cached.sentences, cached.terms))
+}
+
+/// Best-effort write; a failed write only costs the next open its speed-up.
+pub fn store(key: &str, sentences: &[Sentence], terms: &[Term]) {
+    let entry = CachedFileRef { key, sentences, terms };
+    let result = fs::create_dir_all(cache_dir())
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            bincode::serde::encode_to_vec(&entry, bincode::config::standard())
+                .map_err(|e| e.to_string())
+        })
+        .and_then(|bytes| fs::write(entry_path(key), bytes).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => prune(),
+        Err(e) => eprintln!("Failed to write file cache: {}", e),
+    }
+}
+
+/// Drop all but the `MAX_ENTRIES` most recently written entries.
+fn prune() {
+    let Ok(entries) = fs::read_dir(cache_dir()) else { return };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    if files.len() <= MAX_ENTRIES {
+        return;
+    }
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, path) in files.into_iter().skip(MAX_ENTRIES) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Time;
+
+    use super::*;
+    use crate::core::models::{
+        RubyHint,
+        TimeStamp,
+    };
+
+    #[test]
+    fn sentences_round_trip_through_bincode() {
+        let sentence = Sentence {
+            id: 3,
+            source_id: 1,
+            text: "猫が寝た".to_string(),
+            segments: vec![("ねこ".to_string(), crate::segmentation::word::POS::Noun, 0, 3)],
+            timestamp: Some(TimeStamp {
+                start: Time::from_hms_milli(1, 2, 3, 456).unwrap(),
+                end: Time::from_hms_nano(1, 2, 5, 7).unwrap(),
+            }),
+            page: None,
+            comprehension: 0.0,
+            ruby: vec![RubyHint { start: 0, end: 3, reading: "ねこ".to_string() }],
+            translation: Some("The cat slept".to_string()),
+            speaker: None,
+        };
+        let entry =
+            CachedFileRef { key: "k", sentences: std::slice::from_ref(&sentence), terms: &[] };
+        let bytes = bincode::serde::encode_to_vec(&entry, bincode::config::standard()).unwrap();
+        let (cached, _): (CachedFile, usize) =
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).unwrap();
+
+        assert_eq!(cached.key, "k");
+        let back = &cached.sentences[0];
+        assert_eq!(back.text, sentence.text);
+        assert_eq!(back.segments, sentence.segments);
+        assert_eq!(back.ruby, sentence.ruby);
+        assert_eq!(back.translation, sentence.translation);
+        let (got, want) = (back.timestamp.as_ref().unwrap(), sentence.timestamp.as_ref().unwrap());
+        assert_eq!((got.start, got.end), (want.start, want.end));
+    }
+
+    #[test]
+    fn fnv_is_stable() {
+        assert_eq!(hash_str(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash_str("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod file_cache;
+
 use std::{
     fs,
     path::PathBuf,
@@ -33,7 +35,7 @@ pub fn save_json<T: Serialize>(data: &T, filename: &str) -> Result<(), Box<dyn s
     let file_path = get_data_file_path(filename);
     let json = serde_json::to_string_pretty(data)?;
     fs::write(&file_path, json)?;
-    println!("Data saved to: {}", file_path.display());
+    eprintln!("Data saved to: {}", file_path.display());
     Ok(())
 }
 
@@ -48,7 +50,7 @@ pub fn load_json<T: for<'de> Deserialize<'de> + Default>(
 
     let json = fs::read_to_string(&file_path)?;
     let data: T = serde_json::from_str(&json)?;
-    println!("Data loaded from: {}", file_path.display());
+    eprintln!("Data loaded from: {}", file_path.display());
     Ok(data)
 }
 
@@ -66,7 +68,7 @@ pub fn delete_data_file(filename: &str) -> Result<(), Box<dyn std::error::Error>
     let file_path = get_data_file_path(filename);
     if file_path.exists() {
         fs::remove_file(&file_path)?;
-        println!("Deleted: {}", file_path.display());
+        eprintln!("Deleted: {}", file_path.display());
     }
     Ok(())
 }
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
 
 use vibrato::{
     tokenizer::worker::Worker,
@@ -18,6 +21,7 @@ use super::{
 };
 use crate::{
     core::{
+        models::RubyHint,
         utils::{
             is_kanji_char,
             normalize_reading,
@@ -37,271 +41,294 @@ use crate::{
     },
 };
 
+/// Serial term extraction with a single `Worker`: sentence by sentence, then
+/// folded into one term list. [`extract_words_parallel`] is the same over rayon.
 pub fn extract_words(
     mut worker: Worker,
     sentences: &mut [Sentence],
     frequency_manager: &FrequencyManager,
 ) -> Vec<Term> {
-    let mut terms = Vec::<Term>::new();
+    let per_sentence = sentences
+        .iter_mut()
+        .enumerate()
+        .map(|(ord, sentence)| sentence_terms(&mut worker, ord, sentence, frequency_manager))
+        .collect();
+    merge_sentence_terms(per_sentence)
+}
+
+/// [`extract_words`] with the sentences split across rayon threads, one
+/// `Worker` per thread. Per-sentence results are merged in sentence order, so
+/// the output is identical to the serial path.
+pub fn extract_words_parallel(
+    tokenizer: &Tokenizer,
+    sentences: &mut [Sentence],
+    frequency_manager: &FrequencyManager,
+) -> Vec<Term> {
+    use rayon::prelude::*;
+
+    let per_sentence = sentences
+        .par_iter_mut()
+        .enumerate()
+        .map_init(
+            || tokenizer.new_worker(),
+            |worker, (ord, sentence)| sentence_terms(worker, ord, sentence, frequency_manager),
+        )
+        .collect();
+    merge_sentence_terms(per_sentence)
+}
 
-    for (ord, sentence) in sentences.iter_mut().enumerate() {
-        worker.reset_sentence(&sentence.text);
-        worker.tokenize();
+/// Tokenize, rescue, deinflect and phrase-promote one sentence (the `ord`-th),
+/// filling its `segments` and returning its terms in sentence order.
+fn sentence_terms(
+    worker: &mut Worker,
+    ord: usize,
+    sentence: &mut Sentence,
+    frequency_manager: &FrequencyManager,
+) -> Vec<Term> {
+    worker.reset_sentence(&sentence.text);
+    worker.tokenize();
 
-        let tokens: Vec<UnidicToken> = worker
-            .token_iter()
-            .map(|token| {
-                UnidicToken::from_parts(token.surface(), token.feature(), token.range_byte())
-            })
-            .collect();
+    let tokens: Vec<UnidicToken> = worker
+        .token_iter()
+        .map(|token| UnidicToken::from_parts(token.surface(), token.feature(), token.range_byte()))
+        .collect();
 
-        let words: Vec<Word> = match parse_into_words(tokens) {
-            Ok(parsed_words) => parsed_words,
-            Err(_) => Vec::new(),
-        };
-        let words = rescue_words(&mut worker, &sentence.text, words, frequency_manager);
-        let words = split_unvalidated_compounds(words, frequency_manager);
-
-        let mut term_spans: Vec<(usize, usize)> = Vec::with_capacity(words.len());
-        let mut sentence_terms: Vec<Term> = Vec::with_capacity(words.len());
-        for word in words {
-            let span = word.byte_span();
-            // The highlight span ends at start + surface_form.len(), so the
-            // reference must point at the main word, not the segment.
-            let ref_start = word.main_word.as_ref().map_or(span.0, |m| m.start_byte);
-            let mut term: Term = word.into();
-            if term.surface_form.as_str().is_japanese() {
-                match term.part_of_speech {
-                    POS::Verb
-                    | POS::SuruVerb
-                    | POS::AdjectivalNoun
-                    | POS::Adjective
-                    | POS::Noun => {
-                        let deinflections: Vec<(String, String)> =
-                            pairwise_deinflection(&term.surface_form, &term.surface_reading);
-
-                        let mut sorted_deinflections: Vec<(String, String)> = deinflections
-                            .into_iter()
-                            .filter(|(word, reading)| {
-                                frequency_manager
-                                    .get_harmonic_frequency_for_pair(word, reading)
-                                    .is_some()
-                            })
-                            .collect();
-
-                        sorted_deinflections.sort_by_key(|(word, reading)| {
-                            frequency_manager.get_harmonic_frequency_for_pair(word, reading)
-                        });
-
-                        if term.part_of_speech == POS::Verb {
-                            retain_verb_final_candidates(
-                                &mut sorted_deinflections,
-                                &term.surface_form,
-                            );
-                        }
+    let words: Vec<Word> = match parse_into_words(tokens) {
+        Ok(parsed_words) => parsed_words,
+        Err(_) => Vec::new(),
+    };
+    let words = rescue_words(worker, &sentence.text, words, frequency_manager);
+    let words = split_unvalidated_compounds(words, frequency_manager);
 
-                        if sorted_deinflections.len() > 0 {
-                            let unidic_lemma =
-                                (term.lemma_form.clone(), term.lemma_reading.clone());
-                            let chosen = sorted_deinflections
-                                .iter()
-                                .find(|candidate| **candidate == unidic_lemma)
-                                .unwrap_or(&sorted_deinflections[0]);
-                            term.lemma_form = chosen.0.clone();
-                            term.lemma_reading = chosen.1.clone();
-                        }
+    let mut term_spans: Vec<(usize, usize)> = Vec::with_capacity(words.len());
+    let mut sentence_terms: Vec<Term> = Vec::with_capacity(words.len());
+    for word in words {
+        let span = word.byte_span();
+        // The highlight span ends at start + surface_form.len(), so the
+        // reference must point at the main word, not the segment.
+        let ref_start = word.main_word.as_ref().map_or(span.0, |m| m.start_byte);
+        let mut term: Term = word.into();
+        if !sentence.ruby.is_empty() {
+            apply_ruby_hints(&mut term, &sentence.text, ref_start, span, &sentence.ruby);
+        }
+        if term.surface_form.as_str().is_japanese() {
+            match term.part_of_speech {
+                POS::Verb | POS::SuruVerb | POS::AdjectivalNoun | POS::Adjective | POS::Noun => {
+                    let deinflections: Vec<(String, String)> =
+                        pairwise_deinflection(&term.surface_form, &term.surface_reading);
+
+                    let mut sorted_deinflections: Vec<(String, String)> = deinflections
+                        .into_iter()
+                        .filter(|(word, reading)| {
+                            frequency_manager
+                                .get_harmonic_frequency_for_pair(word, reading)
+                                .is_some()
+                        })
+                        .collect();
+
+                    sorted_deinflections.sort_by_key(|(word, reading)| {
+                        frequency_manager.get_harmonic_frequency_for_pair(word, reading)
+                    });
+
+                    if term.part_of_speech == POS::Verb {
+                        retain_verb_final_candidates(&mut sorted_deinflections, &term.surface_form);
+                    }
+
+                    if sorted_deinflections.len() > 0 {
+                        let unidic_lemma = (term.lemma_form.clone(), term.lemma_reading.clone());
+                        let chosen = sorted_deinflections
+                            .iter()
+                            .find(|candidate| **candidate == unidic_lemma)
+                            .unwrap_or(&sorted_deinflections[0]);
+                        term.lemma_form = chosen.0.clone();
+                        term.lemma_reading = chosen.1.clone();
                     }
-                    _ => {}
                 }
+                _ => {}
             }
+        }
 
-            let freq_map: HashMap<String, u32> = frequency_manager.build_freq_map(
-                &term.lemma_form,
-                &term.lemma_reading,
-                term.is_kana,
-            );
-            term.frequencies = freq_map;
+        let freq_map: HashMap<String, u32> =
+            frequency_manager.build_freq_map(&term.lemma_form, &term.lemma_reading, term.is_kana);
+        term.frequencies = freq_map;
 
-            term.sentence_references.push((ord, ref_start));
-            term_spans.push(span);
-            sentence_terms.push(term);
-        }
+        term.sentence_references.push((ord, ref_start));
+        term_spans.push(span);
+        sentence_terms.push(term);
+    }
 
-        sentence.segments.extend(sentence_terms.iter().zip(&term_spans).map(
-            |(term, &(start_index, end_index))| {
-                // The span covers the FULL segment, so the reading must too:
-                // `surface_reading` is the main word's alone (勉強します → べんきょう,
-                // 8月 → がつ), which smeared a partial reading across the whole span
-                // in furigana displays (the 8月22日 bug's second half).
-                (
-                    term.full_segment_reading.clone(),
-                    term.part_of_speech.clone(),
-                    start_index,
-                    end_index,
-                )
-            },
-        ));
-
-        let base_len = sentence_terms.len();
-        let mut suppressed = vec![false; base_len];
-        for start in 0..base_len {
-            for end in (start + 1..sentence_terms.len()).rev() {
-                let subrange = &sentence_terms[start..=end];
-                if !phrase_endpoint_ok(&subrange[0]) || !phrase_endpoint_ok(&subrange[end - start])
-                {
-                    continue;
-                }
-                let mut phrase: Term = Term::from_slice(subrange);
-
-                // Try the plain component-concat reading first, then rendaku
-                // variants (a non-initial component's first kana voiced:
-                // 土曜+日 → どようひ, どようび). Dictionaries store the true
-                // compound reading, and the pair lookup deliberately rejects a
-                // form whose entries all carry a *different* reading — so
-                // without the variants, no rendaku compound could ever be
-                // promoted. A variant hit also corrects the phrase's own
-                // reading for display/furigana.
-                let mut freq = None;
-                for candidate in phrase_reading_candidates(subrange) {
-                    if let Some(f) = frequency_manager.get_harmonic_frequency_for_pair(
-                        &phrase.surface_form.normalize_long_vowel(),
-                        &candidate.normalize_long_vowel(),
-                    ) {
-                        phrase.surface_reading = candidate.clone();
-                        phrase.lemma_reading = candidate.clone();
-                        phrase.full_segment_reading = candidate;
-                        freq = Some(f);
-                        break;
-                    }
+    sentence.segments.extend(sentence_terms.iter().zip(&term_spans).map(
+        |(term, &(start_index, end_index))| {
+            // The span covers the FULL segment, so the reading must too:
+            // `surface_reading` is the main word's alone (勉強します → べんきょう,
+            // 8月 → がつ), which smeared a partial reading across the whole span
+            // in furigana displays (the 8月22日 bug's second half).
+            (term.full_segment_reading.clone(), term.part_of_speech.clone(), start_index, end_index)
+        },
+    ));
+
+    let base_len = sentence_terms.len();
+    let mut suppressed = vec![false; base_len];
+    for start in 0..base_len {
+        for end in (start + 1..sentence_terms.len()).rev() {
+            let subrange = &sentence_terms[start..=end];
+            if !phrase_endpoint_ok(&subrange[0]) || !phrase_endpoint_ok(&subrange[end - start]) {
+                continue;
+            }
+            let mut phrase: Term = Term::from_slice(subrange);
+
+            // Try the plain component-concat reading first, then rendaku
+            // variants (a non-initial component's first kana voiced:
+            // 土曜+日 → どようひ, どようび). Dictionaries store the true
+            // compound reading, and the pair lookup deliberately rejects a
+            // form whose entries all carry a *different* reading — so
+            // without the variants, no rendaku compound could ever be
+            // promoted. A variant hit also corrects the phrase's own
+            // reading for display/furigana.
+            let mut freq = None;
+            for candidate in phrase_reading_candidates(subrange) {
+                if let Some(f) = frequency_manager.get_harmonic_frequency_for_pair(
+                    &phrase.surface_form.normalize_long_vowel(),
+                    &candidate.normalize_long_vowel(),
+                ) {
+                    phrase.surface_reading = candidate.clone();
+                    phrase.lemma_reading = candidate.clone();
+                    phrase.full_segment_reading = candidate;
+                    freq = Some(f);
+                    break;
                 }
+            }
 
-                // dto.rs highlights Expressions by full_segment, so only the lemma takes the citation form.
-                if freq.is_none() {
-                    if let Some(citation) = citation_form_subrange(subrange) {
-                        let surface: String =
-                            citation.iter().map(|t| t.full_segment.as_str()).collect();
-                        for candidate in phrase_reading_candidates(&citation) {
-                            if let Some(f) = frequency_manager.get_harmonic_frequency_for_pair(
-                                &surface.normalize_long_vowel(),
-                                &candidate.normalize_long_vowel(),
-                            ) {
-                                phrase.lemma_form = surface.clone();
-                                phrase.lemma_reading = candidate;
-                                freq = Some(f);
-                                break;
-                            }
+            // dto.rs highlights Expressions by full_segment, so only the lemma takes the citation form.
+            if freq.is_none() {
+                if let Some(citation) = citation_form_subrange(subrange) {
+                    let surface: String =
+                        citation.iter().map(|t| t.full_segment.as_str()).collect();
+                    for candidate in phrase_reading_candidates(&citation) {
+                        if let Some(f) = frequency_manager.get_harmonic_frequency_for_pair(
+                            &surface.normalize_long_vowel(),
+                            &candidate.normalize_long_vowel(),
+                        ) {
+                            phrase.lemma_form = surface.clone();
+                            phrase.lemma_reading = candidate;
+                            freq = Some(f);
+                            break;
                         }
                     }
                 }
+            }
 
-                if let Some(frequency) = freq {
-                    let word_frequencies: Vec<(String, f32)> = subrange
-                        .iter()
-                        .map(|term| {
-                            let freq = term
-                                .frequencies
-                                .get("HARMONIC")
-                                .cloned()
-                                .unwrap_or(u32::max_value());
-                            (term.lemma_form.to_string(), freq as f32)
-                        })
-                        .collect();
+            if let Some(frequency) = freq {
+                let word_frequencies: Vec<(String, f32)> = subrange
+                    .iter()
+                    .map(|term| {
+                        let freq =
+                            term.frequencies.get("HARMONIC").cloned().unwrap_or(u32::max_value());
+                        (term.lemma_form.to_string(), freq as f32)
+                    })
+                    .collect();
 
-                    let mult_frequencies: f32 =
-                        word_frequencies.iter().map(|(_, freq)| *freq as f32).product();
+                let mult_frequencies: f32 =
+                    word_frequencies.iter().map(|(_, freq)| *freq as f32).product();
 
-                    let k = subrange.len() as u32;
-                    let score: f32 = (frequency as f32).powf(k as f32) / mult_frequencies;
+                let k = subrange.len() as u32;
+                let score: f32 = (frequency as f32).powf(k as f32) / mult_frequencies;
 
-                    let ratios: Vec<f32> = word_frequencies
-                        .iter()
-                        .map(|(_, freq)| (frequency as f32) / *freq)
-                        .collect();
+                let ratios: Vec<f32> =
+                    word_frequencies.iter().map(|(_, freq)| (frequency as f32) / *freq).collect();
 
-                    let max_ratio: f32 = ratios.iter().fold(0.0, |acc, &x| acc.max(x));
-                    let char_count = phrase.lemma_form.chars().count();
+                let max_ratio: f32 = ratios.iter().fold(0.0, |acc, &x| acc.max(x));
+                let char_count = phrase.lemma_form.chars().count();
 
-                    let all_nouns = subrange.iter().all(|term| {
-                        matches!(
-                            term.part_of_speech,
-                            POS::Noun | POS::CompoundNoun | POS::ProperNoun
-                        )
-                    });
-                    let all_content_words = subrange.iter().all(phrase_endpoint_ok);
-
-                    let score_threshold = 10.0;
-                    let ratio_threshold = 120.0;
-                    // The score/ratio gates guard against junk n-gram entries
-                    // some dictionaries carry; all-kanji noun compounds with
-                    // an exact reading match (複合体) don't need them.
-                    let kanji_noun_compound =
-                        all_nouns && phrase.lemma_form.chars().all(is_kanji_char);
-                    let min_len = if kanji_noun_compound { 3 } else { 4 };
-
-                    let override_ratio_threshold = 40.0;
-                    let phrase_freq_threshold = 10000;
-
-                    if char_count < min_len {
-                        continue;
-                    }
+                let all_nouns = subrange.iter().all(|term| {
+                    matches!(term.part_of_speech, POS::Noun | POS::CompoundNoun | POS::ProperNoun)
+                });
+                let all_content_words = subrange.iter().all(phrase_endpoint_ok);
 
-                    // A component no dictionary knows (負け+じと's じと) means the
-                    // 1-best parse is itself suspect — the corroborated phrase wins.
-                    let has_unvalidated_component =
-                        word_frequencies.iter().any(|(_, freq)| *freq == u32::MAX as f32);
-
-                    if !kanji_noun_compound
-                        && !has_unvalidated_component
-                        && frequency > phrase_freq_threshold
-                        && max_ratio < override_ratio_threshold
-                    {
-                        continue;
-                    }
+                let score_threshold = 10.0;
+                let ratio_threshold = 120.0;
+                // The score/ratio gates guard against junk n-gram entries
+                // some dictionaries carry; all-kanji noun compounds with
+                // an exact reading match (複合体) don't need them.
+                let kanji_noun_compound = all_nouns && phrase.lemma_form.chars().all(is_kanji_char);
+                let min_len = if kanji_noun_compound { 3 } else { 4 };
 
-                    phrase.part_of_speech =
-                        if all_nouns { POS::NounExpression } else { POS::Expression };
-
-                    if kanji_noun_compound
-                        || score <= score_threshold
-                        || max_ratio >= ratio_threshold
-                    {
-                        phrase.sentence_references.push((ord, term_spans[start].0));
-                        let freq_map = frequency_manager.build_freq_map(
-                            &phrase.lemma_form,
-                            &phrase.lemma_reading,
-                            phrase.is_kana,
-                        );
-                        phrase.frequencies = freq_map;
-                        sentence_terms.push(phrase);
+                let override_ratio_threshold = 40.0;
+                let phrase_freq_threshold = 10000;
 
-                        if all_content_words {
-                            // The inner range re-reads the vec length, so `end` can index an already-pushed phrase.
-                            for flag in suppressed[start..=end.min(base_len - 1)].iter_mut() {
-                                *flag = true;
-                            }
-                        }
+                if char_count < min_len {
+                    continue;
+                }
 
-                        // Largest phrase at this start position is accepted; move to next start.
-                        // (Was previously `break 'outer`, which stopped after the first phrase
-                        // in the sentence — preventing detection of e.g. 土曜日 when an earlier
-                        // 実は had already been accepted.)
-                        break;
+                // A component no dictionary knows (負け+じと's じと) means the
+                // 1-best parse is itself suspect — the corroborated phrase wins.
+                let has_unvalidated_component =
+                    word_frequencies.iter().any(|(_, freq)| *freq == u32::MAX as f32);
+
+                if !kanji_noun_compound
+                    && !has_unvalidated_component
+                    && frequency > phrase_freq_threshold
+                    && max_ratio < override_ratio_threshold
+                {
+                    continue;
+                }
+
+                phrase.part_of_speech =
+                    if all_nouns { POS::NounExpression } else { POS::Expression };
+
+                if kanji_noun_compound || score <= score_threshold || max_ratio >= ratio_threshold {
+                    phrase.sentence_references.push((ord, term_spans[start].0));
+                    let freq_map = frequency_manager.build_freq_map(
+                        &phrase.lemma_form,
+                        &phrase.lemma_reading,
+                        phrase.is_kana,
+                    );
+                    phrase.frequencies = freq_map;
+                    sentence_terms.push(phrase);
+
+                    if all_content_words {
+                        // The inner range re-reads the vec length, so `end` can index an already-pushed phrase.
+                        for flag in suppressed[start..=end.min(base_len - 1)].iter_mut() {
+                            *flag = true;
+                        }
                     }
+
+                    // Largest phrase at this start position is accepted; move to next start.
+                    // (Was previously `break 'outer`, which stopped after the first phrase
+                    // in the sentence — preventing detection of e.g. 土曜日 when an earlier
+                    // 実は had already been accepted.)
+                    break;
                 }
             }
         }
+    }
 
-        let mut idx = 0;
-        sentence_terms.retain(|_| {
-            let keep = idx >= base_len || !suppressed[idx];
-            idx += 1;
-            keep
-        });
+    let mut idx = 0;
+    sentence_terms.retain(|_| {
+        let keep = idx >= base_len || !suppressed[idx];
+        idx += 1;
+        keep
+    });
+    sentence_terms
+}
 
-        for term in terms.iter_mut() {
-            if let Some(sentence_term) =
-                sentence_terms.iter().find(|st| st.lemma_form == term.lemma_form)
-            {
+/// Fold per-sentence terms, in sentence order, into the file's term list: the
+/// first term of a lemma in a sentence adds its references to every earlier
+/// term with that lemma, and a (lemma, reading) pair not seen before is
+/// appended. Indexed by lemma so long files don't rescan the list per sentence.
+fn merge_sentence_terms(per_sentence: Vec<Vec<Term>>) -> Vec<Term> {
+    let mut terms = Vec::<Term>::new();
+    let mut by_lemma: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut seen_pairs: HashSet<(String, String)> = HashSet::new();
+
+    for mut sentence_terms in per_sentence {
+        let mut merged_lemmas = HashSet::new();
+        for sentence_term in &sentence_terms {
+            if !merged_lemmas.insert(sentence_term.lemma_form.as_str()) {
+                continue;
+            }
+            for &idx in by_lemma.get(&sentence_term.lemma_form).into_iter().flatten() {
+                let term = &mut terms[idx];
                 for sentence_ref in &sentence_term.sentence_references {
                     if !term.sentence_references.contains(sentence_ref) {
                         term.sentence_references.push(*sentence_ref);
@@ -311,13 +338,15 @@ pub fn extract_words(
         }
 
         sentence_terms.retain(|sentence_term| {
-            !terms.iter().any(|existing_term| {
-                existing_term.lemma_form == sentence_term.lemma_form
-                    && existing_term.lemma_reading == sentence_term.lemma_reading
-            })
+            !seen_pairs
+                .contains(&(sentence_term.lemma_form.clone(), sentence_term.lemma_reading.clone()))
         });
 
-        terms.append(&mut sentence_terms);
+        for term in sentence_terms {
+            seen_pairs.insert((term.lemma_form.clone(), term.lemma_reading.clone()));
+            by_lemma.entry(term.lemma_form.clone()).or_default().push(terms.len());
+            terms.push(term);
+        }
     }
 
     terms
@@ -472,8 +501,6 @@ pub fn batch_deinflect_terms(
     terms: &[Term],
     frequency_manager: &FrequencyManager,
 ) -> HashMap<(String, String), (String, String)> {
-    use std::collections::HashSet;
-
     use rayon::prelude::*;
 
     // Collect unique (surface_form, surface_reading, POS) tuples
@@ -556,6 +583,77 @@ fn retain_verb_final_candidates(candidates: &mut Vec<(String, String)>, surface:
     }
 }
 
+/// The reading of `text[start..end]` from author ruby: hinted spans read as
+/// their ruby, kana as itself. `None` unless a hint lies inside the span and
+/// every other character is kana.
+fn ruby_reading(text: &str, start: usize, end: usize, ruby: &[RubyHint]) -> Option<String> {
+    let mut hints: Vec<&RubyHint> =
+        ruby.iter().filter(|h| h.start >= start && h.end <= end && h.start < h.end).collect();
+    if hints.is_empty() {
+        return None;
+    }
+    hints.sort_by_key(|h| h.start);
+
+    let mut reading = String::new();
+    let mut pos = start;
+    for hint in hints {
+        // Overlapping hints make the range invalid, and `get` rejects it.
+        let gap = text.get(pos..hint.start)?;
+        if !gap.is_empty() && !gap.is_kana() {
+            return None;
+        }
+        reading.push_str(gap);
+        reading.push_str(&hint.reading);
+        pos = hint.end;
+    }
+    let tail = text.get(pos..end)?;
+    if !tail.is_empty() && !tail.is_kana() {
+        return None;
+    }
+    reading.push_str(tail);
+    Some(reading)
+}
+
+/// Prefer the author's ruby (Aozora texts) over UniDic's reading for the
+/// surface, the full segment and — when it shares the hinted stem — the lemma
+/// (思《おも》っ → 思う gets おもう). Runs before deinflection so candidates are
+/// generated from the author's reading.
+fn apply_ruby_hints(
+    term: &mut Term,
+    text: &str,
+    surface_start: usize,
+    span: (usize, usize),
+    ruby: &[RubyHint],
+) {
+    let surface_end = surface_start + term.surface_form.len();
+    if text.get(surface_start..surface_end) != Some(term.surface_form.as_str()) {
+        return;
+    }
+    let Some(surface_reading) = ruby_reading(text, surface_start, surface_end, ruby) else {
+        return;
+    };
+
+    let shared = term
+        .surface_form
+        .char_indices()
+        .zip(term.lemma_form.chars())
+        .take_while(|((_, a), b)| a == b)
+        .last()
+        .map_or(0, |((i, c), _)| i + c.len_utf8());
+    let lemma_tail = &term.lemma_form[shared..];
+    if lemma_tail.is_empty() || lemma_tail.is_kana() {
+        if let Some(stem) = ruby_reading(text, surface_start, surface_start + shared, ruby) {
+            term.lemma_reading =
+                normalize_reading(&term.lemma_form, &format!("{stem}{lemma_tail}"));
+        }
+    }
+
+    term.surface_reading = normalize_reading(&term.surface_form, &surface_reading);
+    if let Some(segment_reading) = ruby_reading(text, span.0, span.1, ruby) {
+        term.full_segment_reading = normalize_reading(&term.full_segment, &segment_reading);
+    }
+}
+
 fn phrase_endpoint_ok(term: &Term) -> bool {
     !matches!(term.part_of_speech, POS::Postposition | POS::Copula | POS::Symbol)
 }
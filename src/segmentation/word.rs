@@ -13,7 +13,7 @@ use super::{
 };
 use crate::core::Term;
 
-#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq, Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Copy, Debug, Default, Hash, Eq, Serialize, Deserialize)]
 pub enum POS {
     Noun,
     ProperNoun,
@@ -39,6 +39,7 @@ pub enum POS {
     Expression,
     NounExpression,
     Other,
+    #[default]
     Unknown,
 }
 
@@ -169,6 +170,25 @@ impl POS {
             Unknown,
         ]
     }
+
+    /// Words a learner would study, for i+N sentence counting. Particles,
+    /// affixes and numbers don't make a sentence harder to read; unlike
+    /// `AnkiMatcher::is_content_word`, names don't either.
+    pub fn is_vocabulary_word(&self) -> bool {
+        matches!(
+            self,
+            POS::Noun
+                | POS::CompoundNoun
+                | POS::NounExpression
+                | POS::Adjective
+                | POS::AdjectivalNoun
+                | POS::Adverb
+                | POS::Verb
+                | POS::SuruVerb
+                | POS::Expression
+                | POS::Onomatopoeia
+        )
+    }
 }
 
 // impl fmt::Display for POS {
@@ -215,6 +235,7 @@ impl From<Word> for Term {
                 sentence_references: Vec::new(),
                 comprehension: 0.0,
                 jlpt_level: None,
+                priority: 0.0,
             }
         } else {
             let is_kana = word.surface_form.as_str().is_kana();
@@ -232,6 +253,7 @@ impl From<Word> for Term {
                 sentence_references: Vec::new(),
                 comprehension: 0.0,
                 jlpt_level: None,
+                priority: 0.0,
             }
         }
     }
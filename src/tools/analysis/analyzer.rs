@@ -29,6 +29,7 @@ use zip::{
 
 use crate::{
     core::{
+        models::SourceFileType,
         utils::NormalizeLongVowel,
         LanguageTools,
         SourceFile,
@@ -53,6 +54,7 @@ pub struct FrequencyAnalysisResult {
     pub total_terms: usize,
     pub unique_terms: usize,
     pub skipped_files: Vec<(String, String)>,
+    pub manga_volumes: usize, // Mokuro volumes among the analyzed files
 }
 
 fn should_exclude_token(lemma_form: &str) -> bool {
@@ -102,6 +104,7 @@ pub fn analyze_files(
     let mut unique_terms: HashMap<(String, String), Term> = HashMap::new();
     let mut total_terms = 0;
     let mut skipped_files: Vec<(String, String)> = Vec::new();
+    let mut manga_volumes = 0;
 
     for (idx, file_path) in file_paths.iter().enumerate() {
         // Check for cancellation
@@ -124,12 +127,17 @@ pub fn analyze_files(
         let source_file = SourceFile {
             id: idx as u32,
             source: None,
-            file_type: crate::core::models::SourceFileType::from_extension(&file_path_str),
+            file_type: crate::mokuro::source_file_type(&file_path_str),
             title: file_name.clone(),
             creator: None,
             original_file: file_path_str,
             epub_chapters: None,
             epub_label: None,
+            encoding: None,
+            text_markup: None,
+            mkv_track: None,
+            secondary_subtitle: None,
+            excluded_styles: None,
         };
 
         let mut sentences = match parser::read(&source_file) {
@@ -148,6 +156,10 @@ pub fn analyze_files(
             continue;
         }
 
+        if matches!(source_file.file_type, SourceFileType::Mokuro) {
+            manga_volumes += 1;
+        }
+
         // Extract terms from this file
         let file_terms = extract_words_for_frequency(
             &language_tools.tokenizer,
@@ -246,6 +258,7 @@ pub fn analyze_files(
         total_terms,
         unique_terms: unique_terms_count,
         skipped_files,
+        manga_volumes,
     })
 }
 
@@ -19,7 +19,7 @@ impl CorpusBalancer {
         let source_files = self.group_files_by_source();
 
         if source_files.len() <= 1 {
-            println!("Balance corpus: Only one source detected, no balancing applied");
+            eprintln!("Balance corpus: Only one source detected, no balancing applied");
             return self.files.clone();
         }
 
@@ -98,16 +98,16 @@ impl CorpusBalancer {
         let trim_each_side = trim_count / 2;
         let trimmed_mean_mb = trimmed_mean_bytes as f64 / BYTES_TO_MB;
 
-        println!("\n=== Corpus Balancing (Trimmed Mean) ===");
-        println!("Total sources: {}", num_sources);
+        eprintln!("\n=== Corpus Balancing (Trimmed Mean) ===");
+        eprintln!("Total sources: {}", num_sources);
         if trim_each_side > 0 {
             let sizes_only: Vec<u64> = source_sizes.iter().map(|(_, size, _)| *size).collect();
             let trimmed_count = sizes_only.len() - trim_count;
-            println!("Trimmed {} sources from each end ({} total)", trim_each_side, trim_count);
-            println!("Calculating mean from {} middle sources", trimmed_count);
+            eprintln!("Trimmed {} sources from each end ({} total)", trim_each_side, trim_count);
+            eprintln!("Calculating mean from {} middle sources", trimmed_count);
         }
-        println!("Target size per source: {:.2} MB (trimmed mean)", trimmed_mean_mb);
-        println!("\nPer-source selection:");
+        eprintln!("Target size per source: {:.2} MB (trimmed mean)", trimmed_mean_mb);
+        eprintln!("\nPer-source selection:");
     }
 
     fn perform_balancing(
@@ -150,7 +150,7 @@ impl CorpusBalancer {
                     selected_count += 1;
                 }
 
-                println!(
+                eprintln!(
                     "  {}: {:.2} MB ({} files) → {:.2} MB ({} files, randomly sampled)",
                     source_name,
                     size_mb,
@@ -165,11 +165,11 @@ impl CorpusBalancer {
     }
 
     fn log_balance_summary(&self, balanced_files: &[PathBuf], original_count: usize) {
-        println!(
+        eprintln!(
             "\nTotal files after balancing: {} (was {})",
             balanced_files.len(),
             original_count
         );
-        println!("========================\n");
+        eprintln!("========================\n");
     }
 }
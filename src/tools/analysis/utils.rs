@@ -10,25 +10,59 @@ pub fn find_supported_files_recursive(dir: &Path) -> Vec<PathBuf> {
     use crate::core::models::SourceFileType;
 
     let mut files = Vec::new();
+    let mut local = Vec::new();
     let supported_extensions = SourceFileType::supported_extensions();
 
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_dir() {
+            if crate::mokuro::is_page_dir(&path) {
+                // A mokuro page directory is one volume, not a tree to recurse.
+                if !crate::mokuro::is_cached_volume(&path) {
+                    files.push(path);
+                }
+            } else if path.is_dir() {
                 files.extend(find_supported_files_recursive(&path));
             } else if path.is_file() {
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
                     if supported_extensions.contains(&ext) {
-                        files.push(path);
+                        local.push(path);
                     }
                 }
             }
         }
     }
 
+    let sidecars: Vec<PathBuf> = local
+        .iter()
+        .filter(|path| {
+            let file_type = SourceFileType::from_extension(&path.to_string_lossy());
+            file_type.is_timed() && !matches!(file_type, SourceFileType::MKV)
+        })
+        .cloned()
+        .collect();
+    local.retain(|path| {
+        !matches!(SourceFileType::from_extension(&path.to_string_lossy()), SourceFileType::MKV)
+            || !has_sidecar_subtitle(path, &sidecars)
+    });
+    files.extend(local);
     files
 }
+
+/// Whether an MKV's subtitles also sit beside it as a file (`ep01.ass` or
+/// `ep01.ja.srt` next to `ep01.mkv`). Reading both would count every line
+/// twice, so the sidecar wins.
+fn has_sidecar_subtitle(mkv: &Path, sidecars: &[PathBuf]) -> bool {
+    let Some(stem) = mkv.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    sidecars.iter().any(|sidecar| {
+        sidecar.file_stem().and_then(|s| s.to_str()).is_some_and(|s| {
+            s == stem || s.strip_prefix(stem).is_some_and(|rest| rest.starts_with('.'))
+        })
+    })
+}
+
 pub fn calculate_progress_fraction(current: usize, total: usize) -> f32 {
     if total > 0 {
         current as f32 / total as f32
@@ -2,9 +2,10 @@ use std::sync::Arc;
 
 use crate::{
     anki::{
-        comprehensibility::comp_term,
+        comprehensibility::comp_vocab,
         AnkiState,
     },
+    core::SuspendedCards,
     dictionary::frequency_manager::FrequencyManager,
     jlpt::{
         JlptDatabase,
@@ -17,7 +18,8 @@ pub struct BandStats {
     /// Fraction (0..1) of the band's words present in Anki, regardless of study state.
     pub coverage: f32,
     /// Average estimated comprehension (0..1) across the band's words, using the same
-    /// per-word `comp_term` estimate as sentence/file comprehension.
+    /// per-word `comp_vocab` estimate (FSRS retrievability, else interval) as
+    /// sentence/file comprehension.
     pub comprehension: f32,
     /// Number of reference words in the band.
     pub total: usize,
@@ -84,10 +86,13 @@ fn band_for_rank(rank: u32) -> Option<usize> {
 pub fn compute_knowledge_summary(
     frequency_manager: Arc<FrequencyManager>,
     known_interval: u32,
+    suspended_cards: SuspendedCards,
 ) -> KnowledgeSummary {
     let mut summary = KnowledgeSummary::default();
 
-    let Some(anki) = AnkiState::from_cache(frequency_manager.clone(), known_interval) else {
+    let Some(anki) =
+        AnkiState::from_cache(frequency_manager.clone(), known_interval, suspended_cards)
+    else {
         return summary; // No Anki vocab cache yet; nothing to report against.
     };
 
@@ -122,13 +127,14 @@ pub fn compute_knowledge_summary(
 
         let mut coverage_sums = vec![0.0_f32; FREQUENCY_BANDS.len()];
         let mut comprehension_sums = vec![0.0_f32; FREQUENCY_BANDS.len()];
-        for vocab in anki.vocab() {
+        let now = chrono::Utc::now().timestamp();
+        for vocab in anki.vocab().iter().filter(|v| !anki.counts_as_unknown(v)) {
             if let Some(rank) =
                 frequency_manager.get_harmonic_frequency_for_pair(&vocab.term, &vocab.reading)
             {
                 if let Some(band) = band_for_rank(rank) {
                     coverage_sums[band] += 1.0;
-                    comprehension_sums[band] += comp_term(vocab.interval, known_interval);
+                    comprehension_sums[band] += comp_vocab(vocab, known_interval, now);
                 }
             }
         }
@@ -1,2 +1,3 @@
 pub mod analysis;
 pub mod knowledge_summary;
+pub mod term_export;
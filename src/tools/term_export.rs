@@ -0,0 +1,322 @@
+//! The mining table as a file: CSV and JSON for spreadsheets and scripts, and a
+//! TSV that Anki's text importer reads as-is (`漢字[かんじ]` furigana, file
+//! headers naming the columns).
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashMap,
+    },
+    path::Path,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use wana_kana::ConvertJapanese;
+
+use crate::core::{
+    models::TimeStamp,
+    utils::is_kanji_char,
+    Sentence,
+    Term,
+    YomineError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TermExportFormat {
+    Csv,
+    Json,
+    AnkiTsv,
+}
+
+impl TermExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            TermExportFormat::Csv => "csv",
+            TermExportFormat::Json => "json",
+            TermExportFormat::AnkiTsv => "tsv",
+        }
+    }
+}
+
+/// One row of the export: a term plus its most comprehensible example sentence.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedTerm {
+    pub term: String,
+    pub reading: String,
+    pub part_of_speech: String,
+    pub jlpt: Option<&'static str>,
+    /// Weighted harmonic rank across the enabled dictionaries.
+    pub frequency: Option<u32>,
+    /// Rank per frequency dictionary, by dictionary name.
+    pub frequencies: BTreeMap<String, u32>,
+    pub occurrences: usize,
+    pub sentence: Option<String>,
+    /// Cue start (`H:MM:SS.mmm`) for timed sources.
+    pub timestamp: Option<String>,
+    /// Page number for manga sources.
+    pub page: Option<usize>,
+    #[serde(skip)]
+    sentence_furigana: Option<String>,
+    /// Byte range of the term inside `sentence`, bolded in the Anki TSV.
+    #[serde(skip)]
+    highlight: Option<(usize, usize)>,
+}
+
+fn timestamp_label(timestamp: &TimeStamp) -> String {
+    let (h, m, s, ms) = timestamp.start.as_hms_milli();
+    format!("{h}:{m:02}:{s:02}.{ms:03}")
+}
+
+/// The referenced sentence the learner is most likely to understand (highest
+/// comprehension, then the shortest), i.e. the best card context.
+fn best_example<'a>(
+    term: &Term,
+    sentences: &HashMap<usize, &'a Sentence>,
+) -> Option<(&'a Sentence, usize)> {
+    term.sentence_references
+        .iter()
+        .filter_map(|(id, start)| sentences.get(id).map(|sentence| (*sentence, *start)))
+        .max_by(|(a, _), (b, _)| {
+            a.comprehension
+                .total_cmp(&b.comprehension)
+                .then_with(|| b.text.chars().count().cmp(&a.text.chars().count()))
+        })
+}
+
+pub fn exported_terms(terms: &[Term], sentences: &[Sentence]) -> Vec<ExportedTerm> {
+    let by_id: HashMap<usize, &Sentence> = sentences.iter().map(|s| (s.id, s)).collect();
+
+    terms
+        .iter()
+        .map(|term| {
+            let example = best_example(term, &by_id);
+            let highlight = example.and_then(|(sentence, start)| {
+                let end = start + term.surface_form.len();
+                (sentence.text.get(start..end) == Some(term.surface_form.as_str()))
+                    .then_some((start, end))
+            });
+            let mut frequencies: BTreeMap<String, u32> =
+                term.frequencies.iter().map(|(name, rank)| (name.clone(), *rank)).collect();
+            let frequency = frequencies.remove("HARMONIC");
+
+            ExportedTerm {
+                term: term.lemma_form.clone(),
+                reading: term.lemma_reading.clone(),
+                part_of_speech: term.part_of_speech.to_string(),
+                jlpt: term.jlpt_level.map(|level| level.label()),
+                frequency,
+                frequencies,
+                occurrences: term.sentence_references.len(),
+                sentence: example.map(|(sentence, _)| sentence.text.clone()),
+                timestamp: example
+                    .and_then(|(sentence, _)| sentence.timestamp.as_ref())
+                    .map(timestamp_label),
+                page: example.and_then(|(sentence, _)| sentence.page).map(|page| page.page),
+                sentence_furigana: example.map(|(sentence, _)| sentence_furigana(sentence)),
+                highlight,
+            }
+        })
+        .collect()
+}
+
+fn fold_katakana(c: char) -> char {
+    match c as u32 {
+        0x30A1..=0x30F6 => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Anki's furigana syntax for one word: kana the surface shares with the
+/// reading at either end (okurigana) stays outside the brackets, and a space
+/// opens the bracketed base. Text without kanji comes back unchanged.
+pub fn anki_furigana(surface: &str, reading: &str) -> String {
+    if !surface.chars().any(is_kanji_char) {
+        return surface.to_string();
+    }
+    let chars: Vec<char> = surface.chars().collect();
+    let kana: Vec<char> = reading.to_hiragana().chars().collect();
+    let same = |c: char, k: char| !is_kanji_char(c) && fold_katakana(c) == k;
+
+    let mut head = 0;
+    while head < chars.len() && head < kana.len() && same(chars[head], kana[head]) {
+        head += 1;
+    }
+    let mut tail = 0;
+    while tail < chars.len() - head
+        && tail < kana.len() - head
+        && same(chars[chars.len() - 1 - tail], kana[kana.len() - 1 - tail])
+    {
+        tail += 1;
+    }
+
+    let base: String = chars[head..chars.len() - tail].iter().collect();
+    let ruby: String = kana[head..kana.len() - tail].iter().collect();
+    if ruby.is_empty() {
+        return surface.to_string(); // Reading doesn't fit the surface.
+    }
+    let before: String = chars[..head].iter().collect();
+    let after: String = chars[chars.len() - tail..].iter().collect();
+    format!("{before} {base}[{ruby}]{after}")
+}
+
+/// A whole sentence in Anki furigana, from its segmentation readings.
+fn sentence_furigana(sentence: &Sentence) -> String {
+    let text = &sentence.text;
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut cursor = 0;
+    for (reading, _, start, end) in &sentence.segments {
+        if *start < cursor || *end > text.len() {
+            continue;
+        }
+        out.push_str(&text[cursor..*start]);
+        out.push_str(&anki_furigana(&text[*start..*end], reading));
+        cursor = *end;
+    }
+    out.push_str(&text[cursor..]);
+    out.trim_start().to_string()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escaped for an Anki field with `#html:true`: no tabs or raw newlines.
+fn anki_field(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\t', " ")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+fn render_csv(rows: &[ExportedTerm]) -> String {
+    let dictionaries: BTreeSet<&str> =
+        rows.iter().flat_map(|row| row.frequencies.keys().map(String::as_str)).collect();
+
+    let mut header = vec![
+        "Term",
+        "Reading",
+        "Part of Speech",
+        "JLPT",
+        "Frequency",
+        "Occurrences",
+        "Sentence",
+        "Timestamp",
+        "Page",
+    ];
+    header.extend(dictionaries.iter());
+    let mut out = header.iter().map(|h| csv_field(h)).collect::<Vec<_>>().join(",");
+    out.push('\n');
+
+    let opt = |value: Option<String>| value.unwrap_or_default();
+    for row in rows {
+        let mut fields = vec![
+            csv_field(&row.term),
+            csv_field(&row.reading),
+            csv_field(&row.part_of_speech),
+            row.jlpt.unwrap_or_default().to_string(),
+            opt(row.frequency.map(|rank| rank.to_string())),
+            row.occurrences.to_string(),
+            csv_field(row.sentence.as_deref().unwrap_or_default()),
+            opt(row.timestamp.clone()),
+            opt(row.page.map(|page| page.to_string())),
+        ];
+        fields.extend(
+            dictionaries.iter().map(|name| opt(row.frequencies.get(*name).map(u32::to_string))),
+        );
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn render_anki_tsv(rows: &[ExportedTerm]) -> String {
+    let mut out = String::from(
+        "#separator:tab\n#html:true\n#tags:yomine\n\
+         #columns:Term\tReading\tTerm Furigana\tSentence\tSentence Furigana\tPart of Speech\tJLPT\tFrequency\tTimestamp\n",
+    );
+    for row in rows {
+        let sentence = match (&row.sentence, row.highlight) {
+            (Some(text), Some((start, end))) => format!(
+                "{}<b>{}</b>{}",
+                anki_field(&text[..start]),
+                anki_field(&text[start..end]),
+                anki_field(&text[end..])
+            ),
+            (Some(text), None) => anki_field(text),
+            (None, _) => String::new(),
+        };
+        let location = row.timestamp.clone().or_else(|| row.page.map(|page| format!("p. {page}")));
+        let fields = [
+            anki_field(&row.term),
+            anki_field(&row.reading),
+            anki_field(anki_furigana(&row.term, &row.reading).trim_start()),
+            sentence,
+            anki_field(row.sentence_furigana.as_deref().unwrap_or_default()),
+            anki_field(&row.part_of_speech),
+            row.jlpt.unwrap_or_default().to_string(),
+            row.frequency.map(|rank| rank.to_string()).unwrap_or_default(),
+            location.unwrap_or_default(),
+        ];
+        out.push_str(&fields.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+/// The terms (in the given order) as the text of an export file.
+pub fn render_terms(
+    terms: &[Term],
+    sentences: &[Sentence],
+    format: TermExportFormat,
+) -> Result<String, YomineError> {
+    let rows = exported_terms(terms, sentences);
+    Ok(match format {
+        TermExportFormat::Csv => render_csv(&rows),
+        TermExportFormat::Json => serde_json::to_string_pretty(&rows)? + "\n",
+        TermExportFormat::AnkiTsv => render_anki_tsv(&rows),
+    })
+}
+
+pub fn export_terms(
+    terms: &[Term],
+    sentences: &[Sentence],
+    format: TermExportFormat,
+    output_path: &Path,
+) -> Result<(), YomineError> {
+    std::fs::write(output_path, render_terms(terms, sentences, format)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn furigana_keeps_okurigana_outside_the_brackets() {
+        assert_eq!(anki_furigana("食べる", "たべる"), " 食[た]べる");
+        assert_eq!(anki_furigana("お茶", "オチャ"), "お 茶[ちゃ]");
+        assert_eq!(anki_furigana("勉強", "べんきょう"), " 勉強[べんきょう]");
+        assert_eq!(anki_furigana("ありがとう", "ありがとう"), "ありがとう");
+        assert_eq!(anki_furigana("食べる", "たべる").trim_start(), "食[た]べる");
+    }
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(csv_field("猫"), "猫");
+        assert_eq!(csv_field("はい、そう"), "はい、そう");
+        assert_eq!(csv_field("yes, sir"), "\"yes, sir\"");
+        assert_eq!(csv_field("a \"b\", c"), "\"a \"\"b\"\", c\"");
+        assert_eq!(anki_field("A<B>\tC\nD"), "A&lt;B&gt; C<br>D");
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+
+use quick_xml::{
+    escape::resolve_xml_entity,
+    events::{
+        BytesStart,
+        Event,
+    },
+    Reader,
+};
+use time::Time;
+
+use crate::core::YomineError;
+
+/// Elements whose content never reaches a cue's text.
+const SKIPPED_TAGS: &[&[u8]] = &[b"head", b"metadata", b"styling", b"layout", b"set"];
+
+/// `tts:ruby` roles that hold the annotation (furigana or its parentheses), not the base text.
+fn is_ruby_annotation(role: &str) -> bool {
+    matches!(role, "text" | "textContainer" | "delimiter")
+}
+
+/// The document's `ttp:*` timing parameters, which give frame (`f`) and tick
+/// (`t`) time expressions their meaning.
+struct TimeBase {
+    frame_rate: f64,
+    sub_frame_rate: f64,
+    tick_rate: f64,
+}
+
+impl Default for TimeBase {
+    fn default() -> Self {
+        Self { frame_rate: 30.0, sub_frame_rate: 1.0, tick_rate: 1.0 }
+    }
+}
+
+impl TimeBase {
+    fn from_root(tt: &BytesStart) -> Self {
+        let mut frame_rate = None;
+        let mut multiplier = 1.0;
+        let mut sub_frame_rate = 1.0;
+        let mut tick_rate = None;
+
+        for (name, value) in attributes(tt) {
+            match name.as_str() {
+                "frameRate" => frame_rate = positive(&value),
+                "subFrameRate" => sub_frame_rate = positive(&value).unwrap_or(1.0),
+                "tickRate" => tick_rate = positive(&value),
+                // "1000 1001" → NTSC's 29.97 from a nominal 30.
+                "frameRateMultiplier" => {
+                    let mut parts = value.split_whitespace().filter_map(positive);
+                    if let (Some(numerator), Some(denominator)) = (parts.next(), parts.next()) {
+                        multiplier = numerator / denominator;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let effective_frame_rate = frame_rate.unwrap_or(30.0) * multiplier;
+        // Per the spec an unspecified tick rate follows the frame rate when one is given, else 1.
+        let tick_rate = tick_rate.unwrap_or(match frame_rate {
+            Some(_) => effective_frame_rate * sub_frame_rate,
+            None => 1.0,
+        });
+
+        Self { frame_rate: effective_frame_rate, sub_frame_rate, tick_rate }
+    }
+
+    /// Clock time (`hh:mm:ss.fff`, `hh:mm:ss:ff.sub`) or offset time
+    /// (`12.5s`, `90f`, `10010000t`, ...) → seconds.
+    fn seconds(&self, expr: &str) -> Option<f64> {
+        let expr = expr.trim();
+
+        let secs = if expr.contains(':') {
+            let parts: Vec<&str> = expr.split(':').collect();
+            let (hours, minutes, seconds, frames) = match parts.as_slice() {
+                [h, m, s] => (*h, *m, *s, None),
+                [h, m, s, f] => (*h, *m, *s, Some(*f)),
+                _ => return None,
+            };
+            let mut secs = hours.parse::<f64>().ok()? * 3600.0
+                + minutes.parse::<f64>().ok()? * 60.0
+                + seconds.parse::<f64>().ok()?;
+            if let Some(frames) = frames {
+                let (frames, sub_frames) = frames.split_once('.').unwrap_or((frames, "0"));
+                let frames = frames.parse::<f64>().ok()?
+                    + sub_frames.parse::<f64>().ok()? / self.sub_frame_rate;
+                secs += frames / self.frame_rate;
+            }
+            secs
+        } else {
+            let unit_start = expr.find(|c: char| c.is_ascii_alphabetic())?;
+            let (value, unit) = expr.split_at(unit_start);
+            let value = value.parse::<f64>().ok()?;
+            match unit {
+                "h" => value * 3600.0,
+                "m" => value * 60.0,
+                "s" => value,
+                "ms" => value / 1000.0,
+                "f" => value / self.frame_rate,
+                "t" => value / self.tick_rate,
+                _ => return None,
+            }
+        };
+
+        (secs.is_finite() && secs >= 0.0).then_some(secs)
+    }
+}
+
+fn positive(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|v| v.is_finite() && *v > 0.0)
+}
+
+/// Unprefixed attribute name → value (`tts:ruby` and `ruby` are the same to us).
+fn attributes(element: &BytesStart) -> Vec<(String, String)> {
+    element
+        .attributes()
+        .flatten()
+        .filter_map(|attr| {
+            let name = String::from_utf8(attr.key.local_name().as_ref().to_vec()).ok()?;
+            let value = attr.unescape_value().ok()?.into_owned();
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// A `<style>` definition, kept only for what decides whether text is ruby annotation.
+struct Style {
+    ruby: Option<String>,
+    parents: Vec<String>,
+}
+
+fn styles_are_ruby_annotation(styles: &HashMap<String, Style>, ids: &str, depth: usize) -> bool {
+    // Style chains are shallow in practice; the cap only guards against cycles.
+    depth < 8
+        && ids.split_whitespace().any(|id| {
+            styles.get(id).is_some_and(|style| match &style.ruby {
+                Some(role) => is_ruby_annotation(role),
+                None => style
+                    .parents
+                    .iter()
+                    .any(|parent| styles_are_ruby_annotation(styles, parent, depth + 1)),
+            })
+        })
+}
+
+/// The active time interval and visibility inherited by an element's content.
+#[derive(Clone, Copy)]
+struct Scope {
+    begin: f64,
+    end: Option<f64>,
+    skip: bool,
+}
+
+fn seconds_to_time(secs: f64) -> Time {
+    // Subtitle timestamps wrap at 24h, like the other formats.
+    let ms = (secs * 1000.0).round() as u64 % 86_400_000;
+    Time::from_hms_milli(
+        (ms / 3_600_000) as u8,
+        (ms / 60_000 % 60) as u8,
+        (ms / 1000 % 60) as u8,
+        (ms % 1000) as u16,
+    )
+    .unwrap_or(Time::MIDNIGHT)
+}
+
+/// Raw `(start, end, text)` cues from a TTML/DFXP document, one per timed `<p>`.
+/// Ruby annotations are dropped so only the base text reaches the tokenizer;
+/// `<br/>` becomes a line break. A `<p>` with no resolvable end is skipped.
+pub fn parse_cues(xml: &str) -> Result<Vec<(Time, Time, String)>, YomineError> {
+    let mut reader = Reader::from_str(xml);
+    let config = reader.config_mut();
+    config.check_end_names = false;
+    config.allow_unmatched_ends = true;
+
+    let mut time_base = TimeBase::default();
+    let mut styles: HashMap<String, Style> = HashMap::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+    // (begin, end, text) of the `<p>` being read.
+    let mut cue: Option<(f64, Option<f64>, String)> = None;
+    let mut cues = Vec::new();
+    let mut seen_root = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| YomineError::Custom(format!("Error Parsing TTML File: {}", e)))?;
+
+        match event {
+            Event::Start(e) => {
+                let name = e.local_name().as_ref().to_vec();
+                if name == b"tt" {
+                    time_base = TimeBase::from_root(&e);
+                    seen_root = true;
+                }
+                if name == b"style" {
+                    record_style(&mut styles, &e);
+                }
+
+                let scope = enter(&e, &name, scopes.last().copied(), &time_base, &styles);
+                if name == b"p" && !scope.skip {
+                    cue = Some((scope.begin, scope.end, String::new()));
+                }
+                scopes.push(scope);
+            }
+            Event::End(e) => {
+                scopes.pop();
+                if e.local_name().as_ref() == b"p" {
+                    if let Some((begin, Some(end), text)) = cue.take() {
+                        if end > begin {
+                            cues.push((seconds_to_time(begin), seconds_to_time(end), text));
+                        }
+                    }
+                }
+            }
+            Event::Empty(e) => {
+                let name = e.local_name();
+                if name.as_ref() == b"style" {
+                    record_style(&mut styles, &e);
+                } else if name.as_ref() == b"br" {
+                    if let (Some((_, _, text)), Some(scope)) = (cue.as_mut(), scopes.last()) {
+                        if !scope.skip {
+                            text.push('\n');
+                        }
+                    }
+                }
+            }
+            Event::Text(e) => {
+                if let (Some((_, _, text)), Some(scope)) = (cue.as_mut(), scopes.last()) {
+                    if !scope.skip {
+                        if let Ok(t) = e.decode() {
+                            text.push_str(&t);
+                        }
+                    }
+                }
+            }
+            Event::GeneralRef(e) => {
+                if let (Some((_, _, text)), Some(scope)) = (cue.as_mut(), scopes.last()) {
+                    if !scope.skip {
+                        if let Ok(Some(c)) = e.resolve_char_ref() {
+                            text.push(c);
+                        } else if let Some(entity) =
+                            e.decode().ok().and_then(|name| resolve_xml_entity(&name))
+                        {
+                            text.push_str(entity);
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_root {
+        return Err(YomineError::Custom(
+            "Error Parsing TTML File: missing <tt> root element".to_string(),
+        ));
+    }
+
+    Ok(cues)
+}
+
+fn record_style(styles: &mut HashMap<String, Style>, element: &BytesStart) {
+    let mut id = None;
+    let mut style = Style { ruby: None, parents: Vec::new() };
+    for (name, value) in attributes(element) {
+        match name.as_str() {
+            "id" => id = Some(value),
+            "ruby" => style.ruby = Some(value),
+            "style" => style.parents = value.split_whitespace().map(String::from).collect(),
+            _ => {}
+        }
+    }
+    if let Some(id) = id {
+        styles.insert(id, style);
+    }
+}
+
+/// The scope an element opens: `begin` is relative to the parent's begin (TTML's
+/// default parallel time containment), `end`/`dur` bound it, and anything
+/// without its own timing inherits the parent's interval.
+fn enter(
+    element: &BytesStart,
+    name: &[u8],
+    parent: Option<Scope>,
+    time_base: &TimeBase,
+    styles: &HashMap<String, Style>,
+) -> Scope {
+    let parent = parent.unwrap_or(Scope { begin: 0.0, end: None, skip: false });
+
+    let mut begin = None;
+    let mut end = None;
+    let mut dur = None;
+    let mut ruby_annotation = false;
+    for (key, value) in attributes(element) {
+        match key.as_str() {
+            "begin" => begin = time_base.seconds(&value),
+            "end" => end = time_base.seconds(&value),
+            "dur" => dur = time_base.seconds(&value),
+            "ruby" => ruby_annotation |= is_ruby_annotation(value.trim()),
+            "style" => ruby_annotation |= styles_are_ruby_annotation(styles, &value, 0),
+            _ => {}
+        }
+    }
+
+    let begin = parent.begin + begin.unwrap_or(0.0);
+    let end = match (end, dur) {
+        (Some(end), _) => Some(parent.begin + end),
+        (None, Some(dur)) => Some(begin + dur),
+        (None, None) => parent.end,
+    };
+
+    Scope { begin, end, skip: parent.skip || ruby_annotation || SKIPPED_TAGS.contains(&name) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(t: Time) -> f64 {
+        let (h, m, s, ms) = t.as_hms_milli();
+        h as f64 * 3600.0 + m as f64 * 60.0 + s as f64 + ms as f64 / 1000.0
+    }
+
+    #[test]
+    fn parses_netflix_style_ruby_and_tick_times() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:tts="http://www.w3.org/ns/ttml#styling"
+    xmlns:ttp="http://www.w3.org/ns/ttml#parameter" ttp:tickRate="10000000">
+  <head>
+    <styling>
+      <style xml:id="s1" tts:color="white"/>
+      <style xml:id="ruby_base" tts:ruby="base"/>
+      <style xml:id="ruby_text" tts:ruby="text" tts:fontSize="50%"/>
+      <style xml:id="ruby_text_inherited" style="ruby_text"/>
+    </styling>
+    <layout><region xml:id="bottom" tts:origin="10% 80%"/></layout>
+  </head>
+  <body style="s1">
+    <div>
+      <p begin="10010000t" end="25000000t" region="bottom"><span tts:ruby="container"><span style="ruby_base">漢字</span><span style="ruby_text">かんじ</span></span>を<br/>読む&amp;書く</p>
+      <p begin="30000000t" end="40000000t"><span style="ruby_base">明日</span><span style="ruby_text_inherited">あした</span></p>
+      <p begin="50000000t">終わりがない</p>
+    </div>
+  </body>
+</tt>"#;
+
+        let cues = parse_cues(xml).unwrap();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].2, "漢字を\n読む&書く");
+        assert!((secs(cues[0].0) - 1.001).abs() < 1e-6);
+        assert!((secs(cues[0].1) - 2.5).abs() < 1e-6);
+        assert_eq!(cues[1].2, "明日");
+    }
+
+    #[test]
+    fn resolves_clock_frame_and_offset_expressions() {
+        let base = TimeBase::default();
+        assert_eq!(base.seconds("00:01:02.500"), Some(62.5));
+        assert_eq!(base.seconds("01:00:00:15"), Some(3600.5));
+        assert_eq!(base.seconds("1.5h"), Some(5400.0));
+        assert_eq!(base.seconds("250ms"), Some(0.25));
+        assert_eq!(base.seconds("bogus"), None);
+
+        let xml = r#"<tt ttp:frameRate="30" ttp:frameRateMultiplier="1000 1001"
+            xmlns:ttp="http://www.w3.org/ns/ttml#parameter"><body>
+            <div begin="10s"><p begin="30f" dur="2s">台詞</p></div></body></tt>"#;
+        let cues = parse_cues(xml).unwrap();
+        assert_eq!(cues.len(), 1);
+        assert!((secs(cues[0].0) - (10.0 + 30.0 / (30.0 * 1000.0 / 1001.0))).abs() < 1e-3);
+        assert!((secs(cues[0].1) - secs(cues[0].0) - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn rejects_non_ttml_documents() {
+        assert!(parse_cues("<html><body><p>x</p></body></html>").is_err());
+    }
+}
@@ -127,7 +127,11 @@ impl RemoteSubtitle {
             text,
             segments: Vec::new(),
             timestamp: Some(TimeStamp { start: ms_to_time(self.start), end: ms_to_time(self.end) }),
+            page: None,
             comprehension: 0.0,
+            ruby: Vec::new(),
+            translation: None,
+            speaker: None,
         })
     }
 }
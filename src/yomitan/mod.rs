@@ -139,18 +139,30 @@ fn parse_markers(template: &str) -> Vec<String> {
 pub struct SentenceContext<'a> {
     pub sentence: &'a str,
     pub term: &'a str,
+    /// The sentence's aligned translation and the note field it goes into.
+    pub translation: Option<Translation<'a>>,
+}
+
+pub struct Translation<'a> {
+    pub field: &'a str,
+    pub text: &'a str,
 }
 
 /// Substitute rendered markers into the field templates. Empty results are
-/// dropped so they never overwrite what Anki/asbplayer would fill.
+/// dropped so they never overwrite what Anki/asbplayer would fill. A translation
+/// replaces whatever its field's template rendered.
 pub fn assemble_fields(
     format: &CardFormat,
     rendered: &HashMap<String, String>,
     sentence_ctx: Option<SentenceContext<'_>>,
 ) -> HashMap<String, String> {
     let mut effective: HashMap<String, String> = rendered.clone();
+    let mut translation = None;
 
     if let Some(ctx) = sentence_ctx {
+        translation = ctx
+            .translation
+            .filter(|t| !t.text.trim().is_empty() && format.fields.contains_key(t.field));
         effective.insert(SENTENCE_MARKER.to_string(), ctx.sentence.to_string());
         // Cloze markers = the sentence split around the mined term.
         if let Some(pos) = ctx.sentence.find(ctx.term) {
@@ -190,6 +202,7 @@ pub fn assemble_fields(
                 Some((name.clone(), value))
             }
         })
+        .chain(translation.map(|t| (t.field.to_string(), t.text.to_string())))
         .collect()
 }
 
@@ -250,12 +263,31 @@ mod tests {
             ("Cloze", "{cloze-prefix}[{cloze-body}]{cloze-suffix}"),
         ]);
         let rendered = HashMap::new();
-        let ctx = SentenceContext { sentence: "毎日パンを食べる。", term: "食べる" };
+        let ctx =
+            SentenceContext {
+                sentence: "毎日パンを食べる。", term: "食べる", translation: None
+            };
         let fields = assemble_fields(&f, &rendered, Some(ctx));
         assert_eq!(fields["Sentence"], "毎日パンを食べる。");
         assert_eq!(fields["Cloze"], "毎日パンを[食べる]。");
     }
 
+    #[test]
+    fn translation_fills_its_mapped_field() {
+        let f = format(&[("Sentence", "{sentence}"), ("SentenceEnglish", "")]);
+        let rendered = HashMap::new();
+        let ctx = |field| SentenceContext {
+            sentence: "パンを食べる。",
+            term: "食べる",
+            translation: Some(Translation { field, text: "I eat bread." }),
+        };
+        let fields = assemble_fields(&f, &rendered, Some(ctx("SentenceEnglish")));
+        assert_eq!(fields["SentenceEnglish"], "I eat bread.");
+        // Not a field of the card's note type — AnkiConnect would reject the note.
+        let fields = assemble_fields(&f, &rendered, Some(ctx("Meaning")));
+        assert_eq!(fields.len(), 1);
+    }
+
     #[test]
     fn unclosed_brace_is_literal() {
         let f = format(&[("Odd", "{expression} {oops")]);
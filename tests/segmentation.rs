@@ -38,7 +38,10 @@ use std::{
 
 use serde::Deserialize;
 use yomine::{
-    core::models::Sentence,
+    core::models::{
+        Sentence,
+        Term,
+    },
     dictionary::{
         frequency_dict::FrequencyDictionary,
         frequency_manager::FrequencyManager,
@@ -49,6 +52,7 @@ use yomine::{
     },
     segmentation::tokenizer::{
         extract_words,
+        extract_words_parallel,
         init_vibrato,
     },
     vibrato::Tokenizer,
@@ -162,7 +166,11 @@ fn run_case(tok: &Tokenizer, manager: &FrequencyManager, case: &Case) -> Vec<Str
         text: case.text.clone(),
         segments: Vec::new(),
         timestamp: None,
+        page: None,
         comprehension: 0.0,
+        ruby: Vec::new(),
+        translation: None,
+        speaker: None,
     }];
     let terms = extract_words(tok.new_worker(), &mut sentences, manager);
 
@@ -326,3 +334,83 @@ fn segmentation_fixtures() {
     assert!(report.is_empty(), "segmentation regressions ({case_count} cases run):{report}");
     println!("segmentation fixtures: {case_count} cases passed");
 }
+
+fn sentence(id: usize, text: &str) -> Sentence {
+    Sentence {
+        id,
+        source_id: 0,
+        text: text.to_string(),
+        segments: Vec::new(),
+        timestamp: None,
+        page: None,
+        comprehension: 0.0,
+        ruby: Vec::new(),
+        translation: None,
+        speaker: None,
+    }
+}
+
+/// Every `*.toml` under `tests/fixtures/segmentation`, sorted.
+fn fixture_paths() -> Vec<PathBuf> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/segmentation");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .expect("tests/fixtures/segmentation missing")
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no fixture files found in {dir:?}");
+    paths
+}
+
+/// Everything about a term the serial/parallel paths must agree on.
+fn term_summary(term: &Term) -> String {
+    let mut frequencies: Vec<_> = term.frequencies.iter().collect();
+    frequencies.sort();
+    format!(
+        "{}|{}|{}|{}|{}|{:?}|{:?}",
+        term.lemma_form,
+        term.lemma_reading,
+        term.surface_form,
+        term.full_segment,
+        term.part_of_speech.as_key(),
+        term.sentence_references,
+        frequencies
+    )
+}
+
+/// `extract_words_parallel` must reproduce the serial path exactly: every
+/// fixture file's cases as one document (twice over, so terms recur across
+/// sentences and threads), compared term by term and segment by segment.
+#[test]
+fn parallel_extraction_matches_serial() {
+    let Some(tok) = tokenizer() else {
+        eprintln!("!!! SKIPPING parallel parity: no UniDic dictionary.");
+        return;
+    };
+
+    for path in &fixture_paths() {
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let fixture: FixtureFile = toml::from_str(&fs::read_to_string(path).expect("read fixture"))
+            .unwrap_or_else(|e| panic!("{file_name}: bad fixture TOML: {e}"));
+        let manager = build_manager(&fixture.frequencies);
+        let texts = fixture.cases.iter().chain(&fixture.cases).map(|case| case.text.as_str());
+        let document: Vec<Sentence> =
+            texts.enumerate().map(|(id, text)| sentence(id, text)).collect();
+
+        let mut serial_sentences = document.clone();
+        let serial = extract_words(tok.new_worker(), &mut serial_sentences, &manager);
+        let mut parallel_sentences = document;
+        let parallel = extract_words_parallel(tok, &mut parallel_sentences, &manager);
+
+        assert_eq!(
+            serial.iter().map(term_summary).collect::<Vec<_>>(),
+            parallel.iter().map(term_summary).collect::<Vec<_>>(),
+            "{file_name}: terms differ"
+        );
+        for (a, b) in serial_sentences.iter().zip(&parallel_sentences) {
+            assert_eq!(a.segments, b.segments, "{file_name}: segments differ for {:?}", a.text);
+        }
+    }
+}